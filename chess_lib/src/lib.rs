@@ -1,6 +1,8 @@
 use std::fmt;
 use std::collections::HashMap;
-use std::hash::Hash;
+#[cfg(feature = "std")]
+use std::time::Instant;
+use std::str::FromStr;
 
 /// Main Game struct for chess board representation. 
 /// Used to create a position, and play moves. Includes
@@ -13,7 +15,7 @@ use std::hash::Hash;
 /// 
 /// # Creation
 /// * Always use one of the two provided constructors, `new_starting_pos()` 
-/// or `from_fen()`. 
+///   or `from_fen()`. 
 /// 
 /// # Examples
 /// 
@@ -24,8 +26,8 @@ use std::hash::Hash;
 /// println!("{:?}", game);
 /// ``` 
 /// * Using from_fen() to create a new game, note that this
-/// is the FEN representation for the standard starting position,
-/// so this will be identical to calling new_starting_pos()
+///   is the FEN representation for the standard starting position,
+///   so this will be identical to calling new_starting_pos()
 /// ```ignore
 /// let game = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
 /// 
@@ -35,12 +37,14 @@ use std::hash::Hash;
 /// # Errors
 /// 
 /// * Deviating from using the provided constructors may cause
-/// a panic!
+///   a panic!
 /// 
 /// # Notes
 /// * For algebraic notation, refer to: https://www.chess.com/terms/chess-notation#readalgebraic
 /// * fmt::Debug is implemented for Game. By using debug print syntax
-/// this will print a visual representation the board to the terminal
+///   this will print a visual representation the board to the terminal
+/// * fmt::Display is also implemented, printing the board with unicode pieces and
+///   coordinates, see `display_from_perspective()`
 #[derive(Clone, PartialEq)]
 pub struct Game {
     //2d array for board representation, each piece is represented by an Option.
@@ -52,6 +56,8 @@ pub struct Game {
     kingside_castle : HashMap<Color, bool>,
     //queenside castling rights for both players
     queenside_castle : HashMap<Color, bool>,
+    //wether or not each player has castled during the game
+    castled : HashMap<Color, bool>,
     //index of possible en passant square
     en_passant_square : Option<(usize, usize)>,
     //number of half moves for current position
@@ -64,8 +70,8 @@ pub struct Game {
     bishop_move_directions : Vec<(i32, i32)>,
     queen_move_directions : Vec<(i32, i32)>,
     knight_move_directions : Vec<(i32, i32)>,
-    //previous game state
-    previous_state : Option<Box<Game>>,
+    //changes made by each move, used to undo moves, most recent last
+    undo_stack : Vec<UndoRecord>,
     //squares under attack by respective player
     white_attacked_squares : Vec<(usize, usize)>,
     black_attacked_squares : Vec<(usize, usize)>,
@@ -74,7 +80,31 @@ pub struct Game {
     //vector of captured pieces
     captures : Vec<Piece>,
     //possible square where pawn be promoted in current position
-    promotion_square : Option<(usize, usize)>
+    promotion_square : Option<(usize, usize)>,
+    //player who has resigned the game, if any
+    resigned : Option<Color>,
+    //rules the game is played with
+    game_mode : GameMode,
+    //pieces each player can drop onto the board, only used in crazyhouse
+    drop_reserve : HashMap<Color, HashMap<PieceType, u32>>,
+    //squares with a piece promoted from a pawn, bit i * 8 + j is set for square (i, j).
+    //in crazyhouse a captured promoted piece goes to the reserve as a pawn
+    promoted_pieces : u64,
+    //number of checks each player has given during the game
+    checks_delivered : HashMap<Color, u32>,
+    //engine annotations for each half move made, indexed from the first move
+    move_annotations : Vec<Option<MoveAnnotation>>,
+    //time control of the game, if any
+    #[cfg(feature = "std")]
+    clock : Option<Clock>,
+    //squares moved between in the most recent move, None for drops
+    last_move : Option<IndexMove>,
+    //PGN tag pairs of the game, in the order they were first set
+    pgn_headers : Vec<(String, String)>,
+    //en passant square and half move clock from before each null move, most recent last
+    null_moves : Vec<(Option<(usize, usize)>, u32)>,
+    //Zobrist hash of the position, updated incrementally as moves are made
+    zobrist_hash : u64,
 }
 
 //implements debug for game, using debug print will
@@ -98,6 +128,24 @@ impl fmt::Debug for Game {
     }
 }
 
+//implements display for game, printing the board from white's perspective
+//with unicode pieces, see Game::display_from_perspective()
+impl fmt::Display for Game {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_from_perspective(Color::White))
+    }
+}
+
+//iterating over a game yields every position in it, see Game::positions()
+impl IntoIterator for &Game {
+    type Item = Game;
+    type IntoIter = std::vec::IntoIter<Game>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.history().into_iter()
+    }
+}
+
 impl Game {
     //creates empty board
     //helper function for from_fen() constructor
@@ -140,6 +188,10 @@ impl Game {
                 (Color::White, true),
                 (Color::Black, true),
             ]),
+            castled : HashMap::from([
+                (Color::White, false),
+                (Color::Black, false),
+            ]),
             en_passant_square : None,
             half_moves : 0,
             full_moves : 0,
@@ -147,12 +199,30 @@ impl Game {
             bishop_move_directions,
             queen_move_directions,
             knight_move_directions,
-            previous_state : None,
+            undo_stack : Vec::new(),
             white_attacked_squares : Vec::new(),
             black_attacked_squares : Vec::new(),
             insufficient_material: unwinnable_states,
             captures : Vec::new(),
             promotion_square : None,
+            resigned : None,
+            game_mode : GameMode::Standard,
+            drop_reserve : HashMap::from([
+                (Color::White, HashMap::new()),
+                (Color::Black, HashMap::new()),
+            ]),
+            promoted_pieces : 0,
+            checks_delivered : HashMap::from([
+                (Color::White, 0),
+                (Color::Black, 0),
+            ]),
+            move_annotations : Vec::new(),
+            #[cfg(feature = "std")]
+            clock : None,
+            last_move : None,
+            pgn_headers : Vec::new(),
+            null_moves : Vec::new(),
+            zobrist_hash : 0,
         }
     }
     /// Create a new board with the standard starting position.
@@ -170,6 +240,69 @@ impl Game {
         Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap()
     }
 
+    /// Create a new game from a Chess960 starting position, using the standard
+    /// numbering of the 960 positions where `518` is the standard starting position.
+    ///
+    /// # Arguments
+    /// * `n` - Position number, from `0` to `959`.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let game = Game::from_chess960_number(0).unwrap();
+    ///
+    /// assert_eq!(game.to_fen(), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w - - 0 1");
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if `n` is larger than `959`.
+    ///
+    /// # Notes
+    /// * Castling is only supported from the standard starting position, so every
+    ///   other position starts without castling rights.
+    pub fn from_chess960_number(n : u32) -> Result<Game, String> {
+        if n > 959 {
+            return Err(format!("Invalid Chess960 position number {}", n));
+        }
+
+        let back_rank : String = chess960_back_rank(n)
+            .iter()
+            .map(|&piece_type| get_piece_notation(Piece::new(piece_type, Color::White)))
+            .collect();
+
+        let castling = if n == 518 {"KQkq"} else {"-"};
+
+        let fen = format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w {} - 0 1",
+            back_rank.to_lowercase(), back_rank, castling
+        );
+
+        //fen is built from valid pieces, so unwrap is safe
+        Ok(Game::from_fen(&fen).unwrap())
+    }
+
+    /// Get the Chess960 position number of the current back ranks, see `from_chess960_number()`.
+    ///
+    /// # Returns
+    /// * `Some(u32)` if white's first rank is one of the 960 starting arrangements
+    ///   and black's last rank mirrors it, `None` otherwise. Only the back ranks are
+    ///   compared, the rest of the board is ignored.
+    pub fn chess960_position_number(&self) -> Option<u32> {
+        let mut back_rank = Vec::new();
+
+        for j in 0..8 {
+            let white_piece = self.board[7][j]?;
+            let black_piece = self.board[0][j]?;
+
+            if white_piece.color != Color::White || black_piece != Piece::new(white_piece.piece_type, Color::Black) {
+                return None;
+            }
+
+            back_rank.push(white_piece.piece_type);
+        }
+
+        (0..960).find(|&n| chess960_back_rank(n)[..] == back_rank[..])
+    }
+
     /// Parses a Forsyth-Edwards Notation (FEN) string and constructs a chess Game representation.
     ///
     /// FEN is a standard notation used to describe the state of a chess game. The FEN string consists
@@ -188,8 +321,8 @@ impl Game {
     ///
     /// # Returns
     ///
-    /// * `Result<Game, String>` - A `Result` where `Ok` contains a `Game` instance representing the
-    ///   parsed chess position, and `Err` contains an error message if parsing fails.
+    /// * `Result<Game, FenParseError>` - A `Result` where `Ok` contains a `Game` instance representing the
+    ///   parsed chess position, and `Err` describes which field is invalid if parsing fails.
     ///
     /// # Example
     ///
@@ -211,61 +344,79 @@ impl Game {
     ///
     /// # Errors
     ///
-    /// This function returns an `Err` variant with an error message if any of the FEN fields contain
-    /// invalid or unexpected values.
+    /// This function returns an `Err` variant containing a `FenParseError` if any of the FEN fields
+    /// are missing or contain invalid or unexpected values.
     ///
     /// # Notes
     ///
     /// - The FEN string should adhere to the standard format for accurate parsing.
     /// - For details on FEN notation, refer to: https://en.wikipedia.org/wiki/Forsyth–Edwards_Notation
-    pub fn from_fen(fen_str : &str) -> Result<Game, String> {
+    pub fn from_fen(fen_str : &str) -> Result<Game, FenParseError> {
         // Splits up FEN string to the seprate fields
         
         let fen_fields = fen_str
             .split_whitespace()
             .collect::<Vec<&str>>();
 
+        if fen_fields.len() < 6 {
+            return Err(FenParseError::new(fen_fields.len(), "six fields separated by spaces", fen_str));
+        }
+
         let mut board = Game::new_empty();
 
-        let mut j = 0;
+        let ranks = fen_fields[0].split("/").collect::<Vec<&str>>();
+
+        if ranks.len() != 8 {
+            return Err(FenParseError::new(0, "eight ranks separated by '/'", fen_fields[0]));
+        }
 
         // Map piece placement string to Board
-        for (i, row) in fen_fields[0].split("/").enumerate() {
+        for (i, row) in ranks.into_iter().enumerate() {
+            let mut j = 0;
+
             for chr in row.chars() {
                 match chr.to_digit(10) {
-                    Some(number) => {
-                        j  += number - 1;
+                    Some(number @ 1..=8) => {
+                        j += number as usize;
                     },
-                    None => {
-                        board.board[i][j as usize] = Some(get_piece(chr)?);
+                    _ => {
+                        let piece = get_piece(chr)
+                            .map_err(|_| FenParseError::new(0, "a piece letter or a digit from 1 to 8", &chr.to_string()))?;
+
+                        if j < 8 {
+                            board.board[i][j] = Some(piece);
+                        }
+                        j += 1;
                     },
                 }
-                j += 1;
             }
-            j = 0;
+
+            if j != 8 {
+                return Err(FenParseError::new(0, "eight squares in every rank", row));
+            }
         }
 
         // Map active turn string to Board
         board.turn = match fen_fields[1] {
             "w" => Color::White,
             "b" => Color::Black,
-            _c => return Err(format!("Invalid active field {}", _c)),
+            _c => return Err(FenParseError::new(1, "'w' or 'b'", _c)),
         };
 
-        // Map castling rights string to Board
+        // Map castling rights string to Board, rights not listed are lost
+        board.kingside_castle.insert(Color::White, false);
+        board.queenside_castle.insert(Color::White, false);
+        board.kingside_castle.insert(Color::Black, false);
+        board.queenside_castle.insert(Color::Black, false);
+
         for c in fen_fields[2].chars() {
             match c {
                 'K' => {board.kingside_castle.insert(Color::White, true); },
                 'Q' => {board.queenside_castle.insert(Color::White, true); },
                 'k' => {board.kingside_castle.insert(Color::Black, true); },
                 'q' => {board.queenside_castle.insert(Color::Black, true); },
-                '-' => {
-                    board.kingside_castle.insert(Color::White, false);
-                    board.queenside_castle.insert(Color::White, false); 
-                    board.kingside_castle.insert(Color::Black, false);
-                    board.queenside_castle.insert(Color::Black, false);
-                },
-                _c => return Err(format!("Invalid castling field {}", _c)),
+                '-' => (),
+                _ => return Err(FenParseError::new(2, "'-' or any of 'KQkq'", fen_fields[2])),
             } 
         }
 
@@ -273,9 +424,9 @@ impl Game {
         match fen_fields[3] {
             "-" => {},
             _ => {
-                    board.en_passant_square = match alg_notation_to_indx(fen_fields[3]) {
+                board.en_passant_square = match alg_notation_to_indx(fen_fields[3]) {
                     Ok(indx) => Some(indx),
-                    Err(e) => return Err(e.to_string()),
+                    Err(_) => return Err(FenParseError::new(3, "'-' or a square e.g. 'e3'", fen_fields[3])),
                 };
             }
         }
@@ -283,19 +434,312 @@ impl Game {
         // Parse number of half-moves to Board
         board.half_moves = match fen_fields[4].parse::<u32>() {
             Ok(num) => num,
-            Err(e) => return Err(e.to_string()),
+            Err(_) => return Err(FenParseError::new(4, "a non-negative number", fen_fields[4])),
         };
 
         // Parse number of full-moves to Board
         board.full_moves = match fen_fields[5].parse::<u32>() {
             Ok(num) => num,
-            Err(e) => return Err(e.to_string()),
+            Err(_) => return Err(FenParseError::new(5, "a non-negative number", fen_fields[5])),
         };
 
         board.update_attacked_squares();
+        board.zobrist_hash = board.compute_zobrist_hash();
         // board.update_state();
 
-        return Result::Ok(board);
+        Result::Ok(board)
+    }
+
+    /// Create a new game from only the piece placement field of a FEN string, e.g.
+    /// `"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR"`. White is to move, there is no
+    /// en passant square and the move counters are `0` and `1`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::from_board_fen("4k3/8/8/8/8/8/8/R3K3").unwrap();
+    ///
+    /// assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1");
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(FenParseError)` if the piece placement is invalid, or if more
+    ///   than one FEN field is given.
+    ///
+    /// # Notes
+    /// * Every castling right is given where the king and rook are on their starting
+    ///   squares, other castling rights would not be valid for the position.
+    pub fn from_board_fen(board_fen : &str) -> Result<Game, FenParseError> {
+        let board_fen = board_fen.trim();
+
+        if board_fen.contains(char::is_whitespace) {
+            return Err(FenParseError::new(0, "only the piece placement field", board_fen));
+        }
+
+        let mut game = Game::from_fen(&format!("{} w KQkq - 0 1", board_fen))?;
+
+        game.remove_unusable_castling_rights();
+        game.zobrist_hash = game.compute_zobrist_hash();
+
+        Ok(game)
+    }
+
+    /// Create a new game from the standard starting position, and play a list of
+    /// moves in Standard Algebraic Notation (SAN). The moves are kept in the move
+    /// history, so they can be undone using `undo_last_move()`.
+    ///
+    /// # Arguments
+    /// * `sans` - Slice of moves in SAN, e.g. `["e4", "e5", "Nf3"]`. Promotions
+    ///   without a promotion piece, e.g. `"e8"`, promote to a queen.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::from_san_list(&["e4", "e5", "Nf3", "Nc6"]).unwrap();
+    ///
+    /// assert_eq!(game.to_fen(), "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3");
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` containing the index of the move, the move and the reason
+    ///   if any move is invalid or illegal.
+    pub fn from_san_list(sans : &[&str]) -> Result<Game, String> {
+        let mut game = Game::new_starting_pos();
+
+        for (move_index, san) in sans.iter().enumerate() {
+            game.make_move_san(san, true)
+                .map_err(|reason| format!("Invalid SAN at move {} '{}': {}", move_index, san, reason))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Create a new game from a game written in Portable Game Notation (PGN). The moves
+    /// are played from the standard starting position, or from the `FEN` tag if there
+    /// is one, and are kept in the move history. Every tag pair is stored as a header,
+    /// see `get_pgn_header()`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let pgn = "[Event \"Casual game\"]\n[Result \"*\"]\n\n1. e4 {best by test} e5 (1... c5) 2. Nf3 *";
+    /// let game = Game::from_pgn(pgn).unwrap();
+    ///
+    /// assert_eq!(game.get_pgn_header("Event"), Some("Casual game"));
+    /// assert_eq!(game.pgn_move_text(), Ok(String::from("1. e4 e5 2. Nf3 *")));
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if the `FEN` tag or a move is invalid, or if a comment or
+    ///   variation is not closed.
+    ///
+    /// # Notes
+    /// * Comments, variations and numeric annotations are skipped, see `pgn_to_fen_list()`.
+    pub fn from_pgn(pgn : &str) -> Result<Game, String> {
+        let mut game = match pgn_tag_value(pgn, "FEN") {
+            Some(fen) => Game::from_fen(fen)?,
+            None => Game::new_starting_pos(),
+        };
+
+        for (name, value) in pgn_tag_pairs(pgn) {
+            game.set_pgn_header(name, value);
+        }
+
+        for (move_index, san) in pgn_move_tokens(pgn)?.iter().enumerate() {
+            game.make_move_san(san, true)
+                .map_err(|reason| format!("Invalid SAN at move {} '{}': {}", move_index, san, reason))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Reset the game to the position described by a FEN string, in place.
+    /// Move history, captures and the crazyhouse reserves are cleared, so
+    /// `undo_last_move()` can not go back past this position. The game mode,
+    /// clock and PGN headers are kept.
+    ///
+    /// # Arguments
+    /// * `fen` - A string containing the FEN representation of the position.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// game.make_move("e2", "e4", true).unwrap();
+    ///
+    /// game.revert_to_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if the FEN string is invalid, see `from_fen()`.
+    ///   The game is left unchanged in this case.
+    pub fn revert_to_fen(&mut self, fen : &str) -> Result<(), String> {
+        let game = Game::from_fen(fen)?;
+        self.replace_position(game);
+
+        Ok(())
+    }
+
+    /// Reset the game to the standard starting position, in place.
+    /// See `revert_to_fen()`.
+    pub fn revert_to_start(&mut self) {
+        self.replace_position(Game::new_starting_pos());
+    }
+
+    // replaces self with game, keeping the game mode, clock and PGN headers of self
+    fn replace_position(&mut self, mut game : Game) {
+        game.game_mode = self.game_mode;
+        game.pgn_headers = std::mem::take(&mut self.pgn_headers);
+        #[cfg(feature = "std")]
+        {
+            game.clock = self.clock.take();
+        }
+
+        *self = game;
+    }
+
+    /// Place `piece` on `square`, replacing any piece already on it.
+    ///
+    /// # Arguments
+    /// * `square` is an array index, for more detail refer to `Game` struct.
+    ///
+    /// # Errors
+    /// * Returns `Err` if `square` is an invalid index.
+    ///
+    /// # Notes
+    /// * Editing the board clears the move history, so `undo_last_move()` will
+    ///   have no effect until another move is made.
+    /// * The en passant square is removed, and so are the castling rights of any
+    ///   king or rook no longer on its starting square.
+    pub fn place_piece(&mut self, square : (usize, usize), piece : Piece) -> Result<(), String> {
+        if !is_valid_index(square) {
+            return Err(format!("Invalid index {:?}", square));
+        }
+
+        self.board[square.0][square.1] = Some(piece);
+        self.promoted_pieces &= !square_bit(square);
+        self.after_board_edit();
+
+        Ok(())
+    }
+
+    /// Remove the piece on `square`.
+    ///
+    /// # Returns
+    /// * `Some(Piece)` being the removed piece, or `None` if the square was empty
+    ///   or `square` is an invalid index.
+    ///
+    /// # Notes
+    /// * Clears the move history, see `place_piece()`.
+    pub fn remove_piece(&mut self, square : (usize, usize)) -> Option<Piece> {
+        if !is_valid_index(square) {
+            return None;
+        }
+
+        let piece = self.board[square.0][square.1].take();
+        self.promoted_pieces &= !square_bit(square);
+        self.after_board_edit();
+
+        piece
+    }
+
+    /// Remove every piece from the board.
+    ///
+    /// # Notes
+    /// * Clears the move history, see `place_piece()`.
+    pub fn clear_board(&mut self) {
+        self.board = [[None ; 8] ; 8];
+        self.promoted_pieces = 0;
+        self.after_board_edit();
+    }
+
+    /// Swap the active player without making a move, e.g. to analyse a
+    /// position with the other side to move.
+    ///
+    /// # Notes
+    /// * Clears the move history, see `place_piece()`. Any en passant square
+    ///   is removed, since it is only valid directly after the double pawn push.
+    pub fn flip_turn(&mut self) {
+        self.turn = self.turn.opposite();
+        self.en_passant_square = None;
+        self.after_board_edit();
+    }
+
+    /// Create a copy of the game with the other player to move. The board and
+    /// castling rights are unchanged, so the FEN only differs in the active color,
+    /// and the en passant field if an en passant square was set.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::new_starting_pos().swap_sides();
+    ///
+    /// assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1");
+    /// ```
+    ///
+    /// # Notes
+    /// * The copy has no move history, see `flip_turn()`.
+    /// * If the player to move in the original game is in check, the copy is not
+    ///   a legal chess position.
+    pub fn swap_sides(&self) -> Game {
+        let mut game = self.clone();
+        game.flip_turn();
+
+        game
+    }
+
+    /// Create a copy of the game for analysis from the current position. The copy
+    /// has the same position, including castling rights, en passant square and
+    /// move counters, but no move history, captures, move annotations or PGN headers.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::from_san_list(&["e4", "d5", "exd5"]).unwrap();
+    /// let analysis = game.snapshot_for_analysis();
+    ///
+    /// assert_eq!(analysis.to_fen(), game.to_fen());
+    /// assert!(analysis.get_captures(Color::White).is_empty());
+    /// ```
+    pub fn snapshot_for_analysis(&self) -> Game {
+        self.position_copy()
+    }
+
+    // copies the current position without cloning the move history, captures, annotations
+    // or PGN headers, so the cost does not grow with the length of the game
+    fn position_copy(&self) -> Game {
+        Game {
+            board : self.board,
+            turn : self.turn,
+            kingside_castle : self.kingside_castle.clone(),
+            queenside_castle : self.queenside_castle.clone(),
+            castled : self.castled.clone(),
+            en_passant_square : self.en_passant_square,
+            half_moves : self.half_moves,
+            full_moves : self.full_moves,
+            rook_move_directions : self.rook_move_directions.clone(),
+            bishop_move_directions : self.bishop_move_directions.clone(),
+            queen_move_directions : self.queen_move_directions.clone(),
+            knight_move_directions : self.knight_move_directions.clone(),
+            undo_stack : Vec::new(),
+            white_attacked_squares : self.white_attacked_squares.clone(),
+            black_attacked_squares : self.black_attacked_squares.clone(),
+            insufficient_material : self.insufficient_material.clone(),
+            captures : Vec::new(),
+            promotion_square : self.promotion_square,
+            resigned : self.resigned,
+            game_mode : self.game_mode,
+            drop_reserve : self.drop_reserve.clone(),
+            promoted_pieces : self.promoted_pieces,
+            checks_delivered : self.checks_delivered.clone(),
+            move_annotations : Vec::new(),
+            #[cfg(feature = "std")]
+            clock : self.clock.clone(),
+            last_move : None,
+            pgn_headers : Vec::new(),
+            null_moves : Vec::new(),
+            zobrist_hash : self.zobrist_hash,
+        }
     }
 
     /// Generates a Forsyth-Edwards Notation (FEN) string from the current state of the chess game.
@@ -416,19 +860,49 @@ impl Game {
         //field 6 - fullmoves
         fen_str.push_str(&self.full_moves.to_string());
 
-        return fen_str;
+        fen_str
+    }
+
+    /// Same as `to_fen()`, but only the first four fields: piece placement, active color,
+    /// castling rights and en passant target. The move counters are left out since they
+    /// do not affect which moves are legal, which makes this useful for detecting repetitions.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::new_starting_pos();
+    ///
+    /// assert_eq!(game.to_fen_core(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -");
+    /// ```
+    pub fn to_fen_core(&self) -> String {
+        fen_without_counters(&self.to_fen()).to_string()
+    }
+
+    /// Same as `to_fen()`, but only the first field, the piece placement,
+    /// e.g. `"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR"`. See `from_board_fen()`.
+    pub fn board_fen(&self) -> String {
+        let fen = self.to_fen();
+
+        //the piece placement never contains spaces, so unwrap is safe
+        fen.split(' ').next().unwrap().to_string()
+    }
+
+    /// Returns bool representing wether `other` has the same position as the game,
+    /// comparing FEN without the move counters, see `to_fen_core()`.
+    pub fn same_core_position(&self, other : &Game) -> bool {
+        self.to_fen_core() == other.to_fen_core()
     }
 
     /// Get piece at given indexed position.
     /// 
     /// # Arguments
     /// * `indx` is a tuple of type `(usize, usize)`, where the first
-    /// element indexes the rank and second element the file.
-    /// Note that array indicies start at 0 in contrast to algebraic notation.
+    ///   element indexes the rank and second element the file.
+    ///   Note that array indicies start at 0 in contrast to algebraic notation.
     /// 
     /// # Returns
     /// * `Result<Option<Piece>, String>`, `Option<Piece>` being the corresponding
-    /// value on the board (See `Game` struct for board representation).
+    ///   value on the board (See `Game` struct for board representation).
     /// 
     /// # Examples
     /// 
@@ -443,13 +917,14 @@ impl Game {
     /// 
     /// # Errors
     /// * Returns `Err(String)` if input is invalid index
+    #[deprecated(note = "use `piece_at_square()` instead")]
     pub fn piece_at_array_index(&self, indx : (usize, usize)) -> Result<Option<Piece>, String> {
         let (i, j) = indx;
 
-        if !is_valid_pos(i as i32, j as i32) {
-            return Err(format!("Invalid index {:?}", indx));
+        if !is_valid_index((i, j)) {
+            Err(format!("Invalid index {:?}", indx))
         } else {
-            return Ok(self.board[i][j]);
+            Ok(self.board[i][j])
         }
     }
     /// Get piece at given position using algebraic notation.
@@ -459,7 +934,7 @@ impl Game {
     /// 
     /// # Returns
     /// * `Result<Option<Piece>, String>`, `Option<Piece>` being the corresponding
-    /// value on the board (See `Game` struct for board representation).
+    ///   value on the board (See `Game` struct for board representation).
     /// 
     /// # Examples
     /// 
@@ -477,29 +952,103 @@ impl Game {
     pub fn piece_at_alg_notation(&self, notation : &str) -> Result<Option<Piece>, String> {
         let (i, j) = alg_notation_to_indx(notation)?;
 
-        if !is_valid_pos(i as i32, j as i32) {
-            return Err(format!("Invalid notation {:?}", notation));
+        if !is_valid_index((i, j)) {
+            Err(format!("Invalid notation {:?}", notation))
         } else {
-            return Ok(self.board[i][j]);
+            Ok(self.board[i][j])
+        }
+    }
+
+    /// Same as `piece_at_alg_notation()`, but returns `None` both for empty squares
+    /// and invalid notation, for callers that know the notation is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::new_starting_pos();
+    ///
+    /// assert_eq!(game.piece_at("e2"), Some(Piece::new(PieceType::Pawn, Color::White)));
+    /// assert_eq!(game.piece_at("e4"), None);
+    /// ```
+    pub fn piece_at(&self, notation : &str) -> Option<Piece> {
+        self.piece_at_alg_notation(notation).ok().flatten()
+    }
+
+    /// Same as `piece_at_square()`, but returns `None` both for empty squares
+    /// and invalid indicies. `i` and `j` index the rank and file respectively.
+    pub fn piece_at_index(&self, i : usize, j : usize) -> Option<Piece> {
+        Square::new(i, j).ok().and_then(|square| self.piece_at_square(square))
+    }
+
+    /// Get the piece on `square`, or `None` if the square is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::new_starting_pos();
+    /// let square = Square::from_alg("a8").unwrap();
+    ///
+    /// assert_eq!(game.piece_at_square(square), Some(Piece::new(PieceType::Rook, Color::Black)));
+    /// ```
+    pub fn piece_at_square(&self, square : Square) -> Option<Piece> {
+        self.board[square.rank()][square.file()]
+    }
+
+    /// Get a copy of the board, indexed by rank and file, starting with rank 8.
+    /// See `Game` struct for board representation.
+    pub fn board_rows(&self) -> [[Option<Piece>; 8]; 8] {
+        self.board
+    }
+
+    /// Get the pieces on a rank, ordered from the a-file to the h-file.
+    ///
+    /// # Arguments
+    /// * `rank` - Rank in algebraic notation, from `1` to `8`.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if `rank` is not between `1` and `8`.
+    pub fn board_row(&self, rank : u32) -> Result<[Option<Piece>; 8], String> {
+        if !(1..=8).contains(&rank) {
+            return Err(format!("Invalid rank {}", rank));
+        }
+
+        Ok(self.board[8 - rank as usize])
+    }
+
+    /// Get the pieces on a file, ordered from rank 8 to rank 1.
+    ///
+    /// # Arguments
+    /// * `file` - File in algebraic notation, from `'a'` to `'h'`.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if `file` is not between `'a'` and `'h'`.
+    pub fn board_column(&self, file : char) -> Result<[Option<Piece>; 8], String> {
+        if !('a'..='h').contains(&file) {
+            return Err(format!("Invalid file {:?}", file));
         }
+
+        let j = file as usize - 'a' as usize;
+
+        Ok(self.board.map(|row| row[j]))
     }
+
     /// Make a move on the board using algebraic notation.
     ///  
     /// 
     /// # Arguments
     /// * `from` and `to` are both in algebraic notation.
     /// * `from` refers to the square the piece you want to move is currently on, 
-    /// `to` is the square you want to move it to.
+    ///   `to` is the square you want to move it to.
     /// * `auto_promote` is a `bool` indicating wether or not a pawn, once it has 
-    /// reached the end rank, should be promoted automatically. If `true`, it will
-    /// be promoted to a queen automatically, if `false` it will remain a pawn and
-    /// the game state will be `GameState::AwaitPromotion`. The user is then excpected
-    /// to handle promotion explicitly using the `promote_to_piece` method.
+    ///   reached the end rank, should be promoted automatically. If `true`, it will
+    ///   be promoted to a queen automatically, if `false` it will remain a pawn and
+    ///   the game state will be `GameState::AwaitPromotion`. The user is then excpected
+    ///   to handle promotion explicitly using the `promote_to_piece` method.
     ///
     /// # Returns
     /// * `Result<bool, String>` - A `Result` where `Ok` contains a `bool` representing
-    /// wether or not the move is valid, and `Err` contains an error message if the move fails, or
-    /// if one or both of the provided indicies is invalid.
+    ///   wether or not the move is valid, and `Err` contains an error message if the move fails, or
+    ///   if one or both of the provided indicies is invalid.
     /// 
     /// # Examples
     /// 
@@ -531,17 +1080,17 @@ impl Game {
     /// # Arguments
     /// * `from` and `to` are both in algebraic notation.
     /// * `from` refers to the square the piece you want to move is currently on, 
-    /// `to` is the square you want to move it to.
+    ///   `to` is the square you want to move it to.
     /// * `auto_promote` is a `bool` indicating wether or not a pawn, once it has 
-    /// reached the end rank, should be promoted automatically. If `true`, it will
-    /// be promoted to a queen automatically, if `false` it will remain a pawn and
-    /// the game state will be `GameState::AwaitPromotion`. The user is then excpected
-    /// to handle promotion explicitly using the `promote_to_piece` method.
+    ///   reached the end rank, should be promoted automatically. If `true`, it will
+    ///   be promoted to a queen automatically, if `false` it will remain a pawn and
+    ///   the game state will be `GameState::AwaitPromotion`. The user is then excpected
+    ///   to handle promotion explicitly using the `promote_to_piece` method.
     ///
     /// # Returns
     /// * `Result<bool, String>` - A `Result` where `Ok` contains a `bool` representing
-    /// wether or not the move is valid, and `Err` contains an error message if the move fails, or
-    /// if one or both of the provided indicies is invalid.
+    ///   wether or not the move is valid, and `Err` contains an error message if the move fails, or
+    ///   if one or both of the provided indicies is invalid.
     /// 
     /// # Examples
     /// 
@@ -561,6 +1110,7 @@ impl Game {
     /// 
     /// # Notes
     /// * board array indicies start at 0 as opposed to algebraic notation
+    #[deprecated(note = "use `make_move_square()` instead")]
     pub fn make_move_array_index(&mut self, from : (usize, usize), to : (usize, usize), auto_promote : bool) -> Result<bool, String> {
         if is_valid_move(from, to){
             return self.make_move_with_index(from, to, true, auto_promote);
@@ -569,27 +1119,186 @@ impl Game {
         Ok(false)
     }
 
-    /// Used to promote a pawn at the final rank. This method is
-    /// used to promote when using `make_move(auto_promote=false)`. Note
-    /// that this method must be called _after_ calling `make_move`.
-    /// 
+    /// Make a move from one `Square` to another, same as `make_move()`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// let e2 = Square::from_alg("e2").unwrap();
+    /// let e4 = Square::from_alg("e4").unwrap();
+    ///
+    /// assert_eq!(game.make_move_square(e2, e4, true), Ok(true));
+    /// ```
+    ///
+    /// # Errors
+    /// * Same as `make_move()`, although squares are always valid.
+    pub fn make_move_square(&mut self, from : Square, to : Square, auto_promote : bool) -> Result<bool, String> {
+        self.make_move_with_index(from.into(), to.into(), true, auto_promote)
+    }
+
+    /// Make a move on the board using Standard Algebraic Notation (SAN), e.g. `"e4"`,
+    /// `"Nf3"`, `"O-O"`, `"exd5"`, `"Rhe1"` or `"e8=Q"`. See `san_to_move()`.
+    ///
     /// # Arguments
-    /// * `piece_type` is of type `PieceType` and represents the type
-    /// of piece the pawn will be promoted to.
-    /// 
+    /// * `san` is the move in SAN, check and checkmate suffixes (`+`, `#`) are accepted
+    ///   but not required.
+    /// * `auto_promote` is only used for promotion moves written without a promotion
+    ///   piece, e.g. `"e8"`. If `true` the pawn is promoted to a queen, if `false` the game
+    ///   state will be `GameState::AwaitPromotion`, see `make_move()`.
+    ///
     /// # Returns
-    /// * `bool` representing wether or not promotion was successful
-    /// 
+    /// * `Result<bool, String>`, `Ok(true)` if the move was made.
+    ///
     /// # Examples
-    /// * How a game loop might look
+    ///
     /// ```ignore
     /// let mut game = Game::new_starting_pos();
-    /// 
-    /// loop {
-    ///     //Get user input...
-    ///     
-    ///     //`from` and `to` are placeholders
-    ///     game.make_move(from, to, false); //auto_promote set to false
+    ///
+    /// assert_eq!(game.make_move_san("Nf3", true), Ok(true));
+    /// assert!(game.make_move_san("Nf3", true).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if the SAN is invalid, illegal or ambiguous.
+    pub fn make_move_san(&mut self, san : &str, auto_promote : bool) -> Result<bool, String> {
+        let (from, to) = self.san_to_move(san)?;
+
+        //san_to_move() only returns legal moves
+        if !self.make_move_with_index(from, to, true, false)? {
+            return Ok(false);
+        }
+
+        if self.promotion_square.is_some() && (san.contains('=') || auto_promote) {
+            self.promote_to_piece(san_promotion_piece(san));
+        }
+
+        Ok(true)
+    }
+
+    /// Make a move on the board using array indicies, without checking if the move is legal.
+    /// Intended for search code that only plays moves generated by `get_all_legal_moves()`
+    /// or `get_legal_moves_square()`, where checking legality again is redundant.
+    ///
+    /// # Arguments
+    /// * `from` and `to` are array indicies, for more detail refer to `Game` struct.
+    /// * `promotion` is the piece a pawn is promoted to if the move is a promotion move.
+    ///   If `None`, the game state will be `GameState::AwaitPromotion`, see `promote_to_piece()`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// for (from, moves) in game.get_all_legal_moves(Color::White) {
+    ///     for to in moves {
+    ///         game.make_move_unchecked(from, to, Some(PieceType::Queen));
+    ///         //search...
+    ///         game.undo_last_move();
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// * Panics if `from` or `to` is an invalid index, or if there is no piece at `from`.
+    ///
+    /// # Notes
+    /// * Playing an illegal move leaves the game in an invalid state, e.g. the active
+    ///   player's king may be left in check or castling rules may be broken.
+    ///   Any result from the game after that is unspecified.
+    pub fn make_move_unchecked(&mut self, from : (usize, usize), to : (usize, usize), promotion : Option<PieceType>) {
+        //no error is returned when check_legal is false
+        self.make_move_with_index(from, to, false, false).unwrap();
+
+        if let Some(piece_type) = promotion {
+            self.promote_to_piece(piece_type);
+        }
+    }
+
+    /// Pass the turn to the opponent without moving a piece, for null move pruning
+    /// in search. Any en passant square is removed and the move counters are updated
+    /// as for a normal move. Undo with `unmake_null_move()`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// game.make_null_move();
+    /// assert_eq!(game.get_active_player(), Color::Black);
+    ///
+    /// game.unmake_null_move();
+    /// assert_eq!(game.get_active_player(), Color::White);
+    /// ```
+    ///
+    /// # Notes
+    /// * Faster than making a move, since no undo record is saved and attacked squares
+    ///   are not updated.
+    ///   Null moves are not part of the history, so `undo_last_move()` and `fen_history()`
+    ///   do not see them.
+    /// * Passing while in check is not legal chess, the caller is expected to check
+    ///   `in_check()` first.
+    pub fn make_null_move(&mut self) {
+        self.null_moves.push((self.en_passant_square, self.half_moves));
+        self.zobrist_hash ^= self.zobrist_state_key();
+
+        self.en_passant_square = None;
+        self.half_moves += 1;
+
+        if self.turn == Color::Black {
+            self.full_moves += 1;
+        }
+
+        self.turn = self.turn.opposite();
+        self.zobrist_hash ^= self.zobrist_state_key();
+    }
+
+    /// Undo the most recent null move, restoring the en passant square and move
+    /// counters. Does nothing if there is no null move to undo. See `make_null_move()`.
+    ///
+    /// # Notes
+    /// * Moves made after the null move must be undone first.
+    pub fn unmake_null_move(&mut self) {
+        let (en_passant_square, half_moves) = match self.null_moves.pop() {
+            Some(state) => state,
+            None => return,
+        };
+
+        self.zobrist_hash ^= self.zobrist_state_key();
+        self.turn = self.turn.opposite();
+
+        if self.turn == Color::Black {
+            self.full_moves -= 1;
+        }
+
+        self.en_passant_square = en_passant_square;
+        self.half_moves = half_moves;
+        self.zobrist_hash ^= self.zobrist_state_key();
+    }
+
+    /// Used to promote a pawn at the final rank. This method is
+    /// used to promote when using `make_move(auto_promote=false)`. Note
+    /// that this method must be called _after_ calling `make_move`.
+    /// 
+    /// # Arguments
+    /// * `piece_type` is of type `PieceType` and represents the type
+    ///   of piece the pawn will be promoted to.
+    /// 
+    /// # Returns
+    /// * `bool` representing wether or not promotion was successful. Promotion
+    ///   fails if there is no pawn to promote, or if `piece_type` is not one of
+    ///   `legal_promotion_pieces()`, in which case the pawn can still be promoted.
+    /// 
+    /// # Examples
+    /// * How a game loop might look
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// 
+    /// loop {
+    ///     //Get user input...
+    ///     
+    ///     //`from` and `to` are placeholders
+    ///     game.make_move(from, to, false); //auto_promote set to false
     /// 
     ///     match game.get_state() {
     ///         GameState::AwaitPromotion => {
@@ -604,9 +1313,12 @@ impl Game {
     /// ```
     /// 
     /// # Notes
-    /// * No logic preventing promoting a pawn to a pawn, 
-    /// however it is not possible to promote it more than once.
+    /// * It is not possible to promote a pawn more than once.
     pub fn promote_to_piece(&mut self, piece_type : PieceType) -> bool {
+        if !self.can_promote_to(piece_type) {
+            return false;
+        }
+
         let res = match self.promotion_square {
             Some(indx) => {self.promote(indx, piece_type); true}
             None => false,
@@ -617,14 +1329,52 @@ impl Game {
         res
     }
 
+    /// Returns the square of the pawn waiting to be promoted, e.g. to know where
+    /// to show a promotion picker.
+    ///
+    /// # Returns
+    /// * `Some((usize, usize))` with the array index of the pawn if the game state
+    ///   is `GameState::AwaitPromotion`.
+    /// * `None` otherwise.
+    pub fn promotion_square(&self) -> Option<(usize, usize)> {
+        self.promotion_square
+    }
+
+    /// Same as `promotion_square()`, but returns the square in algebraic notation.
+    pub fn promotion_square_as_alg(&self) -> Option<String> {
+        //promotion_square is always a valid index, so unwrap is safe
+        self.promotion_square.map(|indx| indx_to_alg_notation(indx).unwrap())
+    }
+
+    /// Returns the piece types a pawn can be promoted to.
+    pub fn legal_promotion_pieces() -> &'static [PieceType] {
+        &[PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight]
+    }
+
+    /// Returns bool representing wether a pawn can be promoted to `piece_type`,
+    /// see `legal_promotion_pieces()`.
+    pub fn can_promote_to(&self, piece_type : PieceType) -> bool {
+        Game::legal_promotion_pieces().contains(&piece_type)
+    }
+
     //helper function for promote_to_piece
     //places the chosen piece on the board with appropriate color
     fn promote(&mut self, indx : (usize, usize), piece_type : PieceType) {
         let (i, j) = indx;
 
         let piece_color = self.board[i][j].unwrap().color;
+        let gave_check = self.in_check(piece_color.opposite());
+
+        self.set_square((i, j), Some(Piece::new(piece_type, piece_color)));
+        self.promoted_pieces |= square_bit((i, j));
+
+        //the promoted piece may attack different squares than the pawn
+        self.update_attacked_squares();
 
-        self.board[i][j] = Some(Piece::new(piece_type, piece_color));
+        //only count the check if the move itself did not already give check
+        if !gave_check {
+            self.record_check(piece_color);
+        }
     }
 
     /// Undo the last move that was made. Reverts pieces
@@ -633,8 +1383,8 @@ impl Game {
     /// 
     /// # Examples
     /// * Note that `undo_lat_move()` does not revert to previous Game object, 
-    /// rather only reverts effected fields. This means the game will not
-    /// be equivalent to previous game after undoing
+    ///   rather only reverts effected fields. This means the game will not
+    ///   be equivalent to previous game after undoing
     /// 
     /// ```ignore
     /// let mut game = Game::new_starting_pos();
@@ -649,20 +1399,345 @@ impl Game {
     /// !assert_eq!(previous_game, game);
     /// ```
     pub fn undo_last_move(&mut self){
-        if self.previous_state.is_none() {return;}
-
-        //function returns if previous_state is None, so unwrap is safe
-        let mut binding = self.previous_state.clone().unwrap();
-        let prev = binding.as_mut();
-        self.board = prev.board;
-        self.kingside_castle = prev.kingside_castle.clone();
-        self.queenside_castle = prev.queenside_castle.clone();
-        self.en_passant_square = prev.en_passant_square;
-        self.half_moves = prev.half_moves;
-        self.full_moves = prev.full_moves;
-        self.previous_state = prev.previous_state.clone();
-        self.turn = prev.turn;
-        self.captures = prev.captures.clone();
+        let record = match self.undo_stack.pop() {
+            Some(record) => record,
+            None => return,
+        };
+
+        let color = record.moved_piece.color;
+
+        self.board[record.to.0][record.to.1] = None;
+
+        match record.from {
+            Some(from) => self.board[from.0][from.1] = Some(record.moved_piece),
+            None => {
+                //color is always a key in drop_reserve, so unwrap is safe
+                let reserve = self.drop_reserve.get_mut(&color).unwrap();
+                *reserve.entry(record.moved_piece.piece_type).or_insert(0) += 1;
+            },
+        }
+
+        if let Some((rook_from, rook_to)) = record.rook_move {
+            self.board[rook_from.0][rook_from.1] = self.board[rook_to.0][rook_to.1];
+            self.board[rook_to.0][rook_to.1] = None;
+        }
+
+        if let Some((piece, square)) = record.captured {
+            self.board[square.0][square.1] = Some(piece);
+            self.captures.pop();
+
+            if self.game_mode == GameMode::Crazyhouse {
+                //a captured promoted piece was added to the reserve as a pawn
+                let reserve_type = if record.promoted_pieces & square_bit(square) != 0 {
+                    PieceType::Pawn
+                } else {
+                    piece.piece_type
+                };
+
+                //color is always a key in drop_reserve, so unwrap is safe
+                let reserve = self.drop_reserve.get_mut(&color).unwrap();
+                *reserve.entry(reserve_type).or_insert(1) -= 1;
+            }
+        }
+
+        self.promoted_pieces = record.promoted_pieces;
+
+        let [white_kingside, white_queenside, black_kingside, black_queenside] = record.castling_rights;
+        self.kingside_castle.insert(Color::White, white_kingside);
+        self.queenside_castle.insert(Color::White, white_queenside);
+        self.kingside_castle.insert(Color::Black, black_kingside);
+        self.queenside_castle.insert(Color::Black, black_queenside);
+        self.castled.insert(color, record.castled);
+        self.checks_delivered.insert(Color::White, record.checks_delivered[0]);
+        self.checks_delivered.insert(Color::Black, record.checks_delivered[1]);
+
+        self.en_passant_square = record.en_passant_square;
+        self.half_moves = record.half_moves;
+        self.full_moves = record.full_moves;
+        self.turn = record.turn;
+        self.resigned = record.resigned;
+        self.last_move = record.last_move;
+        self.zobrist_hash = record.zobrist_hash;
+        self.promotion_square = record.promotion_square;
+        self.move_annotations.truncate(self.undo_stack.len());
+        self.update_attacked_squares();
+    }
+
+    // creates the undo record for a move of the piece at from to to, before the
+    // move is made. Pieces captured and rooks moved by castling are filled in by the caller
+    fn undo_record(&self, from : Option<(usize, usize)>, to : (usize, usize), moved_piece : Piece) -> UndoRecord {
+        //every color is a key in the castling and check maps, so unwrap is safe
+        UndoRecord {
+            from,
+            to,
+            moved_piece,
+            captured : None,
+            rook_move : None,
+            castling_rights : [
+                *self.kingside_castle.get(&Color::White).unwrap(),
+                *self.queenside_castle.get(&Color::White).unwrap(),
+                *self.kingside_castle.get(&Color::Black).unwrap(),
+                *self.queenside_castle.get(&Color::Black).unwrap(),
+            ],
+            castled : *self.castled.get(&moved_piece.color).unwrap(),
+            turn : self.turn,
+            en_passant_square : self.en_passant_square,
+            half_moves : self.half_moves,
+            full_moves : self.full_moves,
+            checks_delivered : [
+                *self.checks_delivered.get(&Color::White).unwrap(),
+                *self.checks_delivered.get(&Color::Black).unwrap(),
+            ],
+            resigned : self.resigned,
+            last_move : self.last_move,
+            promotion_square : self.promotion_square,
+            promoted_pieces : self.promoted_pieces,
+            zobrist_hash : self.zobrist_hash,
+        }
+    }
+
+    //returns the position before the most recent move, or None if no moves have been made
+    fn previous_position(&self) -> Option<Game> {
+        if self.undo_stack.is_empty() {
+            return None;
+        }
+
+        let mut previous = self.clone();
+        previous.undo_last_move();
+
+        Some(previous)
+    }
+
+    /// Get the FEN string of the position after `move_index` half moves have been
+    /// made, where `0` is the position the game started from.
+    ///
+    /// # Returns
+    /// * `Some(String)` containing the FEN, or `None` if fewer than `move_index`
+    ///   half moves have been made.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// game.make_move("e2", "e4", true).unwrap();
+    ///
+    /// assert_eq!(game.fen_at_move(1), Some(game.to_fen()));
+    /// assert_eq!(game.fen_at_move(2), None);
+    /// ```
+    ///
+    /// # Notes
+    /// * Only records moves made through the Game object, see `get_captures()`.
+    pub fn fen_at_move(&self, move_index : usize) -> Option<String> {
+        self.fen_history().into_iter().nth(move_index)
+    }
+
+    /// Get the FEN string of every position in the game, from the position the game
+    /// started from to the current position. See `fen_at_move()`.
+    pub fn fen_history(&self) -> Vec<String> {
+        self.history().iter().map(|game| game.to_fen()).collect()
+    }
+
+    /// Get an iterator over every position in the game, from the position the game
+    /// started from to the current position. Each position is a copy of the game
+    /// at that point without its move history, so e.g. `undo_last_move()` does
+    /// nothing on it.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::from_san_list(&["e4", "e5", "Nf3"]).unwrap();
+    ///
+    /// for position in game.positions() {
+    ///     println!("{}", position.to_fen());
+    /// }
+    ///
+    /// //same as game.positions()
+    /// assert_eq!((&game).into_iter().count(), 4);
+    /// ```
+    pub fn positions(&self) -> impl Iterator<Item = Game> + '_ {
+        self.into_iter()
+    }
+
+    /// Annotate the most recent move, e.g. with the output of an engine. Replaces
+    /// any earlier annotation of the move. Does nothing if no moves have been made.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// game.make_move("e2", "e4", true).unwrap();
+    ///
+    /// game.annotate_last_move(MoveAnnotation {
+    ///     evaluation : 35,
+    ///     best_line : vec!["e5".to_string(), "Nf3".to_string()],
+    ///     time_ms : 120,
+    /// });
+    ///
+    /// assert_eq!(game.get_annotation(0).unwrap().evaluation, 35);
+    /// ```
+    pub fn annotate_last_move(&mut self, annotation : MoveAnnotation) {
+        let moves_made = self.undo_stack.len();
+
+        if moves_made == 0 {
+            return;
+        }
+
+        self.move_annotations.resize(moves_made, None);
+        self.move_annotations[moves_made - 1] = Some(annotation);
+    }
+
+    /// Get the annotation of the half move with index `move_index`, where `0` is
+    /// the first move made. See `annotate_last_move()`.
+    ///
+    /// # Returns
+    /// * `Some(&MoveAnnotation)` if the move has been annotated, `None` otherwise.
+    pub fn get_annotation(&self, move_index : usize) -> Option<&MoveAnnotation> {
+        self.move_annotations.get(move_index)?.as_ref()
+    }
+
+    /// Get a key identifying the position. Same as `position_hash()`, note that
+    /// `OpeningBook` uses `polyglot_key()` instead.
+    pub fn position_key(&self) -> u64 {
+        self.position_hash()
+    }
+
+    /// Get the Zobrist hash of the position, e.g. for use as the key of a
+    /// transposition table. Two games have the same hash if the pieces, active
+    /// player, castling rights and usable en passant square are equal, move
+    /// counters and history are ignored. The hash is updated incrementally as
+    /// moves are made, so it is cheap to get.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let a = Game::from_san_list(&["Nf3", "Nc6", "Nc3"]).unwrap();
+    /// let b = Game::from_san_list(&["Nc3", "Nc6", "Nf3"]).unwrap();
+    ///
+    /// assert_eq!(a.position_hash(), b.position_hash());
+    /// ```
+    ///
+    /// # Notes
+    /// * Different positions can have the same hash, although it is very unlikely.
+    pub fn position_hash(&self) -> u64 {
+        self.zobrist_hash
+    }
+
+    /// Get the Polyglot key of the position, the key used by Polyglot opening
+    /// books and `OpeningBook`. Like `position_hash()` it covers the pieces, active
+    /// player, castling rights and en passant square, but uses the keys from the
+    /// Polyglot book format, e.g. `0x463b96181691fc9c` for the starting position.
+    ///
+    /// # Notes
+    /// * As in Polyglot, the en passant file is only included if a pawn of the active
+    ///   player stands next to the pawn that made the double push, even if the capture
+    ///   would leave the king in check.
+    /// * The key is computed from the whole board, so it is slower to get than
+    ///   `position_hash()`.
+    pub fn polyglot_key(&self) -> u64 {
+        let mut key = 0;
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j] {
+                    key ^= polyglot::piece_key(piece, (i, j));
+                }
+            }
+        }
+
+        for color in [Color::White, Color::Black] {
+            //color is always a key in kingside_castle and queenside_castle, so unwrap is safe
+            if *self.kingside_castle.get(&color).unwrap() {
+                key ^= polyglot::castling_key(color, true);
+            }
+
+            if *self.queenside_castle.get(&color).unwrap() {
+                key ^= polyglot::castling_key(color, false);
+            }
+        }
+
+        if let Some((i, j)) = self.en_passant_square {
+            //row of the pawn that made the double push, next to the capturing pawns
+            let pawn_row = match self.turn {
+                Color::White => i + 1,
+                Color::Black => i.wrapping_sub(1),
+            };
+            let pawn = Some(Piece::new(PieceType::Pawn, self.turn));
+
+            let has_capturing_pawn = [j.wrapping_sub(1), j + 1].into_iter()
+                .any(|file| pawn_row < 8 && file < 8 && self.board[pawn_row][file] == pawn);
+
+            if has_capturing_pawn {
+                key ^= polyglot::en_passant_key(j);
+            }
+        }
+
+        if self.turn == Color::White {
+            key ^= polyglot::turn_key();
+        }
+
+        key
+    }
+
+    /// Returns bool representing wether the current position is in `book`.
+    pub fn is_book_position(&self, book : &OpeningBook) -> bool {
+        book.entries.contains_key(&self.polyglot_key())
+    }
+
+    /// Get the book moves for the current position in UCI format, e.g. `"e2e4"`,
+    /// in the order they are stored in `book`. Empty if the position is not in the book.
+    ///
+    /// # Notes
+    /// * Castling written as the king capturing its own rook, as in Polyglot books,
+    ///   e.g. `"e1h1"`, is returned as the square the king moves to, e.g. `"e1g1"`.
+    pub fn book_moves(&self, book : &OpeningBook) -> Vec<String> {
+        let moves = match book.entries.get(&self.polyglot_key()) {
+            Some(moves) => moves,
+            None => return Vec::new(),
+        };
+
+        moves.iter().map(|uci| self.convert_book_castling(uci)).collect()
+    }
+
+    // converts castling written as the king capturing its own rook, e.g. "e1h1",
+    // to the square the king moves to, e.g. "e1g1"
+    fn convert_book_castling(&self, uci : &str) -> String {
+        let squares = (uci.get(0..2).map(alg_notation_to_indx), uci.get(2..4).map(alg_notation_to_indx));
+
+        if let (Some(Ok(from)), Some(Ok(to))) = squares {
+            let is_king = matches!(self.board[from.0][from.1], Some(piece) if piece.piece_type == PieceType::King);
+            let is_own_rook = matches!(self.board[to.0][to.1], Some(piece) if piece.piece_type == PieceType::Rook && Some(piece.color) == self.board[from.0][from.1].map(|king| king.color));
+
+            if is_king && is_own_rook && from.0 == to.0 && from.1 == 4 && (to.1 == 0 || to.1 == 7) {
+                let king_file = if to.1 == 7 {6} else {2};
+                //from.0 and king_file are valid indicies, so unwrap is safe
+                return format!("{}{}", &uci[0..2], indx_to_alg_notation((from.0, king_file)).unwrap());
+            }
+        }
+
+        uci.to_string()
+    }
+
+    //returns every position in the game without its history, starting with the oldest one
+    //the moves are undone one at a time on a single copy, so only the copy clones the history
+    fn history(&self) -> Vec<Game> {
+        let mut game = self.clone();
+        let mut undo_stack = std::mem::take(&mut game.undo_stack);
+
+        game.captures.clear();
+        game.move_annotations.clear();
+        game.null_moves.clear();
+
+        let mut history = vec![game.clone()];
+
+        while let Some(record) = undo_stack.pop() {
+            game.undo_stack.push(record);
+            game.undo_last_move();
+            history.push(game.clone());
+        }
+
+        history.reverse();
+
+        history
     }
 
     /// Get a `Vec` of legal moves for a given square. The vector consist 
@@ -674,7 +1749,7 @@ impl Game {
     /// # Returns
     /// 
     /// * Returns `Vec` of tuples `(usize, usize)` describing all array indicies
-    /// that the piece at the provided index can move to.
+    ///   that the piece at the provided index can move to.
     /// * Returns Result with empty vector if the board position is empty.
     /// 
     /// # Examples
@@ -693,7 +1768,7 @@ impl Game {
     pub fn get_legal_moves_alg_notation(&mut self, pos : &str) -> Result<Vec<(usize, usize)>, String>{
         let indx = alg_notation_to_indx(pos)?;
 
-        self.get_legal_moves_array_index(indx)
+        Ok(self.legal_moves_from(indx))
     }
 
     /// Get a `Vec` of legal moves for a given square. The vector consist 
@@ -705,7 +1780,7 @@ impl Game {
     /// # Returns
     /// 
     /// * Returns `Vec` of tuples `(usize, usize)` describing all array indicies
-    /// that the piece at the provided index can move to.
+    ///   that the piece at the provided index can move to.
     /// * Returns Result with empty vector if the board position is empty.
     /// 
     /// # Examples
@@ -721,17 +1796,44 @@ impl Game {
     /// # Errors
     /// 
     /// * If the provided index is invalid the function returns Err(String)
+    #[deprecated(note = "use `get_legal_moves_square()` instead")]
     pub fn get_legal_moves_array_index(&mut self, index : (usize, usize)) -> Result<Vec<(usize, usize)>, String>{
         let (i, j) = index;
         
         //return err if position is invalid
-        if !is_valid_pos(i as i32, j as i32){
+        if !is_valid_index((i, j)){
             return Err(format!("Invalid index {:?}", index));
         }
-        
+
+        Ok(self.legal_moves_from(index))
+    }
+
+    /// Get every square the piece on `square` can legally move to, empty if
+    /// the square is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// let moves = game.get_legal_moves_square(Square::from_alg("b1").unwrap());
+    ///
+    /// assert_eq!(moves.len(), 2);
+    /// ```
+    pub fn get_legal_moves_square(&mut self, square : Square) -> Vec<Square> {
+        self.legal_moves_from(square.into())
+            .into_iter()
+            //legal moves are always on the board, so unwrap is safe
+            .map(|to| Square::try_from(to).unwrap())
+            .collect()
+    }
+
+    // returns the legal moves of the piece at index, must be a valid index
+    fn legal_moves_from(&mut self, index : (usize, usize)) -> Vec<(usize, usize)> {
+        let (i, j) = index;
+
         let color = match self.board[i][j] {
             Some(piece) => piece.color,
-            None => return Ok(Vec::new()),
+            None => return Vec::new(),
         };
 
         let pos = (i, j);
@@ -750,876 +1852,7841 @@ impl Game {
             self.undo_last_move();
         }
 
-        return Ok(legal_moves);
+        legal_moves
     }
 
-    /// Get all legal moves for a player (color) in a given position. 
-    /// 
-    /// # Arguments
-    /// 
-    /// * color `Color` of player who's moves should be retrieved.
-    /// 
-    /// # Returns
-    /// 
-    /// * `HashMap` containing all possible moves for each piece of given color.
-    /// Each key is `(usize, usize)` representing the index of a piece. 
-    /// Each value is a `Vec<(usize, usize)>` representing all indicies to which
-    /// the piece can move to.
-    /// 
-    pub fn get_all_legal_moves(&mut self, color : Color) -> HashMap<(usize, usize), Vec<(usize, usize)>> {
-        let mut move_hash : HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
-
-        for i in 0..8 {
-            for j in 0..8 {
+    /// Get a printable board with unicode pieces, rank numbers on the left and file
+    /// letters at the bottom, as seen by player `color`. Empty squares alternate
+    /// between `·` for dark squares and a space for light squares. Below the board
+    /// is a status line with the active player, castling rights, en passant square
+    /// and move counters.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::new_starting_pos();
+    ///
+    /// println!("{}", game.display_from_perspective(Color::Black));
+    /// ```
+    /// This will print
+    /// ```text
+    /// 1 ♖ ♘ ♗ ♔ ♕ ♗ ♘ ♖
+    /// 2 ♙ ♙ ♙ ♙ ♙ ♙ ♙ ♙
+    /// 3   ·   ·   ·   ·
+    /// 4 ·   ·   ·   ·  
+    /// 5   ·   ·   ·   ·
+    /// 6 ·   ·   ·   ·  
+    /// 7 ♟ ♟ ♟ ♟ ♟ ♟ ♟ ♟
+    /// 8 ♜ ♞ ♝ ♚ ♛ ♝ ♞ ♜
+    ///   h g f e d c b a
+    /// White to move, castling: KQkq, half moves: 0, full moves: 1
+    /// ```
+    pub fn display_from_perspective(&self, color : Color) -> String {
+        let order : Vec<usize> = match color {
+            Color::White => (0..8).collect(),
+            Color::Black => (0..8).rev().collect(),
+        };
+
+        let mut str = String::new();
+
+        for &i in &order {
+            str.push_str(&format!("{}", 8 - i));
+
+            for &j in &order {
+                str.push(' ');
+
+                match self.board[i][j] {
+                    Some(piece) => str.push(get_unicode_repr(piece)),
+                    //a8 is a light square
+                    None if (i + j) % 2 == 1 => str.push('·'),
+                    None => str.push(' '),
+                }
+            }
+
+            str.push('\n');
+        }
+
+        str.push(' ');
+
+        for &j in &order {
+            str.push(' ');
+            str.push((b'a' + j as u8) as char);
+        }
+
+        let fen = self.to_fen();
+        let fields : Vec<&str> = fen.split(' ').collect();
+
+        let player = match self.turn {
+            Color::White => "White",
+            Color::Black => "Black",
+        };
+
+        str.push_str(&format!("\n{} to move, castling: {}", player, fields[2]));
+
+        if fields[3] != "-" {
+            str.push_str(&format!(", en passant: {}", fields[3]));
+        }
+
+        str.push_str(&format!(", half moves: {}, full moves: {}", fields[4], fields[5]));
+
+        str
+    }
+
+    /// Get a printable visualization of the legal moves of the piece at `square`, for
+    /// debugging. The current board is shown on the left, and on the right a board with
+    /// only the piece at `square` and `*` on every square it can move to.
+    ///
+    /// # Arguments
+    /// * `square` is a square in algebraic notation, e.g. `"e2"`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// println!("{}", game.print_legal_moves_visual("g1").unwrap());
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if `square` is not valid algebraic notation.
+    pub fn print_legal_moves_visual(&mut self, square : &str) -> Result<String, String> {
+        let pos = alg_notation_to_indx(square)?;
+        let moves = self.legal_moves_from(pos);
+
+        let mut str = String::new();
+
+        for i in 0..8 {
+            for j in 0..8 {
+                match self.board[i][j] {
+                    Some(piece) => str.push(get_repr(piece)),
+                    None => str.push('.'),
+                }
+                str.push(' ');
+            }
+
+            str.push_str("   ");
+
+            for j in 0..8 {
+                if (i, j) == pos {
+                    match self.board[i][j] {
+                        Some(piece) => str.push(get_repr(piece)),
+                        None => str.push('.'),
+                    }
+                } else if moves.contains(&(i, j)) {
+                    str.push('*');
+                } else {
+                    str.push('.');
+                }
+                str.push(' ');
+            }
+            str.push('\n');
+        }
+
+        Ok(str)
+    }
+
+    /// Get a `Vec` of pseudo-legal moves for a given square, i.e. all moves the piece
+    /// can make before filtering out moves that leave its own king in check.
+    /// Useful for debugging move generation, e.g. counting pseudo-legal and legal
+    /// nodes separately in a perft routine.
+    ///
+    /// # Arguments
+    /// * `square` is an array index in the board, for more detail refer to `Game` struct.
+    ///
+    /// # Returns
+    /// * Returns `Vec` of tuples `(usize, usize)` describing all array indicies
+    ///   that the piece at the provided index can move to, ignoring checks.
+    /// * Returns Result with empty vector if the board position is empty.
+    ///
+    /// # Errors
+    /// * If the provided index is invalid the function returns Err(String)
+    ///
+    /// # Notes
+    /// * __Warning:__ these moves may leave the king in check and are not safe
+    ///   to play directly, use `get_legal_moves_square()` for legal moves.
+    pub fn pseudo_legal_moves(&self, square : (usize, usize)) -> Result<Vec<(usize, usize)>, String> {
+        self.get_pseudo_legal_moves_for_square(square.0, square.1, false)
+    }
+
+    /// Get all legal moves for a player (color) in a given position. 
+    /// 
+    /// # Arguments
+    /// 
+    /// * color `Color` of player who's moves should be retrieved.
+    /// 
+    /// # Returns
+    /// 
+    /// * `HashMap` containing all possible moves for each piece of given color.
+    ///   Each key is `(usize, usize)` representing the index of a piece. 
+    ///   Each value is a `Vec<(usize, usize)>` representing all indicies to which
+    ///   the piece can move to.
+    /// 
+    pub fn get_all_legal_moves(&mut self, color : Color) -> HashMap<(usize, usize), Vec<(usize, usize)>> {
+        let mut move_hash : HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+
+        for i in 0..8 {
+            for j in 0..8 {
                 if let Some(piece) = self.board[i][j] {
                     if piece.color == color {
                         //i, j will always be a valid index, so unwrap is safe
-                        let legal_moves = self.get_legal_moves_array_index((i, j)).unwrap();
+                        let legal_moves = self.legal_moves_from((i, j));
                         move_hash.insert((i, j), legal_moves);
                     }
                 }
             }
         }
 
-        return move_hash;
+        move_hash
     }
 
-    /// Returns bool representing wether a player is in check or not.
-    pub fn in_check(&self, color : Color) -> bool {
-        let attacked_squares = self.get_attacked_squares(color.opposite());
+    /// Get all legal moves for a player (color) as a flat list, for use in e.g. the
+    /// move loop of an engine.
+    ///
+    /// # Returns
+    /// * `Vec` of tuples `(from, to)` with the array indicies of the moving piece and
+    ///   the square it moves to, in board order starting at a8.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// for (from, to) in game.get_all_legal_moves_flat(Color::White) {
+    ///     game.make_move_unchecked(from, to, Some(PieceType::Queen));
+    ///     //search...
+    ///     game.undo_last_move();
+    /// }
+    /// ```
+    pub fn get_all_legal_moves_flat(&mut self, color : Color) -> Vec<IndexMove> {
+        //30 is around the average number of legal moves in a position
+        let mut moves = Vec::with_capacity(30);
 
-        //Find king position
         for i in 0..8 {
             for j in 0..8 {
-                if let Some(piece) = self.board[i][j]{
-                    if piece.piece_type == PieceType::King
-                    && piece.color == color
-                    {
-                        return attacked_squares.contains(&(i, j));
+                if let Some(piece) = self.board[i][j] {
+                    if piece.color == color {
+                        //i, j will always be a valid index, so unwrap is safe
+                        for to in self.legal_moves_from((i, j)) {
+                            moves.push(((i, j), to));
+                        }
                     }
                 }
             }
         }
 
-        return false;
+        moves
     }
 
-    /// Returns current state of the game. For possible game states,
-    /// refer to documentation for `GameState` enum.
-    pub fn get_state(&mut self) -> GameState{
-        if self.promotion_square.is_some() {
-            return GameState::AwaitPromotion;
-        }
-
-        let current_turn_legal_moves = match self.turn {
-            Color::White => self.num_of_legal_moves(Color::White),
-            Color::Black => self.num_of_legal_moves(Color::Black),
-        };
+    /// Get the legal moves of the piece on `square` as `Move`s, with flags describing
+    /// each move and the captured piece set for captures, including en passant.
+    /// A promotion is listed once for each piece the pawn can promote to.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    /// let moves = game.get_legal_moves_as_moves(Square::from_alg("e1").unwrap());
+    ///
+    /// assert!(moves.contains(&Move::new((7, 4), (7, 6), MoveFlags::KingsideCastle)));
+    /// ```
+    pub fn get_legal_moves_as_moves(&mut self, square : Square) -> Vec<Move> {
+        let from = square.into();
+        let mut moves = Vec::new();
 
-        if current_turn_legal_moves == 0 {
-            if self.in_check(self.turn) {
-                return GameState::Win(WinState::Checkmate(self.turn.opposite()));
+        for to in self.legal_moves_from(from) {
+            if self.is_promotion_move(from, to) {
+                for &piece_type in Game::legal_promotion_pieces() {
+                    moves.push(self.flagged_move(from, to, piece_type));
+                }
             } else {
-                return GameState::Draw(DrawState::Stalemate);
+                moves.push(self.flagged_move(from, to, PieceType::Queen));
             }
         }
 
-        if self.half_moves >= 100 {
-            return GameState::Draw(DrawState::FiftyMoveRule);
-        }
+        moves
+    }
 
-        if !self.can_win(Color::White) && !self.can_win(Color::Black) {
-            return GameState::Draw(DrawState::InsufficientMaterial);
+    /// Get all legal moves for `color` as `Move`s, in board order starting at a8.
+    /// See `get_legal_moves_as_moves()`.
+    pub fn get_all_legal_moves_as_moves(&mut self, color : Color) -> Vec<Move> {
+        let mut moves = Vec::with_capacity(30);
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if matches!(self.board[i][j], Some(piece) if piece.color == color) {
+                    //i, j will always be a valid index, so unwrap is safe
+                    moves.extend(self.get_legal_moves_as_moves(Square::new(i, j).unwrap()));
+                }
+            }
         }
 
-        return GameState::InProgress;
+        moves
     }
 
-    /// Returns color of active player
-    pub fn get_active_player(&self) -> Color {
-        self.turn
-    }
+    /// Make a move given as a `Move`. Only the squares and the promotion piece are
+    /// read from the move, the other flags are ignored. A promotion move without
+    /// `MoveFlags::Promotion` promotes to a queen.
+    ///
+    /// # Returns
+    /// * `Result<bool, String>`, same as `make_move_square()`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// assert_eq!(game.play_move(Move::from_alg("e2", "e4").unwrap()), Ok(true));
+    /// ```
+    pub fn play_move(&mut self, mv : Move) -> Result<bool, String> {
+        let promotion = match mv.flags {
+            MoveFlags::Promotion(piece_type) => piece_type,
+            _ => PieceType::Queen,
+        };
 
-    /// Returns `vec` of each `Piece` that `color` has captured
-    /// during the game.
-    /// 
-    /// # Notes
-    /// * Only records moves made through the Game object using any
-    /// implementation of make_move() method. Positions generated
-    /// from FEN will not have captures recorded properly. 
-    pub fn get_captures(&self, color : Color) -> Vec<Piece>{
-        let mut res = Vec::new();
+        if !Game::legal_promotion_pieces().contains(&promotion) {
+            return Ok(false);
+        }
 
-        for p in &self.captures {
-            if p.color == color.opposite() {
-                res.push(*p);
-            }
+        if !is_valid_move(mv.from, mv.to) || !self.make_move_with_index(mv.from, mv.to, true, false)? {
+            return Ok(false);
         }
 
-        res
+        if self.promotion_square.is_some() {
+            self.promote_to_piece(promotion);
+        }
+
+        Ok(true)
     }
 
-    //function to handle movement logic
-    fn make_move_with_index(&mut self, from : (usize, usize), to : (usize, usize), check_legal : bool, auto_promote : bool) -> Result<bool, String> {
-        let (i1, j1) = from;
-        let (i2, j2) = to;
+    /// Get the most recent move as a `Move`, with flags describing the move.
+    ///
+    /// # Returns
+    /// * `Some(Move)`, or `None` if no moves have been made or the last move was a drop.
+    pub fn get_last_move(&self) -> Option<Move> {
+        let (from, to) = self.last_move?;
+        let previous = self.previous_position()?;
+
+        let promotion = match self.board[to.0][to.1] {
+            Some(piece) if previous.is_promotion_move(from, to) => piece.piece_type,
+            _ => PieceType::Queen,
+        };
 
-        //return if move is illegal
-        //ignored if check_legal is false
-        if check_legal{
-            if let Ok(Some(piece)) = self.piece_at_array_index((i1, j1)) {
-                if piece.color != self.turn {
-                    return Ok(false);
-                }
-            }
-            //get_legal_moves_square() will always return Some() since
-            //index (i1, j1) is validated in make_move_array_index()
-            if !(self.get_legal_moves_array_index((i1, j1)).unwrap().contains(&(i2, j2))) {
-                return Ok(false);
-            }
+        Some(previous.flagged_move(from, to, promotion))
+    }
+
+    /// Returns how many legal moves player `color` has in a given position.
+    pub fn legal_move_count(&mut self, color : Color) -> u32 {
+        let mut res = 0;
+
+        for moves in self.get_all_legal_moves(color).values(){
+            res += moves.len();
         }
 
-        //save board state
-        self.previous_state = Some(Box::new(self.clone()));
+        res as u32
+    }
 
-        //increment half moves, if there is a capture or pawn move this will be reset
-        self.half_moves += 1;
+    /// Get every legal move for `color` that gets out of check. Only king moves are
+    /// generated when in double check, otherwise king moves, captures of the checking
+    /// piece and moves blocking the check.
+    ///
+    /// # Returns
+    /// * `Vec` of tuples `(i1, j1, i2, j2)` where `(i1, j1)` is the index of the
+    ///   moving piece and `(i2, j2)` the index it moves to. Empty if `color` is not in check.
+    pub fn check_evasion_moves(&mut self, color : Color) -> Vec<(usize, usize, usize, usize)> {
+        let checkers = self.get_checkers(color);
+
+        let king_pos = match self.find_king(color) {
+            Some(king_pos) if !checkers.is_empty() => king_pos,
+            _ => return Vec::new(),
+        };
 
-        //Capture logic
-        if let Some(piece) = self.board[i2][j2] {
-            self.captures.push(piece);
-            self.half_moves = 0; //piece captured : resets half moves
-        }
+        //squares a piece other than the king can move to, to get out of a single check
+        let mut targets = Vec::new();
 
-        //Check if castling
-        //note board[i1][j1] is always Some(Piece) due to how
-        //this function is called, so unwrap() wont panic
-        if self.board[i1][j1].unwrap().piece_type == PieceType::King {
-            let d = j1 as i32 - j2 as i32;
+        if let [(c_i, c_j)] = checkers[..] {
+            targets.push((c_i, c_j));
 
-            //check if king is moved 2 squares
-            if d.abs() == 2 {
-                //remove castling rights
-                let king_color = self.board[i1][j1].unwrap().color;
-                self.kingside_castle.insert(king_color, false);
-                self.queenside_castle.insert(king_color, false);
+            //checker is always a piece, so unwrap is safe
+            let checker = self.board[c_i][c_j].unwrap();
 
-                //kingside castle
-                if d < 0 {
-                    self.board[i1][5] = self.board[i1][7];
-                    self.board[i1][7] = None;
-                } else { //queenside castle
-                    self.board[i1][3] = self.board[i1][0];
-                    self.board[i1][0] = None;
+            if matches!(checker.piece_type, PieceType::Bishop | PieceType::Rook | PieceType::Queen) {
+                let d_i = (king_pos.0 as i32 - c_i as i32).signum();
+                let d_j = (king_pos.1 as i32 - c_j as i32).signum();
+                let (mut i, mut j) = (c_i as i32 + d_i, c_j as i32 + d_j);
+
+                while (i as usize, j as usize) != king_pos {
+                    targets.push((i as usize, j as usize));
+                    i += d_i;
+                    j += d_j;
                 }
             }
-        } else if self.board[i1][j1].unwrap().piece_type == PieceType::Rook {
-            //remove castling rights if the rook is moved
-
-            let rook_color = self.board[i1][j1].unwrap().color;
-
-            let starting_rank = match rook_color {
-                Color::White => 7,
-                Color::Black => 0,
-            };
 
-            if i1 == starting_rank {
-                match j1 {
-                    0 => {self.queenside_castle.insert(rook_color, false);},
-                    7 => {self.kingside_castle.insert(rook_color, false);},
-                    _ => (),
+            //a pawn that just moved two squares can be captured en passant
+            if let Some(ep_square) = self.en_passant_square {
+                if checker.piece_type == PieceType::Pawn && ep_square.1 == c_j && ep_square.0.abs_diff(c_i) == 1 {
+                    targets.push(ep_square);
                 }
             }
-        } else if self.board[i1][j1].unwrap().piece_type == PieceType::Pawn {
-            self.half_moves = 0; //pawn moved : reset half moves
+        }
 
-            let pawn_color = self.board[i1][j1].unwrap().color;
+        let mut moves = Vec::new();
 
-            //check if pawn is moved two squares
-            let d = i1 as i32 - i2 as i32;
+        for i in 0..8 {
+            for j in 0..8 {
+                if !matches!(self.board[i][j], Some(piece) if piece.color == color) {
+                    continue;
+                }
 
-            if d.abs() == 2 {
-                self.en_passant_square = Some(((i1 + i2) / 2, j1))
-            }
+                //i, j will always be a valid index, so unwrap is safe
+                if (i, j) == king_pos {
+                    for (i2, j2) in self.legal_moves_from((i, j)) {
+                        moves.push((i, j, i2, j2));
+                    }
+                    continue;
+                }
 
-            if self.is_promotion_move(from, to) {
-                self.promotion_square = Some((i2, j2));
-            }
+                for to in self.get_pseudo_legal_moves_for_square(i, j, false).unwrap() {
+                    if !targets.contains(&to) {
+                        continue;
+                    }
 
-            if self.en_passant_square.is_some(){
-                if (i2, j2) == self.en_passant_square.unwrap() {
-                    match pawn_color {
-                        Color::White => self.board[i2 + 1][j2] = None,
-                        Color::Black => self.board[i2 - 1][j2] = None,
+                    //the moving piece may be pinned
+                    self.make_move_with_index((i, j), to, false, true).unwrap();
+                    let legal = !self.in_check(color);
+                    self.undo_last_move();
+
+                    if legal {
+                        moves.push((i, j, to.0, to.1));
                     }
                 }
             }
         }
 
-        if let Some(piece) = self.board[i2][j2] {
-            if piece.piece_type == PieceType::Rook {
-                //remove castling rights if the rook is captured
+        moves
+    }
 
-                let rook_color = self.board[i2][j2].unwrap().color;
+    /// Returns bool representing wether `color` has exactly one legal move,
+    /// see `get_forced_move()`.
+    pub fn is_forced_move(&mut self, color : Color) -> bool {
+        self.get_forced_move(color).is_some()
+    }
 
-                let starting_rank = match rook_color {
-                    Color::White => 7,
-                    Color::Black => 0,
-                };
+    /// Get the only legal move of `color`, if it has exactly one. Stops generating
+    /// moves as soon as a second legal move is found.
+    ///
+    /// # Returns
+    /// * `Some((i1, j1, i2, j2))` where `(i1, j1)` is the index of the moving piece
+    ///   and `(i2, j2)` the index it moves to, `None` if `color` has zero or several legal moves.
+    pub fn get_forced_move(&mut self, color : Color) -> Option<(usize, usize, usize, usize)> {
+        let mut forced_move = None;
 
-                if i2 == starting_rank {
-                    match j2 {
-                        0 => {self.queenside_castle.insert(rook_color, false);},
-                        7 => {self.kingside_castle.insert(rook_color, false);},
-                        _ => (),
+        for i in 0..8 {
+            for j in 0..8 {
+                if !matches!(self.board[i][j], Some(piece) if piece.color == color) {
+                    continue;
+                }
+
+                //i, j will always be a valid index, so unwrap is safe
+                for (i2, j2) in self.legal_moves_from((i, j)) {
+                    if forced_move.is_some() {
+                        return None;
                     }
+
+                    forced_move = Some((i, j, i2, j2));
                 }
             }
         }
 
-        //make move
-        self.board[i2][j2] = self.board[i1][j1];
-        self.board[i1][j1] = None;
+        forced_move
+    }
 
-        if auto_promote {
-            self.promote_to_piece(PieceType::Queen);
+    /// Count the leaf nodes of the move tree at `depth` half moves from the current
+    /// position, with bulk counting: at depth `1` the legal moves are counted
+    /// without being played. Promotions count once for each promotion piece,
+    /// which matches the standard perft numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// assert_eq!(game.perft_bulk(3), 8902);
+    /// ```
+    pub fn perft_bulk(&mut self, depth : u32) -> u64 {
+        if depth == 0 {
+            return 1;
         }
 
-        self.update_attacked_squares();
+        let moves = self.legal_moves_in_board_order(self.turn);
 
-        if self.turn == Color::Black {
-            self.full_moves += 1;
+        if depth == 1 {
+            return moves.iter()
+                .map(|&(i1, j1, i2, j2)| if self.is_promotion_move((i1, j1), (i2, j2)) {4} else {1})
+                .sum();
         }
 
-        self.en_passant_square = None;
-        self.turn = self.turn.opposite();
+        let mut nodes = 0;
 
-        Ok(true)
-    }
+        for (i1, j1, i2, j2) in moves {
+            let promotions = if self.is_promotion_move((i1, j1), (i2, j2)) {
+                vec![Some(PieceType::Queen), Some(PieceType::Rook), Some(PieceType::Bishop), Some(PieceType::Knight)]
+            } else {
+                vec![None]
+            };
 
-    /// Checks wether or not a move is a promotion move
-    fn is_promotion_move(&self, from : (usize, usize), to : (usize, usize)) -> bool {
-            if is_valid_move(from, to){
-                let pawn_color = self.board[from.0][from.1].unwrap().color;
-                
-                let promotion_rank = match pawn_color {
-                    Color::White => 0,
-                    Color::Black => 7,
-                };
-                
-                return to.0 == promotion_rank;
+            for promotion in promotions {
+                self.make_move_unchecked((i1, j1), (i2, j2), promotion);
+                nodes += self.perft_bulk(depth - 1);
+                self.undo_last_move();
             }
+        }
 
-            return false;
+        nodes
+    }
+
+    /// Count the leaf nodes of the move tree at `depth` half moves from the current
+    /// position. Every legal move is played and undone, including at the last
+    /// depth, so this is slower than `perft_bulk()` but also checks that making
+    /// and undoing moves works. Promotions count once for each promotion piece.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// assert_eq!(game.perft(2), 400);
+    /// ```
+    pub fn perft(&mut self, depth : u32) -> u64 {
+        if depth == 0 {
+            return 1;
         }
 
-    ///Returns Result, if Ok -> Vector of all legal moves (usize, usize) for the given square
-    /// 
-    /// Returns Err if provided index is invalid
-    fn get_pseudo_legal_moves_for_square(&self, i : usize, j : usize, only_attacked : bool) -> Result<Vec<(usize, usize)>, String>{
-        if !is_valid_pos(i as i32, j as i32) {
-            return Err(format!("Invalid index : Cannot compute pseudo-legal moves for index {i}, {j}"))
+        let mut nodes = 0;
+
+        for ((from, to), promotion) in self.perft_moves() {
+            self.make_move_unchecked(from, to, promotion);
+            nodes += self.perft(depth - 1);
+            self.undo_last_move();
         }
 
-        //since i, j is validated as a position all calls to pseudo_legal_moves
-        //will not panic when calling unwrap() in the respective function
-        match self.board[i][j] {
-            None => return Ok(Vec::new()),
-            Some(piece) => match piece.piece_type {
-                PieceType::Pawn => Ok(self.pawn_pseudo_legal_moves(i, j, only_attacked)),
-                PieceType::Rook => Ok(self.directional_pseudo_legal_moves(i, j, &self.rook_move_directions, 8, only_attacked)),
-                PieceType::Bishop =>  Ok(self.directional_pseudo_legal_moves(i, j, &self.bishop_move_directions, 8, only_attacked)),
-                PieceType::Knight => Ok(self.directional_pseudo_legal_moves(i, j, &self.knight_move_directions, 1, only_attacked)),
-                PieceType::Queen => Ok(self.directional_pseudo_legal_moves(i, j, &self.queen_move_directions, 8, only_attacked)),
-                PieceType::King => Ok(self.king_pseudo_legal_moves(i, j, only_attacked)),
+        nodes
+    }
+
+    /// Count the leaf nodes of the move tree at `depth` half moves separately for
+    /// each legal move in the current position, see `perft()`. Useful for finding
+    /// which move a move generation bug is under.
+    ///
+    /// # Returns
+    /// * `HashMap` from each legal move in long algebraic notation, e.g. `"e2e4"`
+    ///   or `"a7a8q"`, to the number of leaf nodes after it. Empty if `depth` is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// let divide = game.perft_divide(2);
+    ///
+    /// assert_eq!(divide.len(), 20);
+    /// assert_eq!(divide.get("e2e4"), Some(&20));
+    /// ```
+    pub fn perft_divide(&mut self, depth : u32) -> HashMap<String, u64> {
+        let mut divide = HashMap::new();
+
+        if depth == 0 {
+            return divide;
+        }
+
+        for ((from, to), promotion) in self.perft_moves() {
+            //from and to are valid indicies, so unwrap is safe
+            let mut key = format!("{}{}", indx_to_alg_notation(from).unwrap(), indx_to_alg_notation(to).unwrap());
+
+            if let Some(piece_type) = promotion {
+                key.push(match piece_type {
+                    PieceType::Queen => 'q',
+                    PieceType::Rook => 'r',
+                    PieceType::Bishop => 'b',
+                    _ => 'n',
+                });
             }
+
+            self.make_move_unchecked(from, to, promotion);
+            divide.insert(key, self.perft(depth - 1));
+            self.undo_last_move();
         }
+
+        divide
     }
 
-    /// compute pseudo-legal moves for pieces that move in given directions
-    /// max_moves indicates how far a piece can "slide"
-    /// used for calculating pseudo-legal moves for every piece except for the pawn and king*
-    /// 
-    /// * the king has it's own function to include castling, but uses this function as well
-    /// 
-    /// # Panics
-    /// Function panics if there is not a piece at index i, j
-    /// 
-    /// Function should only be called thorugh get_pseudo_legal_moves_for_square() 
-    fn directional_pseudo_legal_moves(&self, i : usize, j : usize, directions : &Vec<(i32, i32)>, max_moves : u32, include_all_attacked : bool) -> Vec<(usize, usize)> {
-        let piece_color = self.board[i][j].unwrap().color;
+    //returns every legal move for the side to move, with one entry for each promotion piece
+    fn perft_moves(&mut self) -> Vec<(IndexMove, Option<PieceType>)> {
+        let mut moves = Vec::new();
 
-        let mut moves_vec : Vec<(usize, usize)> = Vec::new();
+        for (i1, j1, i2, j2) in self.legal_moves_in_board_order(self.turn) {
+            if self.is_promotion_move((i1, j1), (i2, j2)) {
+                for piece_type in [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight] {
+                    moves.push((((i1, j1), (i2, j2)), Some(piece_type)));
+                }
+            } else {
+                moves.push((((i1, j1), (i2, j2)), None));
+            }
+        }
 
-        //loop thorugh all directions the piece can move in
-        for direction in directions {
-            //create new mutable indicies, i32 to allow for negative values
-            //movement directions may include negative values, so usize is not suitable
-            let mut i_m = i as i32;
-            let mut j_m = j as i32;
+        moves
+    }
 
-            let (d_i, d_j) = direction;
-            let mut moves_made = 0;
+    /// Play a random game from the standard starting position. Each half move is
+    /// picked uniformly among the legal moves, and promotions pick a random piece.
+    /// The same `seed` always gives the same game.
+    ///
+    /// # Arguments
+    /// * `max_moves` - The most half moves to play, the game stops earlier if it is over.
+    /// * `seed` - Seed for the random number generator, any value is allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::random_game(200, 42);
+    ///
+    /// assert!(game.is_terminal() || game.fen_history().len() == 201);
+    /// ```
+    ///
+    /// # Notes
+    /// * Intended for testing and generating data, no attempt is made to play well.
+    pub fn random_game(max_moves : u32, seed : u64) -> Game {
+        let mut game = Game::new_starting_pos();
+        let mut rng_state = seed;
 
-            while moves_made < max_moves {
+        for _ in 0..max_moves {
+            if game.is_terminal() {
+                break;
+            }
 
-                i_m += d_i;
-                j_m += d_j;
+            let moves = game.legal_moves_in_board_order(game.turn);
+            //a game that is not over always has a legal move
+            let (i1, j1, i2, j2) = moves[next_random(&mut rng_state) as usize % moves.len()];
 
-                if is_valid_pos(i_m, j_m) {
-                    //convert to usize for indexing
-                    let i_m = i_m as usize;
-                    let j_m = j_m as usize;
+            let promotion = if game.is_promotion_move((i1, j1), (i2, j2)) {
+                let pieces = Game::legal_promotion_pieces();
+                Some(pieces[next_random(&mut rng_state) as usize % pieces.len()])
+            } else {
+                None
+            };
 
-                    //check if there is a piece at the given index i, j
-                    // No piece -> add index to moves vec
-                    // Piece of other color -> add piece to moves vec and break loop (go to next direction)
-                    // Piece of same color -> break loop (go to next direction)
-                    match self.board[i_m][j_m] {
-                        None => moves_vec.push((i_m, j_m)),
-                        Some(piece) => {
-                            if piece.color == piece_color {
-                                if include_all_attacked {
-                                    moves_vec.push((i_m, j_m));
-                                }
-                                break;
-                            } else {
-                                moves_vec.push((i_m, j_m));
-                                break;
-                            }
-                        }
+            game.make_move_unchecked((i1, j1), (i2, j2), promotion);
+        }
+
+        game
+    }
+
+    /// Play `n` random games of at most `RANDOM_GAMES_MAX_MOVES` half-moves each, see
+    /// `random_game()`. Each game gets its own seed derived from `seed`, so the same
+    /// `seed` always gives the same games.
+    pub fn random_games(n : usize, seed : u64) -> Vec<Game> {
+        let mut rng_state = seed;
+
+        (0..n).map(|_| Game::random_game(RANDOM_GAMES_MAX_MOVES, next_random(&mut rng_state))).collect()
+    }
+
+    /// Returns bool representing wether player `color` has any legal move in a given
+    /// position. Faster than `legal_move_count()`, since it returns as soon as a
+    /// piece with a legal move is found.
+    pub fn has_legal_moves(&mut self, color : Color) -> bool {
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j] {
+                    //i, j will always be a valid index, so unwrap is safe
+                    if piece.color == color && !self.legal_moves_from((i, j)).is_empty() {
+                        return true;
                     }
                 }
-
-                moves_made += 1;
             }
-        };
+        }
 
-        
-        return moves_vec;
+        if self.game_mode == GameMode::Crazyhouse {
+            return self.has_legal_drop(color);
+        }
+
+        false
     }
 
+    /// Get all legal moves for `color` that capture a piece, including en passant.
+    ///
+    /// # Returns
+    /// * `Vec` of tuples `(i1, j1, i2, j2)` where `(i1, j1)` is the index of the
+    ///   moving piece and `(i2, j2)` the index it moves to.
+    pub fn captures_only_moves(&mut self, color : Color) -> Vec<(usize, usize, usize, usize)> {
+        self.legal_moves_in_board_order(color)
+            .into_iter()
+            .filter(|&(i1, j1, i2, j2)| self.is_capture_move((i1, j1), (i2, j2)))
+            .collect()
+    }
 
-    /// # Panics
-    /// Function panics if there is not a piece at index i, j
-    /// 
-    /// Function should only be called thorugh get_pseudo_legal_moves_for_square() 
-    fn pawn_pseudo_legal_moves(&self, i : usize, j : usize, only_attacked : bool)-> Vec<(usize, usize)> {
-        let pawn_color = self.board[i][j].unwrap().color;
+    /// Get all legal capturing moves for `color`, for use in quiescence search.
+    /// Same as `captures_only_moves()`.
+    pub fn available_captures(&mut self, color : Color) -> Vec<(usize, usize, usize, usize)> {
+        self.captures_only_moves(color)
+    }
 
-        let d : i32 = match pawn_color {
-            Color::White => -1,
-            Color::Black => 1,
-        };
+    /// Returns bool representing wether the position is quiet, i.e. the active player
+    /// is not in check and has no legal captures. Used to find the leaf nodes of a
+    /// quiescence search.
+    pub fn is_quiet_position(&mut self) -> bool {
+        let color = self.turn;
 
-        let mut moves_vec : Vec<(usize, usize)> = Vec::new();
+        if self.in_check(color) {
+            return false;
+        }
 
-        let i_indx = i as i32 + d;
+        //most positions have no attacked enemy pieces, which avoids generating legal moves
+        let attacked_squares = self.get_attacked_squares(color);
+        let capture_possible = self.en_passant_square.is_some() || (0..8)
+            .flat_map(|i| (0..8).map(move |j| (i, j)))
+            .any(|(i, j)| {
+                matches!(self.board[i][j], Some(piece) if piece.color != color) && attacked_squares.contains(&(i, j))
+            });
 
-        if !only_attacked {
-            //check squares in front of the pawn
-            if is_valid_pos(i_indx, j as i32){
-                let i_indx = i_indx as usize;
-                //square 1 in front
-                if self.board[i_indx][j].is_none(){
-                    moves_vec.push((i_indx, j));
-    
-                    //2 squares in front
-                    //only possible if pawn is on 2nd or 7th rank depending on color
-                    match pawn_color {
-                        Color::White => {
-                            if i == 6 && self.board[4][j].is_none(){
-                                moves_vec.push((4, j));
-                            }
-                        },
-            
-                        Color::Black => {
-                            if i == 1 && self.board[3][j].is_none(){
-                                moves_vec.push((3, j));
-                            }
-                        },
-                    };
-                }
-            }
-            //check squares that the pawn can capture
-            if is_valid_pos(i_indx, (j + 1) as i32){
-                let i_indx = i_indx as usize;
-                if self.pawn_can_capture(i_indx, j + 1, pawn_color) {
-                    moves_vec.push((i_indx, j + 1))
-                }
-            }
-    
-            if is_valid_pos(i_indx, j as i32 - 1){
-                let i_indx = i_indx as usize;
-                if self.pawn_can_capture(i_indx, j - 1, pawn_color) {
-                    moves_vec.push((i_indx, j - 1))
-                }
-            }
-        } else {
-            //check squares that the pawn can capture
-            if is_valid_pos(i_indx, (j + 1) as i32){
-                let i_indx = i_indx as usize;
-                moves_vec.push((i_indx, j + 1))
-            }
-    
-            if is_valid_pos(i_indx, j as i32 - 1){
-                let i_indx = i_indx as usize;
-                moves_vec.push((i_indx, j - 1))
-            }
+        if !capture_possible {
+            return true;
         }
 
-        return moves_vec;
-    }
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j] {
+                    if piece.color != color {
+                        continue;
+                    }
 
-    fn pawn_can_capture(&self, i : usize, j : usize, pawn_color : Color) -> bool {
-        //checks if en passant is allowed
-        if let Some(en_passant_square) = self.en_passant_square{
-            if en_passant_square == (i, j){
-                match can_en_passant(i) {
-                    Some(color) => return color == pawn_color,
-                    None => return false,
-                }
-            }
-        } else {
-            //checks if pawn can move to given index
-            match self.board[i][j] {
-                None => (),
-                Some(piece) => {
-                    if piece.color != pawn_color {
-                        return true;
+                    //i, j will always be a valid index, so unwrap is safe
+                    for to in self.legal_moves_from((i, j)) {
+                        if self.is_capture_move((i, j), to) {
+                            return false;
+                        }
                     }
                 }
             }
         }
 
-        return false;
+        true
     }
 
-    /// # Panics
-    /// Function panics if there is not a piece at index i, j.
-    /// 
-    /// Will panic if Game.kingside_castle or Game.queenside_castle fields 
-    /// are missing values for Color::White or Color::Black, but this should
-    /// never be an issue when using constructors in the Game struct.
-    /// 
-    /// Function should only be called thorugh get_pseudo_legal_moves_for_square(), 
-    /// this will guarantee index i, j is a Piece.  
-    fn king_pseudo_legal_moves(&self, i : usize, j : usize, include_all_attacked : bool) -> Vec<(usize, usize)> {
-        let king_color = self.board[i][j].unwrap().color;
-        let mut move_vec = self.directional_pseudo_legal_moves(i, j, &self.queen_move_directions, 1, include_all_attacked);
+    /// Get all legal moves for the active player that capture on `square`, e.g.
+    /// every recapture after a capture has been made.
+    ///
+    /// # Returns
+    /// * `Vec` of tuples `(i1, j1, i2, j2)` where `(i1, j1)` is the index of the
+    ///   capturing piece and `(i2, j2)` is `square`. Empty if `square` is invalid.
+    pub fn recaptures_at(&mut self, square : (usize, usize)) -> Vec<(usize, usize, usize, usize)> {
+        let mut captures = Vec::new();
+
+        //only pieces attacking the square can capture on it
+        for from in self.attackers_of(square, self.turn) {
+            if self.is_capture_move(from, square) && self.is_legal_move_index(from, square) {
+                captures.push((from.0, from.1, square.0, square.1));
+            }
+        }
 
-        //king_color should always be a key in kingside_castle and queenside_castle
-        //unwrap is safe
-        let kingside = self.kingside_castle.get(&king_color).unwrap();
-        let queenside = self.queenside_castle.get(&king_color).unwrap();
+        captures
+    }
 
-        //castling logic
-        
-        if *kingside {
-            //checks if squares between king and rook are empty, and are not attacked
-            if self.board[i][j + 1].is_none() && self.board[i][j + 2].is_none() {
-                let attacked_squres = self.get_attacked_squares(king_color.opposite());
+    /// Get all legal moves for `color` that neither capture a piece nor promote a pawn.
+    ///
+    /// # Returns
+    /// * `Vec` of tuples `(i1, j1, i2, j2)` where `(i1, j1)` is the index of the
+    ///   moving piece and `(i2, j2)` the index it moves to.
+    pub fn quiet_moves_only(&mut self, color : Color) -> Vec<(usize, usize, usize, usize)> {
+        self.legal_moves_in_board_order(color)
+            .into_iter()
+            .filter(|&(i1, j1, i2, j2)| {
+                let is_pawn = self.board[i1][j1].unwrap().piece_type == PieceType::Pawn;
+
+                let is_promotion = is_pawn && self.is_promotion_move((i1, j1), (i2, j2));
+
+                !is_promotion && !self.is_capture_move((i1, j1), (i2, j2))
+            })
+            .collect()
+    }
 
-                if !attacked_squres.contains(&(i, j)) && !attacked_squres.contains(&(i, j + 1)) && !attacked_squres.contains(&(i, j + 2))
-                {
-                     move_vec.push((i, j + 2));
-                }
+    /// Check if a move is legal in the current position without making it.
+    ///
+    /// # Returns
+    /// * `bool` representing wether or not the piece at `from` belongs to the
+    ///   active player and can legally move to `to`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::new_starting_pos();
+    ///
+    /// assert!(game.is_legal_move_square(Square::from_alg("e2").unwrap(), Square::from_alg("e4").unwrap()));
+    /// assert!(!game.is_legal_move_square(Square::from_alg("e2").unwrap(), Square::from_alg("e5").unwrap()));
+    /// ```
+    pub fn is_legal_move_square(&self, from : Square, to : Square) -> bool {
+        self.is_legal_move_immutable(from.into(), to.into())
+    }
+
+    /// Same as `is_legal_move_square()`, but with array indicies.
+    ///
+    /// # Returns
+    /// * `bool`, same as `is_legal_move_square()`. Invalid indicies return `false`.
+    #[deprecated(note = "use `is_legal_move_square()` instead")]
+    pub fn is_legal_move(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        self.is_legal_move_immutable(from, to)
+    }
+
+    /// Same as `is_legal_move_square()`, but with array indicies. The game is not changed,
+    /// so the check can be made through a shared reference, e.g. from several threads.
+    /// The move is made on a copy of the position without its history.
+    ///
+    /// # Arguments
+    /// * `from` and `to` are array indicies, for more detail refer to `Game` struct.
+    ///
+    /// # Returns
+    /// * `bool`, same as `is_legal_move_square()`. Invalid indicies return `false`.
+    pub fn is_legal_move_immutable(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        if !is_valid_move(from, to) {
+            return false;
+        }
+
+        let color = match self.board[from.0][from.1] {
+            Some(piece) if piece.color == self.turn => piece.color,
+            _ => return false,
+        };
+
+        //from is validated, so unwrap is safe
+        if !self.get_pseudo_legal_moves_for_square(from.0, from.1, false).unwrap().contains(&to) {
+            return false;
+        }
+
+        let mut position = self.position_copy();
+        //both from and to are valid indicies, so unwrap is safe
+        position.make_move_with_index(from, to, false, true).unwrap();
+
+        !position.in_check(color)
+    }
+
+    // same as is_legal_move_immutable(), but makes the move on the game itself
+    // and undoes it, which is faster when the game can be changed
+    fn is_legal_move_index(&mut self, from : (usize, usize), to : (usize, usize)) -> bool {
+        if !is_valid_move(from, to) {
+            return false;
+        }
+
+        match self.board[from.0][from.1] {
+            Some(piece) if piece.color == self.turn => (),
+            _ => return false,
+        }
+
+        //from is validated, so unwrap is safe
+        self.legal_moves_from(from).contains(&to)
+    }
+
+    /// Check if a move is legal in the current position without making it,
+    /// using algebraic notation.
+    ///
+    /// # Returns
+    /// * `Result<bool, String>` where `Ok` contains wether or not the move is legal,
+    ///   see `is_legal_move_square()`.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if one or both of the provided positions is invalid notation
+    pub fn is_legal_move_alg(&self, from : &str, to : &str) -> Result<bool, String> {
+        Ok(self.is_legal_move_square(Square::from_alg(from)?, Square::from_alg(to)?))
+    }
+
+    /// Get the Standard Algebraic Notation (SAN) for a move in the current position.
+    /// The move is expected to be made by the active player, and is described
+    /// as it would be _before_ it is made.
+    ///
+    /// # Arguments
+    /// * `from` and `to` are array indicies, for more detail refer to `Game` struct.
+    ///
+    /// # Returns
+    /// * `Result<String, String>` where `Ok` contains the SAN of the move e.g. `"Nf3"`,
+    ///   `"exd5"`, `"Raxe1"` or `"O-O"`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// assert_eq!(game.move_to_san((7, 6), (5, 5)), Ok(String::from("Nf3")));
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if the move is not legal in the current position.
+    ///
+    /// # Notes
+    /// * If two pieces of the same type can reach the destination square, the
+    ///   file of the moving piece is added. If they share file, the rank is added
+    ///   instead, and if both are needed, both are added.
+    /// * Promotion moves are assumed to promote to a queen e.g. `"e8=Q"`, use
+    ///   `move_to_san_with_promotion()` to choose the piece.
+    /// * For details on SAN, refer to: https://en.wikipedia.org/wiki/Algebraic_notation_(chess)
+    pub fn move_to_san(&mut self, from : (usize, usize), to : (usize, usize)) -> Result<String, String> {
+        self.move_to_san_with_promotion(from, to, PieceType::Queen)
+    }
+
+    /// Get the move text of the game in Portable Game Notation (PGN), without headers,
+    /// e.g. `"1. e4 e5 2. Nf3 Nc6 3. Bb5 *"`. The text ends with the result of the game,
+    /// `"1-0"`, `"0-1"` or `"1/2-1/2"`, or `"*"` if the game is not over.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::from_san_list(&["e4", "e5", "Nf3"]).unwrap();
+    ///
+    /// assert_eq!(game.pgn_move_text(), Ok(String::from("1. e4 e5 2. Nf3 *")));
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if a move in the history can not be written in SAN.
+    ///
+    /// # Notes
+    /// * Only moves made through the Game object are included. If the game started
+    ///   from a FEN with black to move, the first move is written e.g. `"1... e5"`.
+    /// * Drops in `GameMode::Crazyhouse` are written e.g. `"N@e4"`.
+    pub fn pgn_move_text(&self) -> Result<String, String> {
+        self.pgn_move_text_internal(false, self.pgn_result())
+    }
+
+    /// Same as `pgn_move_text()`, but every annotated move is followed by its annotation
+    /// as a PGN comment, e.g. `"1. e4 { +0.35/2 } e5 *"`. See `annotate_last_move()`.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if a move in the history can not be written in SAN.
+    pub fn pgn_move_text_with_evaluations(&self) -> Result<String, String> {
+        self.pgn_move_text_internal(true, self.pgn_result())
+    }
+
+    /// Get the game in Portable Game Notation (PGN), with headers and move text. The
+    /// Seven Tag Roster is always written, with `"?"` for tags that have not been set,
+    /// followed by the other headers. Games that did not start from the standard starting
+    /// position also get the `SetUp` and `FEN` tags. Lines are at most 80 characters.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::from_san_list(&["e4", "e5"]).unwrap();
+    /// game.set_white_player("Alice");
+    ///
+    /// let pgn = game.to_pgn().unwrap();
+    ///
+    /// assert!(pgn.starts_with("[Event \"?\"]\n"));
+    /// assert!(pgn.ends_with("\n\n1. e4 e5 *\n"));
+    /// assert_eq!(Game::from_pgn(&pgn).unwrap().to_fen(), game.to_fen());
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if a move in the history can not be written in SAN.
+    ///
+    /// # Notes
+    /// * If the `Result` header has been set it is used to end the move text, otherwise
+    ///   the result is taken from the state of the game, see `pgn_move_text()`.
+    pub fn to_pgn(&self) -> Result<String, String> {
+        let result = self.get_pgn_header("Result").unwrap_or(self.pgn_result());
+        let mut pgn = String::new();
+
+        for name in ["Event", "Site", "Date", "Round", "White", "Black"] {
+            let default = if name == "Date" {"????.??.??"} else {"?"};
+            let value = self.get_pgn_header(name).unwrap_or(default);
+
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
+        }
+
+        pgn.push_str(&format!("[Result \"{}\"]\n", result));
+
+        //history always contains the current position, so unwrap is safe
+        let start_fen = self.history().first().unwrap().to_fen();
+
+        if start_fen != Game::new_starting_pos().to_fen() {
+            pgn.push_str(&format!("[SetUp \"1\"]\n[FEN \"{}\"]\n", start_fen));
+        }
+
+        let seven_tag_roster = ["Event", "Site", "Date", "Round", "White", "Black", "Result", "SetUp", "FEN"];
+
+        for (name, value) in &self.pgn_headers {
+            if !seven_tag_roster.contains(&name.as_str()) {
+                pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
             }
-        } 
+        }
 
-        if *queenside {
-            //checks if squares between king and rook are empty, and are not attacked
-            if self.board[i][j - 1].is_none() && self.board[i][j - 2].is_none() {
-                let attacked_squres = self.get_attacked_squares(king_color.opposite());
+        pgn.push('\n');
 
-                if !attacked_squres.contains(&(i, j)) && !attacked_squres.contains(&(i, j - 1)) && !attacked_squres.contains(&(i, j - 2))
-                {
-                     move_vec.push((i, j - 2));
-                }
+        let mut line = String::new();
+
+        for token in self.pgn_move_text_internal(false, result)?.split(' ') {
+            if !line.is_empty() && line.len() + 1 + token.len() > 80 {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
             }
+
+            line.push_str(token);
         }
 
-        return move_vec;
+        pgn.push_str(&line);
+        pgn.push('\n');
+
+        Ok(pgn)
     }
-    
-    /// Returns all squares under attack by `color`
-    fn get_attacked_squares(&self, color : Color) -> &Vec<(usize, usize)> {
-        match color {
-            Color::White => &(self.white_attacked_squares),
-            Color::Black => &(self.black_attacked_squares),
+
+    /// Get the value of the PGN tag pair with name `key`, e.g. `"Event"` or `"ECO"`.
+    ///
+    /// # Returns
+    /// * `Some(&str)` containing the value, or `None` if the tag has not been set.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// game.set_pgn_header("ECO", "C20");
+    ///
+    /// assert_eq!(game.get_pgn_header("ECO"), Some("C20"));
+    /// assert_eq!(game.get_pgn_header("Event"), None);
+    /// ```
+    pub fn get_pgn_header(&self, key : &str) -> Option<&str> {
+        self.pgn_headers.iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get every PGN tag pair of the game as `(name, value)`, in the order the
+    /// tags were first set. See `set_pgn_header()`.
+    pub fn get_pgn_headers(&self) -> &[(String, String)] {
+        &self.pgn_headers
+    }
+
+    /// Set the value of the PGN tag pair with name `key`. If the tag is already set,
+    /// its value is replaced and it keeps its position among the headers, otherwise
+    /// it is added last.
+    ///
+    /// # Notes
+    /// * Headers belong to the game rather than a position, so they are kept when
+    ///   moves are undone.
+    /// * Convenience methods exist for the Seven Tag Roster, e.g. `set_white_player()`.
+    ///   The `Result` tag is not checked against the state of the game.
+    pub fn set_pgn_header(&mut self, key : &str, value : &str) {
+        match self.pgn_headers.iter_mut().find(|(name, _)| name == key) {
+            Some((_, old_value)) => *old_value = value.to_string(),
+            None => self.pgn_headers.push((key.to_string(), value.to_string())),
         }
     }
 
-    /// Update `white_attacked_squares` and `black_attacked_squares` field
-    /// in the Game object.
-    fn update_attacked_squares(&mut self) {
-        let mut white_attack_vec : Vec<(usize, usize)> = Vec::new();
-        let mut black_attack_vec : Vec<(usize, usize)> = Vec::new();
+    /// Set the `Event` PGN tag, the name of the tournament or match.
+    pub fn set_event(&mut self, event : &str) {
+        self.set_pgn_header("Event", event);
+    }
 
-        for i in 0..8 {
-            for j in 0..8 {
-                //check is board[i][j] is some, else get_pseudo_legal_moves_for_square will panic
-                if let Some(piece) = self.board[i][j]{
-                    match piece.color{
-                        //get_pseudo_legal_moves_for_square will return Some(), since
-                        //board[i][j] is a Piece, so the unwrap is safe
-                        Color::White => white_attack_vec.append(&mut self.get_pseudo_legal_moves_for_square(i, j, true).unwrap()),
-                        Color::Black => black_attack_vec.append(&mut self.get_pseudo_legal_moves_for_square(i, j, true).unwrap()),
-                    }
+    /// Set the `Site` PGN tag, the location of the event.
+    pub fn set_site(&mut self, site : &str) {
+        self.set_pgn_header("Site", site);
+    }
+
+    /// Set the `Date` PGN tag, written as `"YYYY.MM.DD"` with `??` for unknown parts.
+    pub fn set_date(&mut self, date : &str) {
+        self.set_pgn_header("Date", date);
+    }
+
+    /// Set the `Round` PGN tag, the playing round of the game within the event.
+    pub fn set_round(&mut self, round : &str) {
+        self.set_pgn_header("Round", round);
+    }
+
+    /// Set the `White` PGN tag, the name of the player with the white pieces.
+    pub fn set_white_player(&mut self, name : &str) {
+        self.set_pgn_header("White", name);
+    }
+
+    /// Set the `Black` PGN tag, the name of the player with the black pieces.
+    pub fn set_black_player(&mut self, name : &str) {
+        self.set_pgn_header("Black", name);
+    }
+
+    /// Set the `Result` PGN tag, one of `"1-0"`, `"0-1"`, `"1/2-1/2"` or `"*"`.
+    pub fn set_result(&mut self, result : &str) {
+        self.set_pgn_header("Result", result);
+    }
+
+    /// Get the most recent move in Portable Game Notation (PGN) with its move number,
+    /// e.g. `"23. Nf3"` for a white move or `"23... Nf6+"` for a black move. Useful for
+    /// writing a PGN file one move at a time, see `pgn_move_text()` for the whole game.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::from_san_list(&["e4", "e5"]).unwrap();
+    ///
+    /// assert_eq!(game.last_move_pgn(), Ok(String::from("1... e5")));
+    ///
+    /// game.make_move("g1", "f3", true).unwrap();
+    ///
+    /// assert_eq!(game.last_move_pgn(), Ok(String::from("2. Nf3")));
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if no moves have been made, or if the move can not be
+    ///   written in SAN.
+    pub fn last_move_pgn(&self) -> Result<String, String> {
+        let previous = match self.previous_position() {
+            Some(previous) => previous,
+            None => return Err("No moves have been made".to_string()),
+        };
+
+        let san = self.last_move_san()?;
+
+        match previous.turn {
+            Color::White => Ok(format!("{}. {}", previous.full_moves, san)),
+            Color::Black => Ok(format!("{}... {}", previous.full_moves, san)),
+        }
+    }
+
+    // builds pgn move text ending with result, with annotation comments if with_evaluations is true
+    fn pgn_move_text_internal(&self, with_evaluations : bool, result : &str) -> Result<String, String> {
+        let history = self.history();
+        //history always contains the current position, so unwrap is safe
+        let first = history.first().unwrap();
+        let mut move_number = first.full_moves;
+        let mut turn = first.turn;
+
+        let mut text = String::new();
+
+        if turn == Color::Black && history.len() > 1 {
+            text.push_str(&format!("{}... ", move_number));
+        }
+
+        for (move_index, san) in self.san_history()?.iter().enumerate() {
+            if turn == Color::White {
+                text.push_str(&format!("{}. ", move_number));
+            }
+
+            text.push_str(san);
+            text.push(' ');
+
+            if with_evaluations {
+                if let Some(annotation) = self.get_annotation(move_index) {
+                    text.push_str(&annotation.to_pgn_comment());
+                    text.push(' ');
                 }
             }
+
+            if turn == Color::Black {
+                move_number += 1;
+            }
+
+            turn = turn.opposite();
         }
 
-        self.white_attacked_squares = white_attack_vec;
-        self.black_attacked_squares = black_attack_vec;
+        text.push_str(result);
+
+        Ok(text)
     }
 
-    /// Returns how many legal moves player `color` has in a given position.
-    fn num_of_legal_moves(&mut self, color : Color) -> u32 {
-        let mut res = 0;
+    // returns the SAN of every move made, starting with the first move
+    fn san_history(&self) -> Result<Vec<String>, String> {
+        let mut history = self.history();
+        let mut sans = Vec::new();
 
-        for moves in self.get_all_legal_moves(color).values(){
-            res += moves.len();
+        for i in 1..history.len() {
+            let (previous, next) = history.split_at_mut(i);
+            sans.push(previous[i - 1].san_of_move_to(&next[0])?);
         }
 
-        return res as u32;
+        Ok(sans)
     }
 
-    /// Checks if `color` has enough pieces to win.
-    fn can_win(&self, color : Color) -> bool {
+    // returns the SAN of the move that lead to the current position,
+    // must only be called when a move has been made
+    fn last_move_san(&self) -> Result<String, String> {
+        //caller guarantees a move has been made, so unwrap is safe
+        self.previous_position().unwrap().san_of_move_to(self)
+    }
 
-        let mut pieces = Vec::new();
+    // returns the SAN of the move that lead from this position to next
+    fn san_of_move_to(&mut self, next : &Game) -> Result<String, String> {
+        match next.last_move {
+            Some((from, to)) => {
+                let promotion = match next.board[to.0][to.1] {
+                    Some(piece) if self.is_promotion_move(from, to) => piece.piece_type,
+                    _ => PieceType::Queen,
+                };
 
-        for i in 0..8 {
-            for j in 0..8 {
-                if let Some(piece) = self.board[i][j]{
-                    if piece.color == color {
-                        pieces.push(piece.piece_type);
-                    }
+                self.move_to_san_with_promotion(from, to, promotion)
+            },
+            //the only square that was empty before a drop and occupied after it
+            None => self.drop_san(&next.board),
+        }
+    }
+
+    // returns the SAN of the drop that lead from the current board to board_after, e.g. "N@e4"
+    fn drop_san(&self, board_after : &[[Option<Piece>; 8]; 8]) -> Result<String, String> {
+        for (i, row) in board_after.iter().enumerate() {
+            for (j, square) in row.iter().enumerate() {
+                if let (None, Some(piece)) = (self.board[i][j], *square) {
+                    let letter = get_piece_notation(piece).to_ascii_uppercase();
+                    //i, j will always be a valid index, so unwrap is safe
+                    return Ok(format!("{}@{}", letter, indx_to_alg_notation((i, j)).unwrap()));
                 }
             }
         }
 
-        return !self.insufficient_material.contains(&pieces);
+        Err("Could not find move in history".to_string())
     }
-}
 
+    // returns the pgn result marker of the game
+    fn pgn_result(&self) -> &'static str {
+        match self.clone().get_state() {
+            GameState::Win(WinState::Checkmate(color))
+            | GameState::Win(WinState::ThreeChecks(color))
+            | GameState::Win(WinState::KingOfTheHill(color)) => match color {
+                Color::White => "1-0",
+                Color::Black => "0-1",
+            },
+            GameState::Win(WinState::Resignation(color)) => match color {
+                Color::White => "0-1",
+                Color::Black => "1-0",
+            },
+            GameState::Draw(_) => "1/2-1/2",
+            GameState::InProgress | GameState::AwaitPromotion => "*",
+        }
+    }
 
-/// Enum for representing the state of a chess game.
-/// 
-/// # Values
-/// * `InProgress`: The game is ongoing.
-/// * `AwaitPromotion`: Waiting for user to choose promotion piece. 
-/// * `Win(WinState)`: One player has won, which player won and how they won
-/// is defined in `WinState`.
-/// * `Draw`: Position is a draw, the cause for the draw is defined in `DrawState` 
-#[derive(Debug, Clone, PartialEq)]
-pub enum GameState {
-    InProgress,
-    AwaitPromotion,
-    Win(WinState),
-    Draw(DrawState),
-}
+    /// Get the Standard Algebraic Notation (SAN) for a move in the current position,
+    /// with `promotion` being the piece a pawn is promoted to e.g. `"dxe8=N"`.
+    /// `promotion` is ignored if the move is not a promotion move.
+    /// For more detail refer to `move_to_san()`.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if the move is not legal in the current position.
+    pub fn move_to_san_with_promotion(&mut self, from : (usize, usize), to : (usize, usize), promotion : PieceType) -> Result<String, String> {
+        if !self.is_legal_move_index(from, to) {
+            return Err(format!("Illegal move {:?} to {:?}", from, to));
+        }
+
+        //move is legal, so there is always a piece at from
+        let piece = self.board[from.0][from.1].unwrap();
+
+        //king moved two squares is castling, same as in make_move_with_index()
+        if piece.piece_type == PieceType::King {
+            let d = from.1 as i32 - to.1 as i32;
+
+            if d.abs() == 2 {
+                let mut san = if d < 0 {
+                    String::from("O-O")
+                } else {
+                    String::from("O-O-O")
+                };
+
+                self.append_check_annotation(from, to, promotion, &mut san);
+
+                return Ok(san);
+            }
+        }
+
+        let is_capture = self.is_capture_move(from, to);
+
+        //from and to are validated, so unwrap on indx_to_alg_notation is safe
+        let from_notation = indx_to_alg_notation(from).unwrap();
+        let to_notation = indx_to_alg_notation(to).unwrap();
+
+        let mut san = String::new();
+
+        if piece.piece_type == PieceType::Pawn {
+            //pawn captures are always written with the file of the pawn
+            if is_capture {
+                san.push_str(&from_notation[..1]);
+            }
+        } else {
+            san.push(get_piece_notation(piece).to_ascii_uppercase());
+            san.push_str(&self.san_disambiguation(from, to));
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+
+        san.push_str(&to_notation);
+
+        if piece.piece_type == PieceType::Pawn && self.is_promotion_move(from, to) {
+            san.push('=');
+            san.push(get_piece_notation(Piece::new(promotion, Color::White)));
+        }
+
+        self.append_check_annotation(from, to, promotion, &mut san);
+
+        Ok(san)
+    }
+
+    //helper function for move_to_san
+    //temporarily makes the move and appends "+" if it gives check,
+    //or "#" if it gives checkmate
+    fn append_check_annotation(&mut self, from : (usize, usize), to : (usize, usize), promotion : PieceType, san : &mut String) {
+        let opponent = self.turn.opposite();
+
+        //move is validated by move_to_san, so unwrap is safe
+        self.make_move_with_index(from, to, false, false).unwrap();
+        //does nothing if the move is not a promotion move
+        self.promote_to_piece(promotion);
+
+        if self.in_check(opponent) {
+            match self.get_state() {
+                GameState::Win(WinState::Checkmate(_)) => san.push('#'),
+                _ => san.push('+'),
+            }
+        }
+
+        self.undo_last_move();
+    }
+
+    /// Get the move described by a Standard Algebraic Notation (SAN) string
+    /// in the current position, for the active player.
+    ///
+    /// # Arguments
+    /// * `san` is a move in SAN e.g. `"e4"`, `"Nf3"`, `"exd5"`, `"Rfxe1"` or `"O-O"`.
+    ///   Check and checkmate suffixes (`+`, `#`) are accepted but not required.
+    ///
+    /// # Returns
+    /// * `Result` containing a tuple `(from, to)` of array indicies.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// assert_eq!(game.san_to_move("Nf3"), Ok(((7, 6), (5, 5))));
+    /// ```
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if the SAN is invalid, illegal or ambiguous.
+    pub fn san_to_move(&mut self, san : &str) -> Result<IndexMove, String> {
+        let mut stripped = san.trim().trim_end_matches(['+', '#']);
+
+        //the promotion piece does not affect which squares are moved between
+        if let Some((body, promotion)) = stripped.split_once('=') {
+            if !["N", "B", "R", "Q"].contains(&promotion) {
+                return Err(format!("Invalid promotion {}", promotion));
+            }
+            stripped = body;
+        }
+
+        //castling is written as a king move, "0-0" is accepted as a common variant
+        let castling_file = match stripped {
+            "O-O" | "0-0" => Some(6),
+            "O-O-O" | "0-0-0" => Some(2),
+            _ => None,
+        };
+
+        if let Some(file) = castling_file {
+            let rank = match self.turn {
+                Color::White => 7,
+                Color::Black => 0,
+            };
+
+            if self.is_legal_move_index((rank, 4), (rank, file))
+                && self.board[rank][4] == Some(Piece::new(PieceType::King, self.turn))
+            {
+                return Ok(((rank, 4), (rank, file)));
+            }
+
+            return Err(format!("Illegal SAN {}", san));
+        }
+
+        let (piece_type, rest) = match stripped.chars().next() {
+            Some('N') => (PieceType::Knight, &stripped[1..]),
+            Some('B') => (PieceType::Bishop, &stripped[1..]),
+            Some('R') => (PieceType::Rook, &stripped[1..]),
+            Some('Q') => (PieceType::Queen, &stripped[1..]),
+            Some('K') => (PieceType::King, &stripped[1..]),
+            Some(_) => (PieceType::Pawn, stripped),
+            None => return Err(format!("Invalid SAN {}", san)),
+        };
+
+        if rest.len() < 2 || !rest.is_ascii() {
+            return Err(format!("Invalid SAN {}", san));
+        }
+
+        let to = alg_notation_to_indx(&rest[rest.len() - 2..])?;
+
+        //remaining characters are the capture sign and disambiguation
+        let mut from_file = None;
+        let mut from_rank = None;
+
+        for c in rest[..rest.len() - 2].chars() {
+            match c {
+                'a'..='h' => from_file = Some(c as usize - 'a' as usize),
+                '1'..='8' => from_rank = Some(8 - c.to_digit(10).unwrap() as usize),
+                'x' => (),
+                _c => return Err(format!("Invalid SAN {}", san)),
+            }
+        }
+
+        let mut candidates = Vec::new();
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if self.board[i][j] != Some(Piece::new(piece_type, self.turn))
+                    || from_rank.is_some_and(|rank| rank != i)
+                    || from_file.is_some_and(|file| file != j)
+                {
+                    continue;
+                }
+
+                if self.is_legal_move_index((i, j), to) {
+                    candidates.push((i, j));
+                }
+            }
+        }
+
+        match candidates.len() {
+            0 => Err(format!("Illegal SAN {}", san)),
+            1 => Ok((candidates[0], to)),
+            _ => Err(format!("Ambiguous SAN {}", san)),
+        }
+    }
+
+    //helper function for move_to_san
+    //returns the file and/or rank needed to tell the piece at `from` apart
+    //from other pieces of the same type that can also move to `to`
+    fn san_disambiguation(&mut self, from : (usize, usize), to : (usize, usize)) -> String {
+        //function is only called with a piece at from, so unwrap is safe
+        let piece = self.board[from.0][from.1].unwrap();
+
+        let mut others = Vec::new();
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if (i, j) == from || self.board[i][j] != Some(piece) {
+                    continue;
+                }
+                //i, j will always be a valid index, so unwrap is safe
+                if self.legal_moves_from((i, j)).contains(&to) {
+                    others.push((i, j));
+                }
+            }
+        }
+
+        //from is valid, so unwrap is safe
+        let from_notation = indx_to_alg_notation(from).unwrap();
+
+        if others.is_empty() {
+            return String::new();
+        }
+
+        if others.iter().all(|other| other.1 != from.1) {
+            return from_notation[..1].to_string();
+        }
+
+        if others.iter().all(|other| other.0 != from.0) {
+            return from_notation[1..].to_string();
+        }
+
+        from_notation
+    }
+
+    /// Returns bool representing wether a player is in check or not.
+    pub fn in_check(&self, color : Color) -> bool {
+        let attacked_squares = self.get_attacked_squares(color.opposite());
+
+        match self.find_king(color) {
+            Some(king_pos) => attacked_squares.contains(&king_pos),
+            None => false,
+        }
+    }
+
+    /// Get the position of every piece giving check to the king of `color`.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies of the checking pieces, empty if `color` is not
+    ///   in check. Contains two indicies in case of a double check.
+    pub fn get_checkers(&self, color : Color) -> Vec<(usize, usize)> {
+        let mut checkers = Vec::new();
+
+        let king_pos = match self.find_king(color) {
+            Some(pos) => pos,
+            None => return checkers,
+        };
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j] {
+                    //i, j will always be a valid index, so unwrap is safe
+                    if piece.color != color
+                        && self.get_pseudo_legal_moves_for_square(i, j, true).unwrap().contains(&king_pos)
+                    {
+                        checkers.push((i, j));
+                    }
+                }
+            }
+        }
+
+        checkers
+    }
+
+    /// Get the position of every piece of `color` that attacks `square`, including
+    /// pieces defending a piece of their own color on `square`.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies of the attacking pieces, empty if `square` is invalid.
+    pub fn attackers_of(&self, square : (usize, usize), color : Color) -> Vec<(usize, usize)> {
+        let mut attackers = Vec::new();
+
+        if !is_valid_index(square) {
+            return attackers;
+        }
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j] {
+                    //i, j will always be a valid index, so unwrap is safe
+                    if piece.color == color
+                        && self.get_pseudo_legal_moves_for_square(i, j, true).unwrap().contains(&square)
+                    {
+                        attackers.push((i, j));
+                    }
+                }
+            }
+        }
+
+        attackers
+    }
+
+    /// Static exchange evaluation (SEE) of a capture. Plays out the sequence of
+    /// captures on `square`, starting with the piece on `attacker_square`, where each
+    /// side recaptures with its least valuable attacker and may stop capturing
+    /// whenever continuing would lose material.
+    ///
+    /// # Arguments
+    /// * `square` is the array index of the square captured on.
+    /// * `attacker_square` is the array index of the piece making the first capture.
+    ///
+    /// # Returns
+    /// * `i32` representing the material gained by the side making the first capture
+    ///   in centipawns, see `piece_value()`. Negative if the exchange loses material.
+    ///   Returns `0` if either index is invalid or there is no piece at `attacker_square`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// //pawn on e4 takes knight on d5, which is defended by the pawn on c6
+    /// let game = Game::from_fen("4k3/8/2p5/3n4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    ///
+    /// assert_eq!(game.see((3, 3), (4, 4)), 220);
+    /// ```
+    ///
+    /// # Notes
+    /// * Pieces behind an attacker on the same line (x-rays) are taken into account.
+    /// * Pins and checks are ignored.
+    pub fn see(&self, square : (usize, usize), attacker_square : (usize, usize)) -> i32 {
+        if !is_valid_move(attacker_square, square) {
+            return 0;
+        }
+
+        let attacker = match self.board[attacker_square.0][attacker_square.1] {
+            Some(piece) => piece,
+            None => return 0,
+        };
+
+        //the captures are played out on a copy of the board, so that
+        //sliding pieces behind a capturing piece will attack the square
+        let mut game = self.clone();
+
+        //gain[d] is the material balance for the side making capture d,
+        //if the sequence were to stop after that capture
+        let mut gain = vec![self.board[square.0][square.1].map_or(0, |p| piece_value(p.piece_type))];
+
+        let mut piece_on_square = attacker;
+        game.board[square.0][square.1] = Some(attacker);
+        game.board[attacker_square.0][attacker_square.1] = None;
+
+        let mut side = attacker.color.opposite();
+
+        loop {
+            let least_valuable = game.attackers_of(square, side)
+                .into_iter()
+                //attackers_of only returns squares with pieces, so unwrap is safe
+                .min_by_key(|&(i, j)| piece_value(game.board[i][j].unwrap().piece_type));
+
+            let (i, j) = match least_valuable {
+                Some(pos) => pos,
+                None => break,
+            };
+
+            gain.push(piece_value(piece_on_square.piece_type) - gain[gain.len() - 1]);
+
+            piece_on_square = game.board[i][j].unwrap();
+            game.board[square.0][square.1] = Some(piece_on_square);
+            game.board[i][j] = None;
+
+            side = side.opposite();
+        }
+
+        //each side only continues the sequence if it gains from it
+        for d in (1..gain.len()).rev() {
+            gain[d - 1] = -(-gain[d - 1]).max(gain[d]);
+        }
+
+        gain[0]
+    }
+
+    /// Returns bool representing wether capturing the piece on `to` with the piece on
+    /// `from` is at worst an even trade, i.e. the captured piece is worth at least as
+    /// much as the capturing piece. A cheap filter to run before `see()`.
+    ///
+    /// # Notes
+    /// * Recaptures and legality of the move are not considered.
+    /// * Returns `false` if either index is invalid or either square is empty, so
+    ///   en passant captures are not counted.
+    pub fn is_capture_profitable(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        match self.capture_pieces(from, to) {
+            Some((attacker, captured)) => piece_value(captured.piece_type) >= piece_value(attacker.piece_type),
+            None => false,
+        }
+    }
+
+    /// Returns bool representing wether the piece on `to` can be captured by the piece
+    /// on `from` and is not defended, so the capture wins material whatever follows.
+    ///
+    /// # Notes
+    /// * Legality of the move is not checked, see `is_capture_profitable()`.
+    pub fn is_hanging_capture(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        match self.capture_pieces(from, to) {
+            Some((_, captured)) => self.attackers_of(to, captured.color).is_empty(),
+            None => false,
+        }
+    }
+
+    // returns the capturing and captured piece of a capture between opponents,
+    // or None if either index is invalid or either square is empty
+    fn capture_pieces(&self, from : (usize, usize), to : (usize, usize)) -> Option<(Piece, Piece)> {
+        if !is_valid_move(from, to) {
+            return None;
+        }
+
+        match (self.board[from.0][from.1], self.board[to.0][to.1]) {
+            (Some(attacker), Some(captured)) if attacker.color != captured.color => Some((attacker, captured)),
+            _ => None,
+        }
+    }
+
+    /// Play out every capture and recapture on `square`, where each side captures with
+    /// its least valuable attacker until no attackers are left. Unlike `see()`, neither
+    /// side stops early, so the whole sequence can be shown e.g. in a teaching tool.
+    ///
+    /// # Returns
+    /// * `Vec` of `(capturing_piece, material_gain)` in the order the captures are made,
+    ///   where `material_gain` is the running material balance in centipawns for the side
+    ///   making the first capture, see `piece_value()`. The first capture is made by the
+    ///   opponent of the piece on `square`, or by the active player if `square` is empty.
+    ///   Empty if `square` is invalid or the first side can not capture on it.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// //rook takes pawn on d5, the pawn on c6 recaptures
+    /// let game = Game::from_fen("4k3/8/2p5/3p4/8/8/8/3RK3 w - - 0 1").unwrap();
+    ///
+    /// assert_eq!(game.analyze_capture_sequence((3, 3)), vec![
+    ///     (Piece::new(PieceType::Rook, Color::White), 100),
+    ///     (Piece::new(PieceType::Pawn, Color::Black), -400),
+    /// ]);
+    /// ```
+    ///
+    /// # Notes
+    /// * Pieces behind an attacker on the same line (x-rays) are taken into account.
+    /// * Pins and checks are ignored, as in `see()`.
+    pub fn analyze_capture_sequence(&self, square : (usize, usize)) -> Vec<(Piece, i32)> {
+        let mut sequence = Vec::new();
+
+        if !is_valid_index(square) {
+            return sequence;
+        }
+
+        let first_side = match self.board[square.0][square.1] {
+            Some(piece) => piece.color.opposite(),
+            None => self.turn,
+        };
+
+        //the captures are played out on a copy of the board, so that
+        //sliding pieces behind a capturing piece will attack the square
+        let mut game = self.clone();
+        let mut side = first_side;
+        let mut material_gain = 0;
+
+        loop {
+            let least_valuable = game.attackers_of(square, side)
+                .into_iter()
+                //attackers_of only returns squares with pieces, so unwrap is safe
+                .min_by_key(|&(i, j)| piece_value(game.board[i][j].unwrap().piece_type));
+
+            let (i, j) = match least_valuable {
+                Some(pos) => pos,
+                None => break,
+            };
+
+            let captured_value = game.board[square.0][square.1].map_or(0, |p| piece_value(p.piece_type));
+
+            if side == first_side {
+                material_gain += captured_value;
+            } else {
+                material_gain -= captured_value;
+            }
+
+            let capturing_piece = game.board[i][j].unwrap();
+            game.board[square.0][square.1] = Some(capturing_piece);
+            game.board[i][j] = None;
+
+            sequence.push((capturing_piece, material_gain));
+            side = side.opposite();
+        }
+
+        sequence
+    }
+
+    /// Check if a move gives check to the opponent, without making it.
+    /// The move is made on a copy of the game, pawns are promoted to a queen.
+    ///
+    /// # Returns
+    /// * `bool` representing wether or not the opponent is in check after the move.
+    ///   Returns `false` if `from` or `to` is invalid or if there is no piece at `from`.
+    ///
+    /// # Notes
+    /// * Legality of the move is not checked.
+    pub fn move_gives_check(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        match self.game_after_move(from, to) {
+            Some((game, opponent)) => game.in_check(opponent),
+            None => false,
+        }
+    }
+
+    /// Check if a move gives double check to the opponent, i.e. two pieces give check
+    /// at the same time. For more detail refer to `move_gives_check()`.
+    pub fn move_gives_double_check(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        match self.game_after_move(from, to) {
+            Some((game, opponent)) => game.get_checkers(opponent).len() >= 2,
+            None => false,
+        }
+    }
+
+    /// Check if a move is a discovered attack, i.e. moving the piece at `from` uncovers
+    /// an attack on an opponent piece by a rook, bishop or queen behind it.
+    ///
+    /// # Returns
+    /// * `bool` representing wether or not the move uncovers an attack. Returns `false`
+    ///   if `from` or `to` is invalid or if there is no piece at `from`.
+    ///
+    /// # Notes
+    /// * Legality of the move is not checked.
+    pub fn move_is_discovery(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        !self.discovered_targets(from, to).is_empty()
+    }
+
+    /// Check if a move is a discovered check, i.e. moving the piece at `from` uncovers
+    /// an attack on the opponent king by a rook, bishop or queen behind it.
+    /// For more detail refer to `move_is_discovery()`.
+    pub fn move_is_discovered_check(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        self.discovered_targets(from, to)
+            .into_iter()
+            .any(|(i, j)| self.board[i][j].unwrap().piece_type == PieceType::King)
+    }
+
+    /// Check if a move is a fork, i.e. the moved piece attacks two or more opponent
+    /// pieces after the move. The move is made on a copy of the game.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies of the attacked opponent pieces, including the king,
+    ///   in board order. Empty if fewer than two pieces are attacked, or if `from` or `to`
+    ///   is invalid or there is no piece at `from`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// //knight on c7 forks king on e8 and rook on a8
+    /// let game = Game::from_fen("r3k3/8/8/1N6/8/8/8/4K3 w - - 0 1").unwrap();
+    ///
+    /// assert_eq!(game.move_is_fork((3, 1), (1, 2)), vec![(0, 0), (0, 4)]);
+    /// ```
+    ///
+    /// # Notes
+    /// * Legality of the move is not checked.
+    pub fn move_is_fork(&self, from : (usize, usize), to : (usize, usize)) -> Vec<(usize, usize)> {
+        let (game, opponent) = match self.game_after_move(from, to) {
+            Some(res) => res,
+            None => return Vec::new(),
+        };
+
+        //to always contains the moved piece, so unwrap is safe
+        let mut targets : Vec<(usize, usize)> = game.get_pseudo_legal_moves_for_square(to.0, to.1, true)
+            .unwrap()
+            .into_iter()
+            .filter(|&(i, j)| game.board[i][j].is_some_and(|piece| piece.color == opponent))
+            .collect();
+
+        if targets.len() < 2 {
+            return Vec::new();
+        }
+
+        targets.sort();
+
+        targets
+    }
+
+    /// Check if a move pins opponent pieces to their king, either with the moved
+    /// piece or by uncovering a rook, bishop or queen. The move is made on a copy of the game.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies of opponent pieces that are pinned after the move
+    ///   but not before it, in board order. Empty if the move creates no pins, or if
+    ///   `from` or `to` is invalid or there is no piece at `from`.
+    ///
+    /// # Notes
+    /// * Legality of the move is not checked.
+    pub fn move_is_pin(&self, from : (usize, usize), to : (usize, usize)) -> Vec<(usize, usize)> {
+        let (game, opponent) = match self.game_after_move(from, to) {
+            Some(res) => res,
+            None => return Vec::new(),
+        };
+
+        let pinned_before = self.pinned_pieces(opponent);
+
+        game.pinned_pieces(opponent)
+            .into_iter()
+            .filter(|square| !pinned_before.contains(square))
+            .collect()
+    }
+
+    /// Check if a move is a skewer, i.e. after the move a rook, bishop or queen attacks
+    /// an opponent piece with a less valuable opponent piece behind it on the same line.
+    /// Both the moved piece and pieces uncovered by the move are considered.
+    /// The move is made on a copy of the game.
+    ///
+    /// # Returns
+    /// * `bool` representing wether or not the move creates a skewer. Returns `false` if
+    ///   `from` or `to` is invalid or if there is no piece at `from`.
+    ///
+    /// # Notes
+    /// * Legality of the move is not checked.
+    /// * Piece values are compared using `piece_value()`.
+    pub fn move_is_skewer(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        let (game, opponent) = match self.game_after_move(from, to) {
+            Some(res) => res,
+            None => return false,
+        };
+
+        for i in 0..8 {
+            for j in 0..8 {
+                let piece = match game.board[i][j] {
+                    Some(piece) if piece.color != opponent => piece,
+                    _ => continue,
+                };
+
+                let directions = match piece.piece_type {
+                    PieceType::Rook => &self.rook_move_directions,
+                    PieceType::Bishop => &self.bishop_move_directions,
+                    PieceType::Queen => &self.queen_move_directions,
+                    _ => continue,
+                };
+
+                //only the moved piece and uncovered pieces can create a new skewer
+                let is_affected = (i, j) == to || self.board[i][j] != game.board[i][j]
+                    || self.get_pseudo_legal_moves_for_square(i, j, true) != game.get_pseudo_legal_moves_for_square(i, j, true);
+
+                if is_affected && game.skewers_along(i, j, directions) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Check if the piece at `square` is pinned to its own king by an opponent
+    /// rook, bishop or queen, i.e. moving it off the line would expose the king.
+    ///
+    /// # Returns
+    /// * `bool` representing wether or not the piece is pinned. Returns `false` if
+    ///   `square` is invalid or empty.
+    pub fn is_pinned(&self, square : (usize, usize)) -> bool {
+        if !is_valid_index(square) {
+            return false;
+        }
+
+        match self.board[square.0][square.1] {
+            Some(piece) => self.pinned_pieces(piece.color).contains(&square),
+            None => false,
+        }
+    }
+
+    //helper function for move_is_discovery() and move_is_discovered_check()
+    //returns the position of every opponent piece that a sliding piece,
+    //other than the moved one, attacks after the move but not before it
+    fn discovered_targets(&self, from : (usize, usize), to : (usize, usize)) -> Vec<(usize, usize)> {
+        let mut targets = Vec::new();
+
+        if !is_valid_move(from, to) {
+            return targets;
+        }
+
+        let color = match self.board[from.0][from.1] {
+            Some(piece) => piece.color,
+            None => return targets,
+        };
+
+        //only the pieces are moved, the rest of the game state does not
+        //affect which squares a sliding piece attacks
+        let mut after = self.clone();
+        after.board[to.0][to.1] = after.board[from.0][from.1];
+        after.board[from.0][from.1] = None;
+
+        for i in 0..8 {
+            for j in 0..8 {
+                let is_slider = match self.board[i][j] {
+                    Some(piece) => piece.color == color && matches!(piece.piece_type, PieceType::Rook | PieceType::Bishop | PieceType::Queen),
+                    None => false,
+                };
+
+                if !is_slider || (i, j) == from {
+                    continue;
+                }
+
+                //i, j is a piece on both boards, so unwrap is safe
+                let attacks_before = self.get_pseudo_legal_moves_for_square(i, j, true).unwrap();
+                let attacks_after = after.get_pseudo_legal_moves_for_square(i, j, true).unwrap();
+
+                for square in attacks_after {
+                    let is_opponent = after.board[square.0][square.1].is_some_and(|piece| piece.color != color);
+
+                    if is_opponent && square != to && !attacks_before.contains(&square) && !targets.contains(&square) {
+                        targets.push(square);
+                    }
+                }
+            }
+        }
+
+        targets
+    }
+
+    //helper function for move predicates
+    //returns a copy of the game with the move made, and the color of the opponent
+    //of the moving piece. Returns None if there is no piece to move
+    fn game_after_move(&self, from : (usize, usize), to : (usize, usize)) -> Option<(Game, Color)> {
+        if !is_valid_move(from, to) {
+            return None;
+        }
+
+        let piece = self.board[from.0][from.1]?;
+
+        let mut game = self.clone();
+        //indicies are validated and there is a piece at from, so unwrap is safe
+        game.make_move_with_index(from, to, false, true).unwrap();
+
+        Some((game, piece.color.opposite()))
+    }
+
+    /// Returns current state of the game. For possible game states,
+    /// refer to documentation for `GameState` enum.
+    pub fn get_state(&mut self) -> GameState{
+        if let Some(color) = self.resigned {
+            return GameState::Win(WinState::Resignation(color));
+        }
+
+        if self.game_mode == GameMode::ThreeCheck {
+            for color in [Color::White, Color::Black] {
+                if self.checks_given(color) >= 3 {
+                    return GameState::Win(WinState::ThreeChecks(color));
+                }
+            }
+        }
+
+        if self.game_mode == GameMode::KingOfTheHill {
+            for color in [Color::White, Color::Black] {
+                if let Some(king_pos) = self.find_king(color) {
+                    if HILL_SQUARES.contains(&king_pos) {
+                        return GameState::Win(WinState::KingOfTheHill(color));
+                    }
+                }
+            }
+        }
+
+        if self.promotion_square.is_some() {
+            return GameState::AwaitPromotion;
+        }
+
+        if !self.has_legal_moves(self.turn) {
+            if self.in_check(self.turn) {
+                return GameState::Win(WinState::Checkmate(self.turn.opposite()));
+            } else {
+                return GameState::Draw(DrawState::Stalemate);
+            }
+        }
+
+        if self.half_moves >= 100 {
+            return GameState::Draw(DrawState::FiftyMoveRule);
+        }
+
+        if self.is_threefold_repetition() {
+            return GameState::Draw(DrawState::ThreefoldRepetition);
+        }
+
+        //in crazyhouse captured pieces can be dropped again, so material never runs out
+        //and in king of the hill a lone king can still win by reaching the center
+        let material_matters = !matches!(self.game_mode, GameMode::Crazyhouse | GameMode::KingOfTheHill);
+
+        if material_matters && !self.can_win(Color::White) && !self.can_win(Color::Black) {
+            return GameState::Draw(DrawState::InsufficientMaterial);
+        }
+
+        GameState::InProgress
+    }
+
+    /// Returns bool representing wether the game is over, i.e. `get_state()` is
+    /// a win or a draw. A game awaiting promotion is not over.
+    pub fn is_terminal(&mut self) -> bool {
+        self.terminal_value().is_some()
+    }
+
+    /// Returns the result of a finished game from white's perspective.
+    ///
+    /// # Returns
+    /// * `Some(1.0)` if white has won, `Some(-1.0)` if black has won, `Some(0.0)`
+    ///   if the game is a draw and `None` if the game is not over.
+    pub fn terminal_value(&mut self) -> Option<f32> {
+        match self.get_state() {
+            GameState::InProgress | GameState::AwaitPromotion => None,
+            GameState::Win(WinState::Checkmate(Color::White)) => Some(1.0),
+            GameState::Win(WinState::Checkmate(Color::Black)) => Some(-1.0),
+            GameState::Win(WinState::Resignation(Color::White)) => Some(-1.0),
+            GameState::Win(WinState::Resignation(Color::Black)) => Some(1.0),
+            GameState::Win(WinState::ThreeChecks(Color::White)) => Some(1.0),
+            GameState::Win(WinState::ThreeChecks(Color::Black)) => Some(-1.0),
+            GameState::Win(WinState::KingOfTheHill(Color::White)) => Some(1.0),
+            GameState::Win(WinState::KingOfTheHill(Color::Black)) => Some(-1.0),
+            GameState::Draw(_) => Some(0.0),
+        }
+    }
+
+    /// Returns the reason the position is a draw, or `None` if it is not a draw.
+    /// Covers every draw detected by `get_state()`, see `DrawState` for
+    /// possible reasons.
+    pub fn draw_reason(&mut self) -> Option<DrawState> {
+        match self.get_state() {
+            GameState::Draw(draw_state) => Some(draw_state),
+            _ => None,
+        }
+    }
+
+    /// Returns bool representing wether the position is a draw, for any reason.
+    /// See `draw_reason()`.
+    pub fn is_drawn_position(&mut self) -> bool {
+        self.draw_reason().is_some()
+    }
+
+    /// Resign the game for `color`. After resigning `get_state()` returns
+    /// `GameState::Win(WinState::Resignation(color))` and no more moves can be made.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// game.resign(Color::White);
+    ///
+    /// assert_eq!(game.get_state(), GameState::Win(WinState::Resignation(Color::White)));
+    /// assert_eq!(game.make_move("e2", "e4", true), Ok(false));
+    /// ```
+    ///
+    /// # Notes
+    /// * Only the first resignation is recorded, resigning again has no effect.
+    pub fn resign(&mut self, color : Color) {
+        if self.resigned.is_none() {
+            self.resigned = Some(color);
+        }
+    }
+
+    /// Set the rules the game is played with, see `GameMode`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// game.set_game_mode(GameMode::Crazyhouse);
+    /// ```
+    pub fn set_game_mode(&mut self, game_mode : GameMode) {
+        self.game_mode = game_mode;
+    }
+
+    /// Returns the rules the game is played with, see `GameMode`.
+    pub fn get_game_mode(&self) -> GameMode {
+        self.game_mode
+    }
+
+    /// Returns the number of times `color` has given check during the game.
+    /// Checks are counted in every game mode, but only decide the game in
+    /// `GameMode::ThreeCheck`.
+    ///
+    /// # Notes
+    /// * Only records checks given through the Game object. Positions generated
+    ///   from FEN will always start with no checks given.
+    pub fn checks_given(&self, color : Color) -> u32 {
+        //color is always a key in checks_delivered, so unwrap is safe
+        *self.checks_delivered.get(&color).unwrap()
+    }
+
+    /// Returns the number of checks `color` has delivered during the game.
+    /// Same as `checks_given()`.
+    pub fn checks_delivered(&self, color : Color) -> u32 {
+        self.checks_given(color)
+    }
+
+    /// Get the pieces `color` holds in reserve and can drop onto the board.
+    /// Only used in `GameMode::Crazyhouse`.
+    ///
+    /// # Returns
+    /// * `HashMap` with the number of pieces of each type, piece types with no
+    ///   pieces in reserve may be missing or have the count `0`.
+    pub fn get_reserve(&self, color : Color) -> HashMap<PieceType, u32> {
+        //color is always a key in drop_reserve, so unwrap is safe
+        self.drop_reserve.get(&color).unwrap().clone()
+    }
+
+    /// Drop a piece from the active player's reserve onto the board. Only
+    /// allowed in `GameMode::Crazyhouse`, where captured pieces are added to
+    /// the reserve of the capturing player.
+    ///
+    /// # Arguments
+    /// * `piece_type` is the type of piece to drop.
+    /// * `to` is the array index of the square the piece is dropped on,
+    ///   for more detail refer to `Game` struct.
+    ///
+    /// # Returns
+    /// * `Result<bool, String>` where `Ok` contains a `bool` representing wether
+    ///   the drop was legal. A drop is illegal if the piece is not in reserve, the square
+    ///   is occupied, a pawn is dropped on the first or last rank, or the drop leaves
+    ///   the active player in check.
+    ///
+    /// # Errors
+    /// * Returns `Err` if the game is not played in crazyhouse mode, or if `to`
+    ///   is an invalid index.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// game.set_game_mode(GameMode::Crazyhouse);
+    ///
+    /// //no pieces in reserve
+    /// assert_eq!(game.drop_piece(PieceType::Knight, (4, 4)), Ok(false));
+    /// ```
+    pub fn drop_piece(&mut self, piece_type : PieceType, to : (usize, usize)) -> Result<bool, String> {
+        if self.game_mode != GameMode::Crazyhouse {
+            return Err("Pieces can only be dropped in crazyhouse".to_string());
+        }
+
+        if !is_valid_index(to) {
+            return Err(format!("Invalid index {:?}", to));
+        }
+
+        let color = self.turn;
+
+        if self.resigned.is_some() || self.promotion_square.is_some() || !self.drop_is_legal(piece_type, to, color) {
+            return Ok(false);
+        }
+
+        //save board state
+        let record = self.undo_record(None, to, Piece::new(piece_type, color));
+        self.undo_stack.push(record);
+        self.last_move = None;
+
+        self.zobrist_hash ^= self.zobrist_state_key();
+
+        //drop_is_legal() checks that the piece is in reserve, so unwrap is safe
+        *self.drop_reserve.get_mut(&color).unwrap().get_mut(&piece_type).unwrap() -= 1;
+        self.set_square(to, Some(Piece::new(piece_type, color)));
+
+        self.half_moves += 1;
+        self.update_attacked_squares();
+        self.record_check(color);
+
+        if color == Color::Black {
+            self.full_moves += 1;
+        }
+
+        self.en_passant_square = None;
+        self.turn = color.opposite();
+        self.zobrist_hash ^= self.zobrist_state_key();
+
+        Ok(true)
+    }
+
+    /// Attach a clock to the game, replacing any previous clock. The clock
+    /// is stopped until `start_clock()` is called.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    /// game.attach_clock(Clock::new(180_000, 2_000));
+    ///
+    /// game.start_clock();
+    /// game.make_move_timed("e2", "e4", true).unwrap(); //switches the clock to black
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn attach_clock(&mut self, clock : Clock) {
+        self.clock = Some(clock);
+    }
+
+    /// Start the clock of the active player. Does nothing if no clock is attached
+    /// or the clock is already running.
+    #[cfg(feature = "std")]
+    pub fn start_clock(&mut self) {
+        let turn = self.turn;
+
+        if let Some(clock) = &mut self.clock {
+            if clock.last_move_start.is_none() {
+                clock.last_move_start = Some((turn, Instant::now()));
+            }
+        }
+    }
+
+    /// Stop the clock, deducting the time used from the player whose clock was
+    /// running and adding the increment, unless the player has run out of time.
+    /// Does nothing if no clock is attached or the clock is not running.
+    #[cfg(feature = "std")]
+    pub fn stop_clock(&mut self) {
+        if let Some(clock) = &mut self.clock {
+            if let Some((color, _)) = clock.last_move_start {
+                let mut remaining = clock.remaining(color);
+
+                if remaining > 0 {
+                    remaining += clock.increment_ms;
+                }
+
+                match color {
+                    Color::White => clock.white_ms = remaining,
+                    Color::Black => clock.black_ms = remaining,
+                }
+
+                clock.last_move_start = None;
+            }
+        }
+    }
+
+    /// Returns the time `color` has left in milliseconds, including time used
+    /// since the clock was started. Returns `0` if no clock is attached.
+    #[cfg(feature = "std")]
+    pub fn time_remaining(&self, color : Color) -> u64 {
+        match &self.clock {
+            Some(clock) => clock.remaining(color),
+            None => 0,
+        }
+    }
+
+    /// Returns the color of the player who has run out of time, or `None`
+    /// if no clock is attached or both players have time left.
+    #[cfg(feature = "std")]
+    pub fn flagged(&self) -> Option<Color> {
+        let clock = self.clock.as_ref()?;
+
+        [self.turn, self.turn.opposite()]
+            .into_iter()
+            .find(|&color| clock.remaining(color) == 0)
+    }
+
+    /// Make a move and switch the clock to the other player, see `make_move()`.
+    /// The clock is only switched if the move is legal.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if either square is invalid, see `make_move()`.
+    #[cfg(feature = "std")]
+    pub fn make_move_timed(&mut self, from : &str, to : &str, auto_promote : bool) -> Result<bool, String> {
+        let moved = self.make_move(from, to, auto_promote)?;
+
+        if moved {
+            self.stop_clock();
+            self.start_clock();
+        }
+
+        Ok(moved)
+    }
+
+    /// Returns color of active player
+    pub fn get_active_player(&self) -> Color {
+        self.turn
+    }
+
+    /// Returns the en passant target square, i.e. the square a pawn moves to
+    /// when capturing en passant, not the square of the captured pawn.
+    ///
+    /// # Returns
+    /// * `Some((usize, usize))` with the array index of the square if the last
+    ///   move was a double pawn push.
+    /// * `None` otherwise.
+    pub fn en_passant_target(&self) -> Option<(usize, usize)> {
+        self.en_passant_square
+    }
+
+    /// Returns bool representing wether there is an en passant target square,
+    /// see `en_passant_target()`.
+    pub fn is_en_passant_available(&self) -> bool {
+        self.en_passant_target().is_some()
+    }
+
+    /// Returns the number of half moves since the last capture or pawn move.
+    pub fn half_move_clock(&self) -> u32 {
+        self.half_moves
+    }
+
+    /// Returns the full move number, as written in FEN.
+    pub fn full_move_number(&self) -> u32 {
+        self.full_moves
+    }
+
+    /// Returns the number of half moves left before the fifty move rule applies.
+    pub fn moves_until_fifty_rule(&self) -> u32 {
+        100u32.saturating_sub(self.half_moves)
+    }
+
+    /// Returns bool representing wether the fifty move rule will apply within
+    /// `threshold` half moves. Useful for adjudicating engine games.
+    pub fn is_approaching_fifty_move_rule(&self, threshold : u32) -> bool {
+        self.half_moves >= 100u32.saturating_sub(threshold)
+    }
+
+    /// Returns how many times the current position has occured during the game,
+    /// including the current occurence. Positions are compared by Zobrist hash, see
+    /// `position_hash()`, so the move counters are ignored and an en passant square
+    /// only counts if the en passant capture is legal.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut game = Game::new_starting_pos();
+    ///
+    /// game.make_move("g1", "f3", true).unwrap();
+    /// game.make_move("g8", "f6", true).unwrap();
+    /// game.make_move("f3", "g1", true).unwrap();
+    /// game.make_move("f6", "g8", true).unwrap();
+    ///
+    /// assert_eq!(game.repetition_count(), 2);
+    /// ```
+    ///
+    /// # Notes
+    /// * Positions reached before a manual board edit, e.g. `place_piece()`, are not counted.
+    pub fn repetition_count(&self) -> u32 {
+        let repetitions = self.undo_stack.iter()
+            .filter(|record| record.zobrist_hash == self.zobrist_hash)
+            .count() as u32;
+
+        repetitions + 1
+    }
+
+    /// Returns bool representing wether the current position has occured at least
+    /// three times, see `repetition_count()`.
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    /// Same as `repetition_count()`, but positions are compared by FEN, ignoring
+    /// the move counters. Slower, since the FEN of every earlier position is
+    /// generated, and an en passant square counts even if no capture is possible.
+    pub fn repetition_count_by_fen(&self) -> u32 {
+        let position = self.to_fen_core();
+
+        //fen_history() always contains the current position
+        self.fen_history().iter()
+            .filter(|fen| fen_without_counters(fen) == position)
+            .count() as u32
+    }
+
+    /// Returns bool representing wether the current position has occured at least
+    /// three times, see `repetition_count_by_fen()`.
+    pub fn is_threefold_by_fen(&self) -> bool {
+        self.repetition_count_by_fen() >= 3
+    }
+
+    /// Returns bool representing wether `color` has castled during the game.
+    ///
+    /// # Notes
+    /// * Only records castling made through the Game object. Positions generated
+    ///   from FEN will always start with neither player having castled.
+    pub fn has_castled(&self, color : Color) -> bool {
+        //color is always a key in castled, so unwrap is safe
+        *self.castled.get(&color).unwrap()
+    }
+
+    /// Returns `vec` of each `Piece` that `color` has captured
+    /// during the game.
+    /// 
+    /// # Notes
+    /// * Only records moves made through the Game object using any
+    ///   implementation of make_move() method. Positions generated
+    ///   from FEN will not have captures recorded properly. 
+    pub fn get_captures(&self, color : Color) -> Vec<Piece>{
+        let mut res = Vec::new();
+
+        for p in &self.captures {
+            if p.color == color.opposite() {
+                res.push(*p);
+            }
+        }
+
+        res
+    }
+
+    /// Get the piece captured on the most recent move, including pawns
+    /// captured en passant.
+    ///
+    /// # Returns
+    /// * `Some(Piece)` if the last move was a capture.
+    /// * `None` if the last move was not a capture, or no moves have been made.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let mut game = Game::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    /// game.make_move("e4", "d5", true).unwrap();
+    ///
+    /// assert_eq!(game.last_captured_piece(), Some(Piece::new(PieceType::Pawn, Color::Black)));
+    /// ```
+    pub fn last_captured_piece(&self) -> Option<Piece> {
+        self.undo_stack.last()?.captured.map(|(piece, _)| piece)
+    }
+
+    /// Returns bool representing wether the most recent move was a capture,
+    /// see `last_captured_piece()`.
+    pub fn last_move_was_capture(&self) -> bool {
+        self.last_captured_piece().is_some()
+    }
+
+    /// Count the pawns of `color` shielding its king, i.e. pawns on the two ranks
+    /// in front of the king, on the king's file or the files next to it.
+    ///
+    /// # Returns
+    /// * `u32` number of shielding pawns, between `0` and `6`. Returns `0` if
+    ///   `color` has no king.
+    pub fn pawn_shield_count(&self, color : Color) -> u32 {
+        let (king_i, king_j) = match self.find_king(color) {
+            Some(pos) => (pos.0 as i32, pos.1 as i32),
+            None => return 0,
+        };
+
+        //direction white and black pawns move in
+        let d = match color {
+            Color::White => -1,
+            Color::Black => 1,
+        };
+
+        let mut count = 0;
+
+        for rank in 1..=2 {
+            for file in -1..=1 {
+                let (i, j) = (king_i + d * rank, king_j + file);
+
+                if is_valid_pos(i, j) && self.board[i as usize][j as usize] == Some(Piece::new(PieceType::Pawn, color)) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Get the squares around the king of `color` that an attacker would target,
+    /// used for evaluating king safety. The zone consists of the (up to) 8 squares
+    /// next to the king, and the (up to) 8 squares one step further in each of
+    /// those directions.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies in board order, starting at a8. Empty if `color`
+    ///   has no king.
+    pub fn king_attack_zone(&self, color : Color) -> Vec<(usize, usize)> {
+        let mut zone = Vec::new();
+
+        let (king_i, king_j) = match self.find_king(color) {
+            Some(pos) => (pos.0 as i32, pos.1 as i32),
+            None => return zone,
+        };
+
+        for (d_i, d_j) in &self.queen_move_directions {
+            for distance in 1..=2 {
+                let (i, j) = (king_i + d_i * distance, king_j + d_j * distance);
+
+                if is_valid_pos(i, j) {
+                    zone.push((i as usize, j as usize));
+                }
+            }
+        }
+
+        zone.sort();
+
+        zone
+    }
+
+    /// Get all squares in the opponent's half of the board that can never be attacked
+    /// by the opponent's pawns, i.e. there are no opponent pawns on the neighbouring
+    /// files that can still advance to attack the square. The squares are returned
+    /// regardless of what occupies them.
+    ///
+    /// # Arguments
+    /// * `color` is the color of the player the outposts are computed for.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies in board order, starting at a8.
+    pub fn outpost_squares(&self, color : Color) -> Vec<(usize, usize)> {
+        let mut squares = Vec::new();
+
+        //ranks in the opponents half of the board
+        let ranks = match color {
+            Color::White => 0..4,
+            Color::Black => 4..8,
+        };
+
+        for i in ranks {
+            for j in 0..8 {
+                if !self.can_be_attacked_by_pawns((i, j), color.opposite()) {
+                    squares.push((i, j));
+                }
+            }
+        }
+
+        squares
+    }
+
+    /// Get the position of every knight of `color` on an outpost, i.e. a square
+    /// returned by `outpost_squares()` that is defended by a pawn of `color`.
+    pub fn knight_outposts(&self, color : Color) -> Vec<(usize, usize)> {
+        self.pieces_on_outposts(color, PieceType::Knight)
+    }
+
+    /// Get the position of every bishop of `color` on an outpost, i.e. a square
+    /// returned by `outpost_squares()` that is defended by a pawn of `color`.
+    pub fn bishop_outposts(&self, color : Color) -> Vec<(usize, usize)> {
+        self.pieces_on_outposts(color, PieceType::Bishop)
+    }
+
+    //helper function for knight_outposts() and bishop_outposts()
+    fn pieces_on_outposts(&self, color : Color, piece_type : PieceType) -> Vec<(usize, usize)> {
+        self.outpost_squares(color)
+            .into_iter()
+            .filter(|&(i, j)| self.board[i][j] == Some(Piece::new(piece_type, color)))
+            .filter(|&square| self.is_defended_by_pawn(square, color))
+            .collect()
+    }
+
+    /// Count how many times `color` attacks each square in the extended center
+    /// of the board, i.e. the squares from c3 to f6.
+    ///
+    /// # Returns
+    /// * `HashMap` where each key is the array index of a square in the extended center,
+    ///   and each value is the number of pieces of `color` attacking that square.
+    pub fn attacks_center(&self, color : Color) -> HashMap<(usize, usize), u32> {
+        let mut attacks = HashMap::new();
+
+        for i in 2..6 {
+            for j in 2..6 {
+                attacks.insert((i, j), 0);
+            }
+        }
+
+        //attacked squares has one entry for each piece attacking the square
+        for square in self.get_attacked_squares(color) {
+            if let Some(count) = attacks.get_mut(square) {
+                *count += 1;
+            }
+        }
+
+        attacks
+    }
+
+    /// Get a score for how well `color` controls the center compared to the opponent.
+    /// Each attack on d4, e4, d5 or e5 is worth `2`, and each attack on the other squares
+    /// in the extended center (c3 to f6) is worth `1`. The opponents score is subtracted.
+    ///
+    /// # Returns
+    /// * `i32` score, positive if `color` controls the center better than the opponent.
+    pub fn center_control_score(&self, color : Color) -> i32 {
+        self.weighted_center_attacks(color) - self.weighted_center_attacks(color.opposite())
+    }
+
+    //helper function for center_control_score()
+    fn weighted_center_attacks(&self, color : Color) -> i32 {
+        let mut score = 0;
+
+        for (square, count) in self.attacks_center(color) {
+            let weight = match square {
+                (3..=4, 3..=4) => 2,
+                _ => 1,
+            };
+
+            score += weight * count as i32;
+        }
+
+        score
+    }
+
+    /// Get the position of every knight and bishop of `color` still on its starting
+    /// square, i.e. b1, g1, c1 and f1 for white and b8, g8, c8 and f8 for black.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies in board order, starting at a8.
+    pub fn undeveloped_pieces(&self, color : Color) -> Vec<(usize, usize)> {
+        let back_rank = match color {
+            Color::White => 7,
+            Color::Black => 0,
+        };
+
+        [(1, PieceType::Knight), (2, PieceType::Bishop), (5, PieceType::Bishop), (6, PieceType::Knight)]
+            .into_iter()
+            .filter(|&(j, piece_type)| self.board[back_rank][j] == Some(Piece::new(piece_type, color)))
+            .map(|(j, _)| (back_rank, j))
+            .collect()
+    }
+
+    /// Get a score for how developed the knights and bishops of `color` are compared
+    /// to the opponent. Each knight or bishop that has left its starting square is worth
+    /// `1` and each of the opponents developed pieces is worth `-1`. Pawns, rooks, queens
+    /// and kings are not counted.
+    ///
+    /// # Returns
+    /// * `i32` score, positive if `color` is further developed than the opponent.
+    pub fn development_score(&self, color : Color) -> i32 {
+        self.developed_piece_count(color) - self.developed_piece_count(color.opposite())
+    }
+
+    //helper function for development_score()
+    fn developed_piece_count(&self, color : Color) -> i32 {
+        let mut minor_pieces = 0;
+
+        for row in self.board {
+            for piece in row.into_iter().flatten() {
+                if piece.color == color
+                    && (piece.piece_type == PieceType::Knight || piece.piece_type == PieceType::Bishop)
+                {
+                    minor_pieces += 1;
+                }
+            }
+        }
+
+        minor_pieces - self.undeveloped_pieces(color).len() as i32
+    }
+
+    /// Check if `color` has a weak back rank, i.e. the king is on its back rank and
+    /// every square in front of it is occupied by a piece of `color`, so the king can
+    /// not escape a check along the back rank. Only a threat if the opponent has a
+    /// rook or queen.
+    ///
+    /// # Returns
+    /// * `bool` representing wether or not the back rank is weak.
+    ///
+    /// # Notes
+    /// * This is a heuristic for evaluation, it does not check if a mate can actually
+    ///   be forced.
+    pub fn back_rank_mate_threat(&self, color : Color) -> bool {
+        let (king_i, king_j) = match self.find_king(color) {
+            Some(pos) => pos,
+            None => return false,
+        };
+
+        //back rank and the rank in front of it
+        let (back_rank, front_rank) = match color {
+            Color::White => (7, 6),
+            Color::Black => (0, 1),
+        };
+
+        if king_i != back_rank {
+            return false;
+        }
+
+        let opponent_has_major_piece = self.board.iter().flatten().flatten().any(|piece| {
+            piece.color != color && (piece.piece_type == PieceType::Rook || piece.piece_type == PieceType::Queen)
+        });
+
+        if !opponent_has_major_piece {
+            return false;
+        }
+
+        for j in king_j.saturating_sub(1)..=(king_j + 1).min(7) {
+            match self.board[front_rank][j] {
+                Some(piece) if piece.color == color => (),
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Get every open file, i.e. files without pawns of either color.
+    ///
+    /// # Returns
+    /// * `Vec` of file indicies, `0` being the a-file.
+    pub fn open_files(&self) -> Vec<usize> {
+        (0..8)
+            .filter(|&j| self.pawns_on_file(j, Color::White) == 0 && self.pawns_on_file(j, Color::Black) == 0)
+            .collect()
+    }
+
+    /// Get every semi-open file for `color`, i.e. files without pawns of `color`
+    /// but with at least one opponent pawn.
+    ///
+    /// # Returns
+    /// * `Vec` of file indicies, `0` being the a-file.
+    pub fn semi_open_files(&self, color : Color) -> Vec<usize> {
+        (0..8)
+            .filter(|&j| self.pawns_on_file(j, color) == 0 && self.pawns_on_file(j, color.opposite()) > 0)
+            .collect()
+    }
+
+    /// Get the position of every rook of `color` on an open file, see `open_files()`.
+    pub fn rooks_on_open_files(&self, color : Color) -> Vec<(usize, usize)> {
+        let files = self.open_files();
+
+        self.find_pieces(Piece::new(PieceType::Rook, color))
+            .into_iter()
+            .filter(|(_, j)| files.contains(j))
+            .collect()
+    }
+
+    /// Get the position of every rook of `color` on a semi-open file, see `semi_open_files()`.
+    pub fn rooks_on_semi_open_files(&self, color : Color) -> Vec<(usize, usize)> {
+        let files = self.semi_open_files(color);
+
+        self.find_pieces(Piece::new(PieceType::Rook, color))
+            .into_iter()
+            .filter(|(_, j)| files.contains(j))
+            .collect()
+    }
+
+    /// Returns the number of rooks and queens of `color` on file `file`, `0` being the a-file.
+    pub fn rooks_on_file(&self, file : usize, color : Color) -> u32 {
+        if file > 7 {
+            return 0;
+        }
+
+        (0..8)
+            .filter_map(|i| self.board[i][file])
+            .filter(|piece| piece.color == color && matches!(piece.piece_type, PieceType::Rook | PieceType::Queen))
+            .count() as u32
+    }
+
+    /// Get which player controls each file with their rooks and queens, see `FileControl`.
+    ///
+    /// # Returns
+    /// * Array indexed by file, `0` being the a-file.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let game = Game::from_fen("3rk3/8/8/8/8/8/8/3RK2R w - - 0 1").unwrap();
+    /// let control = game.file_control();
+    ///
+    /// assert_eq!(control[3], FileControl::Contested);
+    /// assert_eq!(control[7], FileControl::White);
+    /// ```
+    pub fn file_control(&self) -> [FileControl; 8] {
+        let mut control = [FileControl::None; 8];
+
+        for (file, file_control) in control.iter_mut().enumerate() {
+            let white = self.rooks_on_file(file, Color::White) > 0;
+            let black = self.rooks_on_file(file, Color::Black) > 0;
+
+            *file_control = match (white, black) {
+                (true, true) => FileControl::Contested,
+                (true, false) => FileControl::White,
+                (false, true) => FileControl::Black,
+                (false, false) => FileControl::None,
+            };
+        }
+
+        control
+    }
+
+    /// Returns bool representing wether two rooks of `color` are connected, i.e.
+    /// they are on the same rank or file with no pieces between them.
+    pub fn connected_rooks(&self, color : Color) -> bool {
+        let rooks = self.find_pieces(Piece::new(PieceType::Rook, color));
+
+        for (n, &(i1, j1)) in rooks.iter().enumerate() {
+            for &(i2, j2) in &rooks[n + 1..] {
+                let between_empty = squares_between((i1, j1), (i2, j2))
+                    .iter()
+                    .all(|&(i, j)| self.board[i][j].is_none());
+
+                if (i1 == i2 || j1 == j2) && between_empty {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Get the position of every piece of `color` that is attacked by the opponent
+    /// and not defended by any piece of `color`. The king is never included.
+    pub fn hanging_pieces(&self, color : Color) -> Vec<(usize, usize)> {
+        self.attacked_pieces(color)
+            .into_iter()
+            .filter(|square| !self.get_attacked_squares(color).contains(square))
+            .collect()
+    }
+
+    /// Rough estimate of how tactical the position is for `color`, a value near `0`
+    /// means the position is quiet and high values mean there are forcing lines.
+    ///
+    /// # Returns
+    /// * `u32` being the sum of the number of hanging pieces of either side times `2`,
+    ///   the number of attacked pieces of either side and the number of legal
+    ///   moves for `color` that give check times `3`.
+    ///
+    /// # Notes
+    /// * This is a heuristic, intended for e.g. time management in engines.
+    pub fn tactical_complexity(&mut self, color : Color) -> u32 {
+        let mut complexity = 0;
+
+        for side in [Color::White, Color::Black] {
+            complexity += 2 * self.hanging_pieces(side).len() as u32;
+            complexity += self.attacked_pieces(side).len() as u32;
+        }
+
+        for (i1, j1, i2, j2) in self.legal_moves_in_board_order(color) {
+            if self.move_gives_check((i1, j1), (i2, j2)) {
+                complexity += 3;
+            }
+        }
+
+        complexity
+    }
+
+    /// Get every rook and queen battery of `color`, i.e. a rook and a queen on the
+    /// same rank or file with no pieces between them.
+    ///
+    /// # Returns
+    /// * `Vec` of tuples `(i1, j1, i2, j2)` where `(i1, j1)` is the index of the
+    ///   rook and `(i2, j2)` the index of the queen.
+    pub fn rook_queen_batteries(&self, color : Color) -> Vec<(usize, usize, usize, usize)> {
+        self.batteries(PieceType::Rook, color, &self.rook_move_directions)
+    }
+
+    /// Get every bishop and queen battery of `color`, i.e. a bishop and a queen on
+    /// the same diagonal with no pieces between them.
+    ///
+    /// # Returns
+    /// * `Vec` of tuples `(i1, j1, i2, j2)` where `(i1, j1)` is the index of the
+    ///   bishop and `(i2, j2)` the index of the queen.
+    pub fn bishop_queen_batteries(&self, color : Color) -> Vec<(usize, usize, usize, usize)> {
+        self.batteries(PieceType::Bishop, color, &self.bishop_move_directions)
+    }
+
+    /// Get the number of squares in the opponent's half of the board attacked by
+    /// `color`, i.e. ranks 5-8 for white and ranks 1-4 for black. Each square is
+    /// only counted once, regardless of how many pieces attack it.
+    pub fn space_advantage(&self, color : Color) -> u32 {
+        let opponent_half = match color {
+            Color::White => 0..4,
+            Color::Black => 4..8,
+        };
+
+        let mut squares : Vec<(usize, usize)> = self.get_attacked_squares(color)
+            .iter()
+            .filter(|(i, _)| opponent_half.contains(i))
+            .copied()
+            .collect();
+
+        //attacked squares include one entry per attacking piece
+        squares.sort();
+        squares.dedup();
+
+        squares.len() as u32
+    }
+
+    /// Get the difference in space between white and black, see `space_advantage()`.
+    /// A positive value is an advantage for white.
+    pub fn space_advantage_relative(&self) -> i32 {
+        self.space_advantage(Color::White) as i32 - self.space_advantage(Color::Black) as i32
+    }
+
+    /// Get the difference in mobility, i.e. number of legal moves, between white and
+    /// black. A positive value is an advantage for white.
+    ///
+    /// # Notes
+    /// * Generates every legal move for both players, use `mobility_estimate()`
+    ///   when speed is more important than precision.
+    pub fn mobility_imbalance(&mut self) -> i32 {
+        self.legal_move_count(Color::White) as i32 - self.legal_move_count(Color::Black) as i32
+    }
+
+    /// Same as `mobility_imbalance()`, but counts pseudo-legal moves, i.e. moves
+    /// that may leave the king in check.
+    pub fn mobility_estimate(&self) -> i32 {
+        let mut mobility = 0;
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j] {
+                    //i, j will always be a valid index, so unwrap is safe
+                    let moves = self.pseudo_legal_moves((i, j)).unwrap().len() as i32;
+
+                    match piece.color {
+                        Color::White => mobility += moves,
+                        Color::Black => mobility -= moves,
+                    }
+                }
+            }
+        }
+
+        mobility
+    }
+
+    /// Get every square attacked by a pawn of `color`, in board order.
+    pub fn controlled_by_pawns(&self, color : Color) -> Vec<(usize, usize)> {
+        (0..8)
+            .flat_map(|i| (0..8).map(move |j| (i, j)))
+            .filter(|&square| self.is_defended_by_pawn(square, color))
+            .collect()
+    }
+
+    /// Get every weak square on `color`'s half of the board, i.e. ranks 1-4 for white
+    /// and ranks 5-8 for black. A square is weak if no pawn of `color` attacks it and
+    /// no pawn of `color` can advance to attack it. Squares occupied by pieces of
+    /// `color` are not included.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies of the weak squares, in board order.
+    pub fn weak_squares(&self, color : Color) -> Vec<(usize, usize)> {
+        let own_half = match color {
+            Color::White => 4..8,
+            Color::Black => 0..4,
+        };
+
+        own_half
+            .flat_map(|i| (0..8).map(move |j| (i, j)))
+            .filter(|&(i, j)| !matches!(self.board[i][j], Some(piece) if piece.color == color))
+            .filter(|&square| !self.can_be_attacked_by_pawns(square, color))
+            .collect()
+    }
+
+    /// Returns the number of pawns `color` has on the board.
+    pub fn pawn_count(&self, color : Color) -> u32 {
+        self.find_pieces(Piece::new(PieceType::Pawn, color)).len() as u32
+    }
+
+    /// Returns the number of pawn islands of `color`, i.e. groups of pawns on adjacent
+    /// files. Pawns on the a- and c-file with no pawn on the b-file form two islands.
+    /// Fewer islands usually means a healthier pawn structure.
+    pub fn pawn_islands_count(&self, color : Color) -> u32 {
+        let files = self.pawn_file_mask(color);
+
+        //an island starts on every occupied file whose left neighbour is empty
+        (files & !(files << 1)).count_ones()
+    }
+
+    /// Returns the number of isolated pawns of `color`, i.e. pawns with no pawns
+    /// of the same color on adjacent files.
+    pub fn isolated_pawn_count(&self, color : Color) -> u32 {
+        let files = self.pawn_file_mask(color);
+        let neighbours = (files << 1) | (files >> 1);
+
+        self.find_pieces(Piece::new(PieceType::Pawn, color))
+            .into_iter()
+            .filter(|&(_, j)| neighbours & (1 << j) == 0)
+            .count() as u32
+    }
+
+    /// Get the position of every pawn of `color` one push away from promotion,
+    /// i.e. white pawns on rank 7 and black pawns on rank 2.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies of the pawns, in board order.
+    pub fn promotion_candidates(&self, color : Color) -> Vec<(usize, usize)> {
+        let rank = match color {
+            Color::White => 1,
+            Color::Black => 6,
+        };
+
+        self.find_pieces(Piece::new(PieceType::Pawn, color))
+            .into_iter()
+            .filter(|&(i, _)| i == rank)
+            .collect()
+    }
+
+    /// Returns bool representing wether `color` has a pawn one push away from
+    /// promotion, see `promotion_candidates()`.
+    pub fn has_promotion_pawns(&self, color : Color) -> bool {
+        !self.promotion_candidates(color).is_empty()
+    }
+
+    /// Get the position of every pawn of `color` that threatens to promote on the
+    /// next move. Same as `promotion_candidates()`.
+    pub fn promotion_threats(&self, color : Color) -> Vec<(usize, usize)> {
+        self.promotion_candidates(color)
+    }
+
+    /// Returns bool representing wether `color` has a pawn that threatens to
+    /// promote on the next move, see `promotion_threats()`.
+    pub fn has_promotion_threat(&self, color : Color) -> bool {
+        !self.promotion_threats(color).is_empty()
+    }
+
+    /// Get the fewest moves any pawn of `color` needs to promote, counting only pawn
+    /// pushes. A pawn on its starting rank can push two squares, so it needs five moves.
+    /// Useful as a heuristic for detecting promotion races.
+    ///
+    /// # Returns
+    /// * `Some(u32)` with the number of moves, or `None` if `color` has no pawns.
+    ///
+    /// # Notes
+    /// * Pieces blocking the pawns and captures are ignored.
+    pub fn fastest_promotion_distance(&self, color : Color) -> Option<u32> {
+        self.find_pieces(Piece::new(PieceType::Pawn, color))
+            .into_iter()
+            .map(|(i, _)| {
+                let (squares_left, starting_rank) = match color {
+                    Color::White => (i as u32, 6),
+                    Color::Black => (7 - i as u32, 1),
+                };
+
+                if i == starting_rank {squares_left - 1} else {squares_left}
+            })
+            .min()
+    }
+
+    /// Get the material value of the piece on every square, see `piece_value()`.
+    ///
+    /// # Returns
+    /// * 2d array indexed like the board, with the value in centipawns of the piece
+    ///   on each square. White pieces are positive, black pieces negative and empty
+    ///   squares `0`. Kings have the value `20000`.
+    pub fn material_value_map(&self) -> [[i32; 8]; 8] {
+        self.board.map(|row| row.map(|square| match square {
+            Some(piece) if piece.color == Color::White => piece_value(piece.piece_type),
+            Some(piece) => -piece_value(piece.piece_type),
+            None => 0,
+        }))
+    }
+
+    /// Get the total material value on the board in centipawns, see `piece_value()`.
+    ///
+    /// # Returns
+    /// * Tuple `(white, black)` with the sum of the values of each player's pieces,
+    ///   both positive. Kings are not included.
+    pub fn total_material_on_board(&self) -> (i32, i32) {
+        let mut material = (0, 0);
+
+        for piece in self.board.iter().flatten().flatten() {
+            if piece.piece_type == PieceType::King {
+                continue;
+            }
+
+            match piece.color {
+                Color::White => material.0 += piece_value(piece.piece_type),
+                Color::Black => material.1 += piece_value(piece.piece_type),
+            }
+        }
+
+        material
+    }
+
+    /// Returns bool representing wether only the two kings are left on the board,
+    /// one for each player, the simplest case of insufficient material. Useful as a
+    /// fast check for adjudicating engine games.
+    pub fn is_only_kings(&self) -> bool {
+        self.piece_count_total() == 2
+            && self.find_king(Color::White).is_some()
+            && self.find_king(Color::Black).is_some()
+    }
+
+    /// Returns the number of pieces on the board, including pawns and kings.
+    pub fn piece_count_total(&self) -> u32 {
+        self.board.iter().flatten().flatten().count() as u32
+    }
+
+    /// Returns the number of pieces on the board, including pawns but not kings.
+    /// Useful for estimating the phase of the game, with fewer than `7` pieces
+    /// left the position is usually an endgame.
+    pub fn piece_count_excluding_kings(&self) -> u32 {
+        self.board.iter()
+            .flatten()
+            .flatten()
+            .filter(|piece| piece.piece_type != PieceType::King)
+            .count() as u32
+    }
+
+    /// Get how far the king of `color` is from the center, for evaluating king
+    /// activity in endgames.
+    ///
+    /// # Returns
+    /// * `i32` being the Chebyshev distance (the number of king moves) from the king
+    ///   to the nearest of d4, d5, e4 and e5, from `0` on a center square to `3` in a
+    ///   corner. Returns `0` if `color` has no king.
+    ///
+    /// # Notes
+    /// * A centralized king is only an advantage in the endgame, in the middlegame
+    ///   the king is usually safer far from the center.
+    pub fn king_activity_score(&self, color : Color) -> i32 {
+        let (i, j) = match self.find_king(color) {
+            Some(king_pos) => king_pos,
+            None => return 0,
+        };
+
+        HILL_SQUARES.iter()
+            .map(|&center| chebyshev_distance((i, j), center) as i32)
+            .min()
+            //HILL_SQUARES is not empty, so unwrap is safe
+            .unwrap()
+    }
+
+    /// Get every square within two king moves of the king of `color`, not including
+    /// the square of the king itself.
+    ///
+    /// # Returns
+    /// * `Vec` of array indicies, in board order. Empty if `color` has no king.
+    pub fn king_endgame_zone(&self, color : Color) -> Vec<(usize, usize)> {
+        let (i, j) = match self.find_king(color) {
+            Some(king_pos) => king_pos,
+            None => return Vec::new(),
+        };
+
+        (0..8)
+            .flat_map(|i2| (0..8).map(move |j2| (i2, j2)))
+            .filter(|&(i2, j2)| (i2, j2) != (i, j) && i.abs_diff(i2) <= 2 && j.abs_diff(j2) <= 2)
+            .collect()
+    }
+
+    /// Returns the distance between the two kings, counted in king moves. Returns `0`
+    /// if either king is missing.
+    pub fn king_proximity(&self) -> u32 {
+        match (self.find_king(Color::White), self.find_king(Color::Black)) {
+            (Some(white_king), Some(black_king)) => chebyshev_distance(white_king, black_king),
+            _ => 0,
+        }
+    }
+
+    /// Returns bool representing wether the kings are in direct opposition, i.e. on
+    /// the same rank or file with exactly one square between them.
+    pub fn kings_in_opposition(&self) -> bool {
+        match (self.find_king(Color::White), self.find_king(Color::Black)) {
+            (Some((i1, j1)), Some((i2, j2))) => {
+                (i1 == i2 && j1.abs_diff(j2) == 2) || (j1 == j2 && i1.abs_diff(i2) == 2)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns bool representing wether the kings are in diagonal opposition, i.e. on
+    /// the same diagonal with exactly one square between them.
+    pub fn kings_in_diagonal_opposition(&self) -> bool {
+        match (self.find_king(Color::White), self.find_king(Color::Black)) {
+            (Some((i1, j1)), Some((i2, j2))) => i1.abs_diff(i2) == 2 && j1.abs_diff(j2) == 2,
+            _ => false,
+        }
+    }
+
+    /// Returns bool representing wether the active player has the opposition, i.e. can
+    /// make a legal king move that puts the kings in direct or diagonal opposition,
+    /// leaving the opponent to give way.
+    ///
+    /// # Notes
+    /// * If the kings already stand in opposition the active player is the one who
+    ///   has to give way, so `false` is returned.
+    pub fn active_king_has_opposition(&self) -> bool {
+        let (king, opponent_king) = match (self.find_king(self.turn), self.find_king(self.turn.opposite())) {
+            (Some(king), Some(opponent_king)) => (king, opponent_king),
+            _ => return false,
+        };
+
+        if self.kings_in_opposition() || self.kings_in_diagonal_opposition() {
+            return false;
+        }
+
+        let mut position = self.position_copy();
+
+        position.legal_moves_from(king).into_iter().any(|(i, j)| {
+            let (di, dj) = (i.abs_diff(opponent_king.0), j.abs_diff(opponent_king.1));
+
+            (di, dj) == (2, 0) || (di, dj) == (0, 2) || (di, dj) == (2, 2)
+        })
+    }
+
+    //function to handle movement logic
+    fn make_move_with_index(&mut self, from : (usize, usize), to : (usize, usize), check_legal : bool, auto_promote : bool) -> Result<bool, String> {
+        let (i1, j1) = from;
+        let (i2, j2) = to;
+
+        //return if move is illegal
+        //ignored if check_legal is false
+        if check_legal{
+            //no moves can be made after a player has resigned
+            if self.resigned.is_some() {
+                return Ok(false);
+            }
+
+            if let Some(piece) = self.board[i1][j1] {
+                if piece.color != self.turn {
+                    return Ok(false);
+                }
+            }
+            //index (i1, j1) is validated by the caller
+            if !(self.legal_moves_from((i1, j1)).contains(&(i2, j2))) {
+                return Ok(false);
+            }
+        }
+
+        //save board state, board[i1][j1] is always Some(Piece), so unwrap is safe
+        let mut record = self.undo_record(Some(from), to, self.board[i1][j1].unwrap());
+        self.last_move = Some((from, to));
+
+        //side to move, castling rights and en passant square are hashed again after the move
+        self.zobrist_hash ^= self.zobrist_state_key();
+
+        //increment half moves, if there is a capture or pawn move this will be reset
+        self.half_moves += 1;
+
+        //en passant square for the next position, only set by a double pawn push
+        let mut next_en_passant_square = None;
+
+        //Capture logic
+        if let Some(piece) = self.board[i2][j2] {
+            self.record_capture(piece, (i2, j2));
+            record.captured = Some((piece, (i2, j2)));
+            self.half_moves = 0; //piece captured : resets half moves
+        }
+
+        //Check if castling
+        //note board[i1][j1] is always Some(Piece) due to how
+        //this function is called, so unwrap() wont panic
+        if self.board[i1][j1].unwrap().piece_type == PieceType::King {
+            let d = j1 as i32 - j2 as i32;
+
+            //remove castling rights, any king move loses them
+            let king_color = self.board[i1][j1].unwrap().color;
+            self.kingside_castle.insert(king_color, false);
+            self.queenside_castle.insert(king_color, false);
+
+            //check if king is moved 2 squares
+            if d.abs() == 2 {
+                self.castled.insert(king_color, true);
+
+                //kingside castle
+                let (rook_from, rook_to) = if d < 0 {((i1, 7), (i1, 5))} else {((i1, 0), (i1, 3))};
+
+                self.set_square(rook_to, self.board[rook_from.0][rook_from.1]);
+                self.set_square(rook_from, None);
+                self.move_promoted_mark(rook_from, rook_to);
+                record.rook_move = Some((rook_from, rook_to));
+            }
+        } else if self.board[i1][j1].unwrap().piece_type == PieceType::Rook {
+            //remove castling rights if the rook is moved
+
+            let rook_color = self.board[i1][j1].unwrap().color;
+
+            let starting_rank = match rook_color {
+                Color::White => 7,
+                Color::Black => 0,
+            };
+
+            if i1 == starting_rank {
+                match j1 {
+                    0 => {self.queenside_castle.insert(rook_color, false);},
+                    7 => {self.kingside_castle.insert(rook_color, false);},
+                    _ => (),
+                }
+            }
+        } else if self.board[i1][j1].unwrap().piece_type == PieceType::Pawn {
+            self.half_moves = 0; //pawn moved : reset half moves
+
+            let pawn_color = self.board[i1][j1].unwrap().color;
+
+            //check if pawn is moved two squares
+            let d = i1 as i32 - i2 as i32;
+
+            if d.abs() == 2 {
+                next_en_passant_square = Some(((i1 + i2) / 2, j1))
+            }
+
+            if self.is_promotion_move(from, to) {
+                self.promotion_square = Some((i2, j2));
+            }
+
+            if self.en_passant_square == Some((i2, j2)) {
+                let captured_rank = match pawn_color {
+                    Color::White => i2 + 1,
+                    Color::Black => i2 - 1,
+                };
+
+                //record the captured pawn, since it is not on the target square
+                if let Some(piece) = self.board[captured_rank][j2] {
+                    self.record_capture(piece, (captured_rank, j2));
+                    record.captured = Some((piece, (captured_rank, j2)));
+                }
+
+                self.set_square((captured_rank, j2), None);
+            }
+        }
+
+        if let Some(piece) = self.board[i2][j2] {
+            if piece.piece_type == PieceType::Rook {
+                //remove castling rights if the rook is captured
+
+                let rook_color = self.board[i2][j2].unwrap().color;
+
+                let starting_rank = match rook_color {
+                    Color::White => 7,
+                    Color::Black => 0,
+                };
+
+                if i2 == starting_rank {
+                    match j2 {
+                        0 => {self.queenside_castle.insert(rook_color, false);},
+                        7 => {self.kingside_castle.insert(rook_color, false);},
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        //make move
+        self.set_square((i2, j2), self.board[i1][j1]);
+        self.set_square((i1, j1), None);
+        self.move_promoted_mark(from, to);
+        self.undo_stack.push(record);
+
+        self.update_attacked_squares();
+        self.record_check(self.turn);
+
+        //promote after the check of the move itself is recorded, promote() only
+        //records a check if the promoted piece gives one the pawn move did not
+        if auto_promote {
+            self.promote_to_piece(PieceType::Queen);
+        }
+
+        if self.turn == Color::Black {
+            self.full_moves += 1;
+        }
+
+        self.en_passant_square = next_en_passant_square;
+        self.turn = self.turn.opposite();
+        self.zobrist_hash ^= self.zobrist_state_key();
+
+        Ok(true)
+    }
+
+    /// Checks wether or not a move captures a piece, including en passant
+    ///
+    /// # Panics
+    /// Function panics if there is not a piece at index `from`
+    fn is_capture_move(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+        let piece = self.board[from.0][from.1].unwrap();
+
+        //en passant is the only capture where the destination square is empty
+        self.board[to.0][to.1].is_some()
+            || (piece.piece_type == PieceType::Pawn && from.1 != to.1)
+    }
+
+    /// Returns all legal moves for `color` as `(i1, j1, i2, j2)`, in board order
+    /// starting at a8
+    fn legal_moves_in_board_order(&mut self, color : Color) -> Vec<(usize, usize, usize, usize)> {
+        self.get_all_legal_moves_flat(color)
+            .into_iter()
+            .map(|((i1, j1), (i2, j2))| (i1, j1, i2, j2))
+            .collect()
+    }
+
+    /// Checks wether or not a move is a promotion move, i.e. a pawn moving to the last rank
+    fn is_promotion_move(&self, from : (usize, usize), to : (usize, usize)) -> bool {
+            if is_valid_move(from, to){
+                let pawn = match self.board[from.0][from.1] {
+                    Some(piece) if piece.piece_type == PieceType::Pawn => piece,
+                    _ => return false,
+                };
+
+                let promotion_rank = match pawn.color {
+                    Color::White => 0,
+                    Color::Black => 7,
+                };
+                
+                return to.0 == promotion_rank;
+            }
+
+            false
+        }
+
+    /// Returns a move with its flags and captured piece, before it is made.
+    /// `promotion` is only used for promotion moves
+    fn flagged_move(&self, from : (usize, usize), to : (usize, usize), promotion : PieceType) -> Move {
+        let flags = self.move_flags(from, to, promotion);
+
+        let captured_piece = match flags {
+            //the captured pawn is next to the capturing pawn
+            MoveFlags::EnPassant => self.board[from.0][to.1],
+            _ => self.board[to.0][to.1],
+        };
+
+        Move { captured_piece, ..Move::new(from, to, flags) }
+    }
+
+    /// Returns the kind of a move, before it is made. `promotion` is only used for promotion moves
+    fn move_flags(&self, from : (usize, usize), to : (usize, usize), promotion : PieceType) -> MoveFlags {
+        //from always holds the moving piece, so unwrap is safe
+        let piece = self.board[from.0][from.1].unwrap();
+
+        if self.is_promotion_move(from, to) {
+            return MoveFlags::Promotion(promotion);
+        }
+
+        match piece.piece_type {
+            PieceType::King if to.1 as i32 - from.1 as i32 == 2 => MoveFlags::KingsideCastle,
+            PieceType::King if from.1 as i32 - to.1 as i32 == 2 => MoveFlags::QueensideCastle,
+            PieceType::Pawn if from.1 != to.1 && self.board[to.0][to.1].is_none() => MoveFlags::EnPassant,
+            _ if self.board[to.0][to.1].is_some() => MoveFlags::Capture,
+            _ => MoveFlags::Quiet,
+        }
+    }
+
+    ///Returns Result, if Ok -> Vector of all legal moves (usize, usize) for the given square
+    /// 
+    /// Returns Err if provided index is invalid
+    fn get_pseudo_legal_moves_for_square(&self, i : usize, j : usize, only_attacked : bool) -> Result<Vec<(usize, usize)>, String>{
+        if !is_valid_index((i, j)) {
+            return Err(format!("Invalid index : Cannot compute pseudo-legal moves for index {i}, {j}"))
+        }
+
+        //since i, j is validated as a position all calls to pseudo_legal_moves
+        //will not panic when calling unwrap() in the respective function
+        match self.board[i][j] {
+            None => Ok(Vec::new()),
+            Some(piece) => match piece.piece_type {
+                PieceType::Pawn => Ok(self.pawn_pseudo_legal_moves(i, j, only_attacked)),
+                PieceType::Rook => Ok(self.directional_pseudo_legal_moves(i, j, &self.rook_move_directions, 8, only_attacked)),
+                PieceType::Bishop =>  Ok(self.directional_pseudo_legal_moves(i, j, &self.bishop_move_directions, 8, only_attacked)),
+                PieceType::Knight => Ok(self.directional_pseudo_legal_moves(i, j, &self.knight_move_directions, 1, only_attacked)),
+                PieceType::Queen => Ok(self.directional_pseudo_legal_moves(i, j, &self.queen_move_directions, 8, only_attacked)),
+                PieceType::King => Ok(self.king_pseudo_legal_moves(i, j, only_attacked)),
+            }
+        }
+    }
+
+    /// compute pseudo-legal moves for pieces that move in given directions
+    /// max_moves indicates how far a piece can "slide"
+    /// used for calculating pseudo-legal moves for every piece except for the pawn and king*
+    /// 
+    /// * the king has it's own function to include castling, but uses this function as well
+    /// 
+    /// # Panics
+    /// Function panics if there is not a piece at index i, j
+    /// 
+    /// Function should only be called thorugh get_pseudo_legal_moves_for_square() 
+    fn directional_pseudo_legal_moves(&self, i : usize, j : usize, directions : &Vec<(i32, i32)>, max_moves : u32, include_all_attacked : bool) -> Vec<(usize, usize)> {
+        let piece_color = self.board[i][j].unwrap().color;
+
+        let mut moves_vec : Vec<(usize, usize)> = Vec::new();
+
+        //loop thorugh all directions the piece can move in
+        for direction in directions {
+            //create new mutable indicies, i32 to allow for negative values
+            //movement directions may include negative values, so usize is not suitable
+            let mut i_m = i as i32;
+            let mut j_m = j as i32;
+
+            let (d_i, d_j) = direction;
+            let mut moves_made = 0;
+
+            while moves_made < max_moves {
+
+                i_m += d_i;
+                j_m += d_j;
+
+                if is_valid_pos(i_m, j_m) {
+                    //convert to usize for indexing
+                    let i_m = i_m as usize;
+                    let j_m = j_m as usize;
+
+                    //check if there is a piece at the given index i, j
+                    // No piece -> add index to moves vec
+                    // Piece of other color -> add piece to moves vec and break loop (go to next direction)
+                    // Piece of same color -> break loop (go to next direction)
+                    match self.board[i_m][j_m] {
+                        None => moves_vec.push((i_m, j_m)),
+                        Some(piece) => {
+                            if piece.color == piece_color {
+                                if include_all_attacked {
+                                    moves_vec.push((i_m, j_m));
+                                }
+                                break;
+                            } else {
+                                moves_vec.push((i_m, j_m));
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                moves_made += 1;
+            }
+        };
+
+        
+        moves_vec
+    }
+
+
+    /// # Panics
+    /// Function panics if there is not a piece at index i, j
+    /// 
+    /// Function should only be called thorugh get_pseudo_legal_moves_for_square() 
+    fn pawn_pseudo_legal_moves(&self, i : usize, j : usize, only_attacked : bool)-> Vec<(usize, usize)> {
+        let pawn_color = self.board[i][j].unwrap().color;
+
+        let d : i32 = match pawn_color {
+            Color::White => -1,
+            Color::Black => 1,
+        };
+
+        let mut moves_vec : Vec<(usize, usize)> = Vec::new();
+
+        let i_indx = i as i32 + d;
+
+        if !only_attacked {
+            //check squares in front of the pawn
+            if is_valid_pos(i_indx, j as i32){
+                let i_indx = i_indx as usize;
+                //square 1 in front
+                if self.board[i_indx][j].is_none(){
+                    moves_vec.push((i_indx, j));
+    
+                    //2 squares in front
+                    //only possible if pawn is on 2nd or 7th rank depending on color
+                    match pawn_color {
+                        Color::White => {
+                            if i == 6 && self.board[4][j].is_none(){
+                                moves_vec.push((4, j));
+                            }
+                        },
+            
+                        Color::Black => {
+                            if i == 1 && self.board[3][j].is_none(){
+                                moves_vec.push((3, j));
+                            }
+                        },
+                    };
+                }
+            }
+            //check squares that the pawn can capture
+            if is_valid_pos(i_indx, (j + 1) as i32){
+                let i_indx = i_indx as usize;
+                if self.pawn_can_capture(i_indx, j + 1, pawn_color) {
+                    moves_vec.push((i_indx, j + 1))
+                }
+            }
+    
+            if is_valid_pos(i_indx, j as i32 - 1){
+                let i_indx = i_indx as usize;
+                if self.pawn_can_capture(i_indx, j - 1, pawn_color) {
+                    moves_vec.push((i_indx, j - 1))
+                }
+            }
+        } else {
+            //check squares that the pawn can capture
+            if is_valid_pos(i_indx, (j + 1) as i32){
+                let i_indx = i_indx as usize;
+                moves_vec.push((i_indx, j + 1))
+            }
+    
+            if is_valid_pos(i_indx, j as i32 - 1){
+                let i_indx = i_indx as usize;
+                moves_vec.push((i_indx, j - 1))
+            }
+        }
+
+        moves_vec
+    }
+
+    fn pawn_can_capture(&self, i : usize, j : usize, pawn_color : Color) -> bool {
+        //checks if en passant is allowed
+        if let Some(en_passant_square) = self.en_passant_square{
+            if en_passant_square == (i, j){
+                match can_en_passant(i) {
+                    Some(color) => return color == pawn_color,
+                    None => return false,
+                }
+            }
+        }
+
+        //checks if pawn can move to given index
+        match self.board[i][j] {
+            Some(piece) if piece.color != pawn_color => return true,
+            _ => (),
+        }
+
+        false
+    }
+
+    /// # Panics
+    /// Function panics if there is not a piece at index i, j.
+    /// 
+    /// Will panic if Game.kingside_castle or Game.queenside_castle fields 
+    /// are missing values for Color::White or Color::Black, but this should
+    /// never be an issue when using constructors in the Game struct.
+    /// 
+    /// Function should only be called thorugh get_pseudo_legal_moves_for_square(), 
+    /// this will guarantee index i, j is a Piece.  
+    fn king_pseudo_legal_moves(&self, i : usize, j : usize, include_all_attacked : bool) -> Vec<(usize, usize)> {
+        let king_color = self.board[i][j].unwrap().color;
+        let mut move_vec = self.directional_pseudo_legal_moves(i, j, &self.queen_move_directions, 1, include_all_attacked);
+
+        //king_color should always be a key in kingside_castle and queenside_castle
+        //unwrap is safe
+        let kingside = self.kingside_castle.get(&king_color).unwrap();
+        let queenside = self.queenside_castle.get(&king_color).unwrap();
+
+        //castling can never capture a piece, so the castling squares
+        //are not under attack by the king
+        if include_all_attacked {
+            return move_vec;
+        }
+
+        //castling logic
+        
+        //castling rights from a FEN string are not checked against the board, so the
+        //king and rook must be on their starting squares. This also keeps j + 2 and
+        //j - 3 on the board
+        let home_rank = match king_color {
+            Color::White => 7,
+            Color::Black => 0,
+        };
+        let rook = Some(Piece::new(PieceType::Rook, king_color));
+        let on_start_square = i == home_rank && j == 4;
+
+        if *kingside && on_start_square && self.board[i][7] == rook {
+            //checks if squares between king and rook are empty, and are not attacked
+            if self.board[i][j + 1].is_none() && self.board[i][j + 2].is_none() {
+                let attacked_squres = self.get_attacked_squares(king_color.opposite());
+
+                if !attacked_squres.contains(&(i, j)) && !attacked_squres.contains(&(i, j + 1)) && !attacked_squres.contains(&(i, j + 2))
+                {
+                     move_vec.push((i, j + 2));
+                }
+            }
+        } 
+
+        if *queenside && on_start_square && self.board[i][0] == rook {
+            //checks if squares between king and rook are empty, only the squares
+            //the king passes through (e, d and c-file) must not be attacked.
+            //the b-file square is only crossed by the rook, so it must be empty
+            //but is allowed to be under attack
+            if self.board[i][j - 1].is_none() && self.board[i][j - 2].is_none() && self.board[i][j - 3].is_none() {
+                let attacked_squres = self.get_attacked_squares(king_color.opposite());
+
+                if !attacked_squres.contains(&(i, j)) && !attacked_squres.contains(&(i, j - 1)) && !attacked_squres.contains(&(i, j - 2))
+                {
+                     move_vec.push((i, j - 2));
+                }
+            }
+        }
+
+        move_vec
+    }
+    
+    /// Returns all squares under attack by `color`
+    fn get_attacked_squares(&self, color : Color) -> &Vec<(usize, usize)> {
+        match color {
+            Color::White => &(self.white_attacked_squares),
+            Color::Black => &(self.black_attacked_squares),
+        }
+    }
+
+    /// Update `white_attacked_squares` and `black_attacked_squares` field
+    /// in the Game object.
+    fn update_attacked_squares(&mut self) {
+        let mut white_attack_vec : Vec<(usize, usize)> = Vec::new();
+        let mut black_attack_vec : Vec<(usize, usize)> = Vec::new();
+
+        for i in 0..8 {
+            for j in 0..8 {
+                //check is board[i][j] is some, else get_pseudo_legal_moves_for_square will panic
+                if let Some(piece) = self.board[i][j]{
+                    match piece.color{
+                        //get_pseudo_legal_moves_for_square will return Some(), since
+                        //board[i][j] is a Piece, so the unwrap is safe
+                        Color::White => white_attack_vec.append(&mut self.get_pseudo_legal_moves_for_square(i, j, true).unwrap()),
+                        Color::Black => black_attack_vec.append(&mut self.get_pseudo_legal_moves_for_square(i, j, true).unwrap()),
+                    }
+                }
+            }
+        }
+
+        self.white_attacked_squares = white_attack_vec;
+        self.black_attacked_squares = black_attack_vec;
+    }
+
+    /// Checks if a pawn of `color` attacks `square` in the current position
+    fn is_defended_by_pawn(&self, square : (usize, usize), color : Color) -> bool {
+        //pawns attack from the rank behind the square, seen from the pawns side
+        let pawn_rank = match color {
+            Color::White => square.0 as i32 + 1,
+            Color::Black => square.0 as i32 - 1,
+        };
+
+        for file in [square.1 as i32 - 1, square.1 as i32 + 1] {
+            if is_valid_pos(pawn_rank, file)
+                && self.board[pawn_rank as usize][file as usize] == Some(Piece::new(PieceType::Pawn, color))
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Checks if a pawn of `color` attacks `square`, either now or after advancing
+    fn can_be_attacked_by_pawns(&self, square : (usize, usize), color : Color) -> bool {
+        for i in 0..8 {
+            for file in [square.1 as i32 - 1, square.1 as i32 + 1] {
+                if !is_valid_pos(i, file)
+                    || self.board[i as usize][file as usize] != Some(Piece::new(PieceType::Pawn, color))
+                {
+                    continue;
+                }
+
+                //white pawns move towards index 0, black pawns towards index 7
+                let behind_square = match color {
+                    Color::White => i as usize > square.0,
+                    Color::Black => (i as usize) < square.0,
+                };
+
+                if behind_square {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Checks if the piece at i, j attacks a piece along one of `directions`, with a less
+    /// valuable piece of the same color behind it
+    fn skewers_along(&self, i : usize, j : usize, directions : &Vec<(i32, i32)>) -> bool {
+        //function is only called with a piece at i, j, so unwrap is safe
+        let color = self.board[i][j].unwrap().color;
+
+        for (d_i, d_j) in directions {
+            let mut pieces_on_line = Vec::new();
+            let (mut i_m, mut j_m) = (i as i32 + d_i, j as i32 + d_j);
+
+            while is_valid_pos(i_m, j_m) && pieces_on_line.len() < 2 {
+                if let Some(piece) = self.board[i_m as usize][j_m as usize] {
+                    pieces_on_line.push(piece);
+                }
+
+                i_m += d_i;
+                j_m += d_j;
+            }
+
+            if let [front, back] = pieces_on_line[..] {
+                if front.color != color && back.color != color
+                    && piece_value(back.piece_type) < piece_value(front.piece_type)
+                {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    // returns every pair of a slider of piece_type and a queen of color with a
+    // clear line between them, along directions
+    fn batteries(&self, piece_type : PieceType, color : Color, directions : &Vec<(i32, i32)>) -> Vec<(usize, usize, usize, usize)> {
+        let queen = Some(Piece::new(PieceType::Queen, color));
+        let mut batteries = Vec::new();
+
+        for (i, j) in self.find_pieces(Piece::new(piece_type, color)) {
+            //with include_all_attacked each ray ends at the first piece, even if
+            //it is of the same color
+            for (i_q, j_q) in self.directional_pseudo_legal_moves(i, j, directions, 8, true) {
+                if self.board[i_q][j_q] == queen {
+                    batteries.push((i, j, i_q, j_q));
+                }
+            }
+        }
+
+        batteries
+    }
+
+    /// Returns position of every piece of `color` pinned to its king, in board order
+    fn pinned_pieces(&self, color : Color) -> Vec<(usize, usize)> {
+        let mut pinned = Vec::new();
+
+        let (king_i, king_j) = match self.find_king(color) {
+            Some(pos) => (pos.0 as i32, pos.1 as i32),
+            None => return pinned,
+        };
+
+        for &(d_i, d_j) in &self.queen_move_directions {
+            //rooks pin along ranks and files, bishops along diagonals
+            let pinning_type = if d_i == 0 || d_j == 0 {
+                PieceType::Rook
+            } else {
+                PieceType::Bishop
+            };
+
+            let mut first_piece = None;
+            let (mut i, mut j) = (king_i + d_i, king_j + d_j);
+
+            while is_valid_pos(i, j) {
+                if let Some(piece) = self.board[i as usize][j as usize] {
+                    match first_piece {
+                        None if piece.color == color => first_piece = Some((i as usize, j as usize)),
+                        Some(pos) if piece.color != color
+                            && (piece.piece_type == pinning_type || piece.piece_type == PieceType::Queen) =>
+                        {
+                            pinned.push(pos);
+                            break;
+                        },
+                        _ => break,
+                    }
+                }
+
+                i += d_i;
+                j += d_j;
+            }
+        }
+
+        pinned.sort();
+
+        pinned
+    }
+
+    // returns position of every piece of color, except the king, attacked by the opponent
+    fn attacked_pieces(&self, color : Color) -> Vec<(usize, usize)> {
+        let attacked_squares = self.get_attacked_squares(color.opposite());
+        let mut pieces = Vec::new();
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j] {
+                    if piece.color == color && piece.piece_type != PieceType::King && attacked_squares.contains(&(i, j)) {
+                        pieces.push((i, j));
+                    }
+                }
+            }
+        }
+
+        pieces
+    }
+
+    // increments the check count of color if the opponent is in check,
+    // attacked squares must be updated before calling
+    fn record_check(&mut self, color : Color) {
+        if self.in_check(color.opposite()) {
+            //color is always a key in checks_delivered, so unwrap is safe
+            *self.checks_delivered.get_mut(&color).unwrap() += 1;
+        }
+    }
+
+    // adds a piece captured on square to the list of captures, and to the reserve of
+    // the capturing player in crazyhouse, where a promoted piece is added as a pawn
+    fn record_capture(&mut self, piece : Piece, square : (usize, usize)) {
+        self.captures.push(piece);
+
+        let was_promoted = self.promoted_pieces & square_bit(square) != 0;
+        self.promoted_pieces &= !square_bit(square);
+
+        if self.game_mode == GameMode::Crazyhouse {
+            let reserve_type = if was_promoted {PieceType::Pawn} else {piece.piece_type};
+
+            //color is always a key in drop_reserve, so unwrap is safe
+            let reserve = self.drop_reserve.get_mut(&piece.color.opposite()).unwrap();
+            *reserve.entry(reserve_type).or_insert(0) += 1;
+        }
+    }
+
+    // moves the promoted mark of the piece on from, if any, to to
+    fn move_promoted_mark(&mut self, from : (usize, usize), to : (usize, usize)) {
+        if self.promoted_pieces & square_bit(from) != 0 {
+            self.promoted_pieces = self.promoted_pieces & !square_bit(from) | square_bit(to);
+        }
+    }
+
+    /// Checks wether `color` may drop `piece_type` on `to`, see `drop_piece()`.
+    ///
+    /// # Panics
+    /// * Panics if `to` is an invalid index.
+    fn drop_is_legal(&mut self, piece_type : PieceType, to : (usize, usize), color : Color) -> bool {
+        //color is always a key in drop_reserve, so unwrap is safe
+        let in_reserve = self.drop_reserve.get(&color).unwrap().get(&piece_type).copied().unwrap_or(0) > 0;
+
+        if !in_reserve || self.board[to.0][to.1].is_some() {
+            return false;
+        }
+
+        if piece_type == PieceType::Pawn && (to.0 == 0 || to.0 == 7) {
+            return false;
+        }
+
+        if !self.in_check(color) {
+            return true;
+        }
+
+        //a drop can only get out of check by blocking it
+        self.board[to.0][to.1] = Some(Piece::new(piece_type, color));
+        self.update_attacked_squares();
+
+        let legal = !self.in_check(color);
+
+        self.board[to.0][to.1] = None;
+        self.update_attacked_squares();
+
+        legal
+    }
+
+    // checks if color has any legal drop, see drop_piece()
+    fn has_legal_drop(&mut self, color : Color) -> bool {
+        //color is always a key in drop_reserve, so unwrap is safe
+        let piece_types : Vec<PieceType> = self.drop_reserve.get(&color).unwrap().keys().copied().collect();
+
+        for piece_type in piece_types {
+            for i in 0..8 {
+                for j in 0..8 {
+                    if self.drop_is_legal(piece_type, (i, j), color) {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    // updates state after the board has been edited manually, previous
+    // positions are no longer reachable through legal moves so history is cleared
+    fn after_board_edit(&mut self) {
+        self.undo_stack.clear();
+        self.last_move = None;
+        self.null_moves.clear();
+        self.en_passant_square = None;
+        self.remove_unusable_castling_rights();
+        self.zobrist_hash = self.compute_zobrist_hash();
+        self.update_attacked_squares();
+    }
+
+    // removes the castling rights of every king or rook that is not on its starting square
+    fn remove_unusable_castling_rights(&mut self) {
+        for (color, rank) in [(Color::White, 7), (Color::Black, 0)] {
+            let king = self.board[rank][4] == Some(Piece::new(PieceType::King, color));
+            let rook = Some(Piece::new(PieceType::Rook, color));
+
+            //color is always a key in kingside_castle and queenside_castle, so unwrap is safe
+            let kingside = *self.kingside_castle.get(&color).unwrap();
+            let queenside = *self.queenside_castle.get(&color).unwrap();
+
+            self.kingside_castle.insert(color, kingside && king && self.board[rank][7] == rook);
+            self.queenside_castle.insert(color, queenside && king && self.board[rank][0] == rook);
+        }
+    }
+
+    /// Places `piece` on `square`, keeping the Zobrist hash up to date
+    fn set_square(&mut self, square : (usize, usize), piece : Option<Piece>) {
+        if let Some(old) = self.board[square.0][square.1] {
+            self.zobrist_hash ^= zobrist::piece_key(old, square);
+        }
+
+        if let Some(new) = piece {
+            self.zobrist_hash ^= zobrist::piece_key(new, square);
+        }
+
+        self.board[square.0][square.1] = piece;
+    }
+
+    /// Returns the Zobrist hash of the position, computed from scratch
+    fn compute_zobrist_hash(&self) -> u64 {
+        let mut hash = self.zobrist_state_key();
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j] {
+                    hash ^= zobrist::piece_key(piece, (i, j));
+                }
+            }
+        }
+
+        hash
+    }
+
+    /// Returns the part of the Zobrist hash for the side to move, castling rights
+    /// and en passant square
+    fn zobrist_state_key(&self) -> u64 {
+        let mut key = 0;
+
+        if self.turn == Color::Black {
+            key ^= zobrist::side_key();
+        }
+
+        for color in [Color::White, Color::Black] {
+            //color is always a key in kingside_castle and queenside_castle, so unwrap is safe
+            if *self.kingside_castle.get(&color).unwrap() {
+                key ^= zobrist::castling_key(color, true);
+            }
+
+            if *self.queenside_castle.get(&color).unwrap() {
+                key ^= zobrist::castling_key(color, false);
+            }
+        }
+
+        if let Some((_, j)) = self.en_passant_square {
+            if self.en_passant_capture_is_legal() {
+                key ^= zobrist::en_passant_key(j);
+            }
+        }
+
+        key
+    }
+
+    /// Returns bool representing wether the active player can capture en passant
+    /// without leaving their king in check
+    fn en_passant_capture_is_legal(&self) -> bool {
+        let (i, j) = match self.en_passant_square {
+            Some(square) => square,
+            None => return false,
+        };
+
+        let color = self.turn;
+        let pawn = Piece::new(PieceType::Pawn, color);
+
+        //row of the pawn that made the double push, next to the capturing pawns
+        let pawn_row = match color {
+            Color::White => i + 1,
+            Color::Black => i.wrapping_sub(1),
+        };
+
+        if pawn_row >= 8 || self.board[pawn_row][j] != Some(Piece::new(PieceType::Pawn, color.opposite())) {
+            return false;
+        }
+
+        for capture_file in [j.wrapping_sub(1), j + 1] {
+            if capture_file >= 8 || self.board[pawn_row][capture_file] != Some(pawn) {
+                continue;
+            }
+
+            //make the capture on a copy of the board only, the move is not recorded
+            let mut position = Game::new_empty();
+            position.board = self.board;
+            position.board[pawn_row][capture_file] = None;
+            position.board[pawn_row][j] = None;
+            position.board[i][j] = Some(pawn);
+
+            match position.find_king(color) {
+                Some(king) if !position.attackers_of(king, color.opposite()).is_empty() => (),
+                _ => return true,
+            }
+        }
+
+        false
+    }
+
+    /// Returns position of every square containing `piece`, in board order
+    fn find_pieces(&self, piece : Piece) -> Vec<(usize, usize)> {
+        let mut positions = Vec::new();
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if self.board[i][j] == Some(piece) {
+                    positions.push((i, j));
+                }
+            }
+        }
+
+        positions
+    }
+
+    /// Returns a bitmask of the files with pawns of `color`, bit `j` set for file `j`
+    fn pawn_file_mask(&self, color : Color) -> u8 {
+        (0..8)
+            .filter(|&j| self.pawns_on_file(j, color) > 0)
+            .fold(0, |mask, j| mask | (1 << j))
+    }
+
+    /// Returns how many pawns of `color` are on file `j`
+    fn pawns_on_file(&self, j : usize, color : Color) -> u32 {
+        (0..8)
+            .filter(|&i| self.board[i][j] == Some(Piece::new(PieceType::Pawn, color)))
+            .count() as u32
+    }
+
+    /// Returns position of the king of `color`, or None if there is no king
+    fn find_king(&self, color : Color) -> Option<(usize, usize)> {
+        for i in 0..8 {
+            for j in 0..8 {
+                if self.board[i][j] == Some(Piece::new(PieceType::King, color)) {
+                    return Some((i, j));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Checks if `color` has enough pieces to win.
+    fn can_win(&self, color : Color) -> bool {
+
+        let mut pieces = Vec::new();
+        let mut bishop_squares = Vec::new();
+
+        for i in 0..8 {
+            for j in 0..8 {
+                if let Some(piece) = self.board[i][j]{
+                    if piece.color == color {
+                        pieces.push(piece.piece_type);
+
+                        if piece.piece_type == PieceType::Bishop {
+                            bishop_squares.push((i, j));
+                        }
+                    }
+                }
+            }
+        }
+
+        // king and two bishops on the same square color can't force mate
+        if pieces.len() == 3 && bishop_squares.len() == 2
+            && square_color(bishop_squares[0]) == square_color(bishop_squares[1]) {
+            return false;
+        }
+
+        !self.insufficient_material.contains(&pieces)
+    }
+}
+
+
+/// Enum for representing the state of a chess game.
+/// 
+/// # Values
+/// * `InProgress`: The game is ongoing.
+/// * `AwaitPromotion`: Waiting for user to choose promotion piece. 
+/// * `Win(WinState)`: One player has won, which player won and how they won
+///   is defined in `WinState`.
+/// * `Draw`: Position is a draw, the cause for the draw is defined in `DrawState` 
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameState {
+    InProgress,
+    AwaitPromotion,
+    Win(WinState),
+    Draw(DrawState),
+}
+
+/// Enum for the rules a game is played with.
+///
+/// # Variants
+/// * `Standard`: Standard chess.
+/// * `Crazyhouse`: Captured pieces are added to the capturing player's reserve,
+///   and can be dropped back onto the board using `Game::drop_piece()`.
+/// * `ThreeCheck`: The first player to give check three times wins.
+/// * `KingOfTheHill`: The first player to move their king to one of the
+///   `HILL_SQUARES` wins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameMode {
+    Standard,
+    Crazyhouse,
+    ThreeCheck,
+    KingOfTheHill,
+}
+
+/// Maximum number of half-moves played in each game of `Game::random_games()`.
+pub const RANDOM_GAMES_MAX_MOVES : u32 = 200;
+
+/// Array indicies of the center squares d4, d5, e4 and e5, used in `GameMode::KingOfTheHill`.
+pub const HILL_SQUARES : [(usize, usize); 4] = [(4, 3), (3, 3), (4, 4), (3, 4)];
+
+/// Which player controls a file with their rooks and queens, see `Game::file_control()`.
+///
+/// # Variants
+/// * `White`: Only white has a rook or queen on the file.
+/// * `Black`: Only black has a rook or queen on the file.
+/// * `Contested`: Both players have a rook or queen on the file.
+/// * `None`: Neither player has a rook or queen on the file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileControl {
+    White,
+    Black,
+    Contested,
+    None,
+}
+
+impl fmt::Display for FileControl {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FileControl::White => "white",
+            FileControl::Black => "black",
+            FileControl::Contested => "contested",
+            FileControl::None => "none",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Draw states used in `GameState::Draw`
+///
+/// # Notes
+/// * `ThreefoldRepetition` is reached as soon as the position occurs for the
+///   third time, see `Game::repetition_count()`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawState {
+    Stalemate,
+    InsufficientMaterial,
+    FiftyMoveRule,
+    ThreefoldRepetition,
+}
+#[derive(Debug, Clone, PartialEq)]
+/// Win state used in `GameState::Win`.
+/// 
+/// # Variants
+/// * `Checkmate(Color)`: `Color` represents the color of the winner.
+/// * `Resignation(Color)`: `Color` represents the color of the player who resigned.
+/// * `ThreeChecks(Color)`: `Color` represents the color of the winner, who has given
+///   three checks in `GameMode::ThreeCheck`.
+/// * `KingOfTheHill(Color)`: `Color` represents the color of the winner, whose king
+///   has reached one of the `HILL_SQUARES` in `GameMode::KingOfTheHill`.
+pub enum WinState {
+    Checkmate(Color),
+    Resignation(Color),
+    ThreeChecks(Color),
+    KingOfTheHill(Color),
+}
+/// Kind of move, see `Move`.
+///
+/// # Variants
+/// * `Quiet`: A move that is not a capture and none of the special moves below.
+/// * `Capture`: A capture, other than en passant.
+/// * `EnPassant`: A pawn capturing en passant.
+/// * `KingsideCastle` and `QueensideCastle`: Castling, the move is the move of the king.
+/// * `Promotion(PieceType)`: A pawn reaching the last rank, with or without capturing,
+///   and the piece it is promoted to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveFlags {
+    Quiet,
+    Capture,
+    EnPassant,
+    KingsideCastle,
+    QueensideCastle,
+    Promotion(PieceType),
+}
+
+/// A move written as the array indicies of the square moved from and the square
+/// moved to, for more detail refer to `Game` struct.
+pub type IndexMove = ((usize, usize), (usize, usize));
+
+/// A square on the board, used instead of array index tuples so rank and file
+/// can not be mixed up.
+///
+/// # Fields
+/// * `rank` and `file` are array indicies, for more detail refer to `Game` struct,
+///   e.g. `Square::new(0, 0)` is a8.
+///
+/// # Examples
+///
+/// ```ignore
+/// let square : Square = "e4".parse().unwrap();
+///
+/// assert_eq!(square, Square::new(4, 4).unwrap());
+/// assert_eq!(square.to_string(), "e4");
+/// assert_eq!(<(usize, usize)>::from(square), (4, 4));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Hash)]
+pub struct Square {
+    rank : usize,
+    file : usize,
+}
+
+impl Square {
+    /// Create a square from array indicies.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if `rank` or `file` is not in `0..8`.
+    pub fn new(rank : usize, file : usize) -> Result<Square, String> {
+        if !is_valid_index((rank, file)) {
+            return Err(format!("Invalid index {:?}", (rank, file)));
+        }
+
+        Ok(Square { rank, file })
+    }
+
+    /// Create a square from algebraic notation, e.g. `"e4"`.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if `notation` is not a valid square.
+    pub fn from_alg(notation : &str) -> Result<Square, String> {
+        let (rank, file) = alg_notation_to_indx(notation)?;
+
+        Ok(Square { rank, file })
+    }
+
+    /// Get the square in algebraic notation, e.g. `"e4"`.
+    pub fn to_alg(&self) -> String {
+        //rank and file are always valid, so unwrap is safe
+        indx_to_alg_notation((self.rank, self.file)).unwrap()
+    }
+
+    /// Get the array index of the rank, `0` being rank 8.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Get the array index of the file, `0` being the a-file.
+    pub fn file(&self) -> usize {
+        self.file
+    }
+}
+
+impl From<Square> for (usize, usize) {
+    fn from(square : Square) -> (usize, usize) {
+        (square.rank, square.file)
+    }
+}
+
+impl TryFrom<(usize, usize)> for Square {
+    type Error = String;
+
+    fn try_from(indx : (usize, usize)) -> Result<Square, String> {
+        Square::new(indx.0, indx.1)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_alg())
+    }
+}
+
+impl FromStr for Square {
+    type Err = String;
+
+    fn from_str(notation : &str) -> Result<Square, String> {
+        Square::from_alg(notation)
+    }
+}
+
+/// A move from one square to another, see `Game::get_legal_moves_as_moves()`.
+///
+/// # Fields
+/// * `from` and `to` are array indicies, for more detail refer to `Game` struct.
+/// * `flags` is the kind of move, see `MoveFlags`.
+/// * `captured_piece` is the piece the move captures, if any. Only set for moves
+///   returned by `Game`, it is not needed to play a move.
+///
+/// # Notes
+/// * Moves are compared by `from`, `to` and `flags` only, so a move created with
+///   `Move::new()` equals the same move returned by `Game` with `captured_piece` set.
+#[derive(Debug, Clone, Copy)]
+pub struct Move {
+    pub from : (usize, usize),
+    pub to : (usize, usize),
+    pub flags : MoveFlags,
+    pub captured_piece : Option<Piece>,
+}
+
+//implements equality for moves, ignoring captured_piece, see Move
+impl PartialEq for Move {
+    fn eq(&self, other : &Move) -> bool {
+        self.from == other.from && self.to == other.to && self.flags == other.flags
+    }
+}
+
+impl Move {
+    pub fn new(from : (usize, usize), to : (usize, usize), flags : MoveFlags) -> Move {
+        Move { from, to, flags, captured_piece : None }
+    }
+
+    /// Create a move from two squares in algebraic notation, e.g. `"e2"` and `"e4"`.
+    /// The flags are `MoveFlags::Quiet`, since the kind of move depends on the position.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if either square is invalid notation.
+    pub fn from_alg(from : &str, to : &str) -> Result<Move, String> {
+        Ok(Move::new(alg_notation_to_indx(from)?, alg_notation_to_indx(to)?, MoveFlags::Quiet))
+    }
+}
+
+// changes made by a single move or drop, see Game::undo_last_move()
+#[derive(Debug, Clone, PartialEq)]
+struct UndoRecord {
+    //square the piece moved from, None for drops
+    from : Option<(usize, usize)>,
+    to : (usize, usize),
+    //piece as it was before the move, i.e. a pawn for promotions
+    moved_piece : Piece,
+    //captured piece and the square it was captured on, which differs from to for en passant
+    captured : Option<(Piece, (usize, usize))>,
+    //squares the rook moved between when castling
+    rook_move : Option<IndexMove>,
+    //state before the move, castling rights are white kingside, white queenside,
+    //black kingside and black queenside
+    castling_rights : [bool; 4],
+    castled : bool,
+    turn : Color,
+    en_passant_square : Option<(usize, usize)>,
+    half_moves : u32,
+    full_moves : u32,
+    checks_delivered : [u32; 2],
+    resigned : Option<Color>,
+    last_move : Option<IndexMove>,
+    promotion_square : Option<(usize, usize)>,
+    promoted_pieces : u64,
+    zobrist_hash : u64,
+}
+
+/// Engine analysis of a single move, see `Game::annotate_last_move()`.
+///
+/// # Fields
+/// * `evaluation` is the evaluation after the move in centipawns, from
+///   white's perspective.
+/// * `best_line` is the expected continuation, in SAN.
+/// * `time_ms` is the time spent analysing the move, in milliseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveAnnotation {
+    pub evaluation : i32,
+    pub best_line : Vec<String>,
+    pub time_ms : u64,
+}
+
+impl MoveAnnotation {
+    /// Format the annotation as a PGN comment, e.g. `{ +0.35/2 }` where the
+    /// evaluation is in pawns and the number after the slash is the length
+    /// of `best_line`. The length is left out if `best_line` is empty.
+    pub fn to_pgn_comment(&self) -> String {
+        let sign = if self.evaluation < 0 {"-"} else {"+"};
+        let pawns = self.evaluation.unsigned_abs();
+        let mut comment = format!("{{ {}{}.{:02}", sign, pawns / 100, pawns % 100);
+
+        if !self.best_line.is_empty() {
+            comment.push_str(&format!("/{}", self.best_line.len()));
+        }
+
+        comment.push_str(" }");
+
+        comment
+    }
+}
+
+/// Error returned by `Game::from_fen()` when a FEN string is invalid.
+///
+/// # Fields
+/// * `field_index` is the index of the invalid field, from `0` for the piece
+///   placement to `5` for the full move number. If fields are missing, it is the
+///   index of the first missing field.
+/// * `expected` describes valid input for the field.
+/// * `got` is the invalid input.
+///
+/// # Notes
+/// * Can be converted into a `String` with the `?` operator, for use in
+///   functions returning `Result<_, String>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FenParseError {
+    pub field_index : usize,
+    pub expected : String,
+    pub got : String,
+}
+
+impl FenParseError {
+    fn new(field_index : usize, expected : &str, got : &str) -> FenParseError {
+        FenParseError {
+            field_index,
+            expected : expected.to_string(),
+            got : got.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FenParseError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let field = match self.field_index {
+            0 => "piece placement",
+            1 => "active color",
+            2 => "castling availability",
+            3 => "en passant square",
+            4 => "half move clock",
+            5 => "full move number",
+            _ => "unknown",
+        };
+
+        write!(f, "Invalid FEN {} field, expected {} but got '{}'", field, self.expected, self.got)
+    }
+}
+
+impl std::error::Error for FenParseError {}
+
+impl From<FenParseError> for String {
+    fn from(error : FenParseError) -> String {
+        error.to_string()
+    }
+}
+
+/// Opening book mapping positions to book moves in UCI format, e.g. `"e2e4"`.
+///
+/// # Creation
+/// * `OpeningBook::new()` creates an empty book, positions are added with
+///   `add_position()`.
+/// * `OpeningBook::from_polyglot()` reads the entries of a Polyglot book.
+///
+/// # Notes
+/// * Positions are looked up using `Game::polyglot_key()`, so books read with
+///   `from_polyglot()` and books built with `add_position()` use the same keys.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OpeningBook {
+    entries : HashMap<u64, Vec<String>>,
+}
+
+impl OpeningBook {
+    /// Create an empty opening book.
+    pub fn new() -> OpeningBook {
+        OpeningBook { entries : HashMap::new() }
+    }
+
+    /// Read an opening book in the Polyglot binary format. Each entry is 16 bytes,
+    /// a big endian 64 bit position key followed by a 16 bit move, a 16 bit weight
+    /// and a 32 bit learn value. The moves of a position are stored with the
+    /// highest weight first.
+    ///
+    /// # Errors
+    /// * Returns `Err(String)` if the length of `data` is not a multiple of 16,
+    ///   or if an entry contains an invalid move.
+    ///
+    /// # Notes
+    /// * Polyglot writes castling as the king capturing its own rook, e.g. `"e1h1"`.
+    ///   These moves are converted to the king's destination by `Game::book_moves()`.
+    /// * Learn values are ignored.
+    pub fn from_polyglot(data : &[u8]) -> Result<OpeningBook, String> {
+        if !data.len().is_multiple_of(16) {
+            return Err(format!("Invalid Polyglot book length {}", data.len()));
+        }
+
+        let mut weighted_entries : HashMap<u64, Vec<(u16, String)>> = HashMap::new();
+
+        for entry in data.chunks(16) {
+            //chunks are always 16 bytes, so the conversions are safe
+            let key = u64::from_be_bytes(entry[0..8].try_into().unwrap());
+            let raw_move = u16::from_be_bytes(entry[8..10].try_into().unwrap());
+            let weight = u16::from_be_bytes(entry[10..12].try_into().unwrap());
+
+            weighted_entries.entry(key).or_default().push((weight, polyglot_move_to_uci(raw_move)?));
+        }
+
+        let entries = weighted_entries.into_iter().map(|(key, mut moves)| {
+            //stable sort, so moves with equal weight keep the order of the file
+            moves.sort_by_key(|(weight, _)| std::cmp::Reverse(*weight));
+            (key, moves.into_iter().map(|(_, uci)| uci).collect())
+        }).collect();
+
+        Ok(OpeningBook { entries })
+    }
+
+    /// Add `uci_move` as a book move in the position of `game`.
+    pub fn add_position(&mut self, game : &Game, uci_move : &str) {
+        self.entries.entry(game.polyglot_key()).or_default().push(uci_move.to_string());
+    }
+
+    /// Returns the number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns bool representing wether the book contains no positions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Chess clock for games with a time control, see `Game::attach_clock()`.
+///
+/// # Creation
+/// * `Clock::new(initial_ms, increment_ms)` gives both players `initial_ms`
+///   milliseconds, and adds `increment_ms` after every move.
+///
+/// # Notes
+/// * Only available with the `std` feature, which is enabled by default, since
+///   the clock is timed with `std::time::Instant`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clock {
+    pub white_ms : u64,
+    pub black_ms : u64,
+    pub increment_ms : u64,
+    //player whose time is running and when their clock was started
+    last_move_start : Option<(Color, Instant)>,
+}
+
+#[cfg(feature = "std")]
+impl Clock {
+    /// Create a stopped clock with `initial_ms` milliseconds for both players.
+    pub fn new(initial_ms : u64, increment_ms : u64) -> Clock {
+        Clock {
+            white_ms : initial_ms,
+            black_ms : initial_ms,
+            increment_ms,
+            last_move_start : None,
+        }
+    }
+
+    // returns the remaining time of color, including time used since the clock was started
+    fn remaining(&self, color : Color) -> u64 {
+        let stored = match color {
+            Color::White => self.white_ms,
+            Color::Black => self.black_ms,
+        };
+
+        match self.last_move_start {
+            Some((running, start)) if running == color => {
+                stored.saturating_sub(start.elapsed().as_millis() as u64)
+            },
+            _ => stored,
+        }
+    }
+}
+
+/// Struct for representing a chess piece.
+/// 
+/// # Creation
+/// * `piece_type` represents what type of piece it is e.g. pawn, 
+///   knight, bishop etc.
+/// * `color` represents the color of the piece, `Color::White` or `Color::Black`
+/// 
+/// # Examples
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Hash)]
+pub struct Piece {
+    pub piece_type : PieceType,
+    pub color : Color,
+}
+
+impl Piece {
+    pub fn new(piece_type : PieceType, color : Color) -> Piece{
+        Piece {
+            piece_type,
+            color,
+        }
+    }
+}
+
+/// Enum for all types of standard chess pieces
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Hash)]
+pub enum PieceType {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+/// Enum for piece color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Hash)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    /// Returns the opposite color of the piece
+    pub fn opposite(&self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+fn is_valid_pos(i : i32, j : i32) -> bool {
+    (0..=7).contains(&i) && (0..=7).contains(&j)
+}
+
+// same as is_valid_pos(), but for array indicies, compared as usize so large
+// indicies are not truncated
+fn is_valid_index(index : (usize, usize)) -> bool {
+    index.0 < 8 && index.1 < 8
+}
+
+// returns the bit of square in a 64 bit board mask, bit i * 8 + j for square (i, j)
+fn square_bit(square : (usize, usize)) -> u64 {
+    1 << (square.0 * 8 + square.1)
+}
+
+fn is_valid_move(from : (usize, usize), to : (usize, usize)) -> bool {
+    is_valid_index(from) && is_valid_index(to)
+}
+
+
+fn get_piece(chr : char) -> Result<Piece, String> {
+    match chr {
+        'P' => Ok(Piece::new(PieceType::Pawn, Color::White)),
+        'N' => Ok(Piece::new(PieceType::Knight, Color::White)),
+        'B' => Ok(Piece::new(PieceType::Bishop, Color::White)),
+        'R' => Ok(Piece::new(PieceType::Rook, Color::White)),
+        'Q' => Ok(Piece::new(PieceType::Queen, Color::White)),
+        'K' => Ok(Piece::new(PieceType::King, Color::White)),
+        'p' => Ok(Piece::new(PieceType::Pawn, Color::Black)),
+        'n' => Ok(Piece::new(PieceType::Knight, Color::Black)),
+        'b' => Ok(Piece::new(PieceType::Bishop, Color::Black)),
+        'r' => Ok(Piece::new(PieceType::Rook, Color::Black)),
+        'q' => Ok(Piece::new(PieceType::Queen, Color::Black)),
+        'k' => Ok(Piece::new(PieceType::King, Color::Black)),
+        e => Err(e.to_string())
+    }       
+}
+
+fn get_piece_notation(piece : Piece) -> char {
+    let mut letter = match piece.piece_type {
+        PieceType::Pawn=> 'P',
+        PieceType::Knight => 'N',
+        PieceType::Bishop => 'B',
+        PieceType::Rook => 'R',
+        PieceType::Queen => 'Q',
+        PieceType::King => 'K',
+    };
+
+    if piece.color == Color::Black {
+        letter = letter.to_ascii_lowercase();
+    }
+
+    letter
+}
+
+fn get_unicode_repr(piece : Piece) -> char {
+    match piece.color {
+        Color::White => match piece.piece_type {
+            PieceType::Pawn => '♙',
+            PieceType::Knight => '♘',
+            PieceType::Bishop => '♗',
+            PieceType::Rook => '♖',
+            PieceType::Queen => '♕',
+            PieceType::King => '♔',
+        }
+        Color::Black => match piece.piece_type {
+            PieceType::Pawn => '♟',
+            PieceType::Knight => '♞',
+            PieceType::Bishop => '♝',
+            PieceType::Rook => '♜',
+            PieceType::Queen => '♛',
+            PieceType::King => '♚',
+        }
+    }
+}
+
+fn get_repr(piece : Piece) -> char {
+    match piece.color {
+        Color::White => match piece.piece_type {
+            PieceType::Pawn => 'P', 
+            PieceType::Knight => 'N', 
+            PieceType::Bishop => 'B', 
+            PieceType::Rook => 'R', 
+            PieceType::Queen => 'Q', 
+            PieceType::King => 'K', 
+        }
+        Color::Black => match piece.piece_type {
+            PieceType::Pawn => 'p', 
+            PieceType::Knight => 'n', 
+            PieceType::Bishop => 'b', 
+            PieceType::Rook => 'r', 
+            PieceType::Queen => 'q', 
+            PieceType::King => 'k', 
+        }
+    }
+}
+
+/// Get array indicies for a give `notation` written in
+/// algebraic notation.
+/// 
+/// # Arguments
+/// * `notation` is a `str` describing a square on the board in algebraic notation.
+/// 
+/// # Returns
+/// * A `Result` containing the array index `(usize, usize)` corresponding
+///   to the input algebraic notation.
+/// 
+/// # Errors
+/// * Returns `Err(String)` if the provided notation is invalid
+pub fn alg_notation_to_indx(notation : &str) -> Result<(usize , usize), String> {
+    let chr_vec = notation
+        .chars()
+        .collect::<Vec<char>>();
+
+    if chr_vec.len() != 2 {
+        return Err(format!("Invalid notation {}", notation));
+    }
+
+    let col : usize = match chr_vec[0] {
+        'a' => 0,
+        'b' => 1,
+        'c' => 2,
+        'd' => 3,
+        'e' => 4,
+        'f' => 5,
+        'g' => 6,
+        'h' => 7,
+        _c => return Err(format!("Invalid file {}", _c)),
+    };
+
+    // 8 - n since ranks in the array are mirrored, and the first rank is at index 7
+    let row = match chr_vec[1].to_digit(10) {
+        Some(digit) if (1..=8).contains(&digit) => 8 - digit as usize,
+        _ => return Err(format!("Invalid row {}", chr_vec[1]))
+    };
+    
+    
+    Ok((row, col))
+}
+
+/// Get algebraic notation for a given `indx`.
+/// 
+/// # Returns
+/// * `Result` containing the algebraic notation as a `String`
+/// 
+/// # Errors
+/// * Returns `Err(String)` if provided index is invalid.
+pub fn indx_to_alg_notation(indx : (usize, usize)) -> Result<String, String> {
+    let rank : char = match indx.1 {
+        0 => 'a',
+        1 => 'b',
+        2 => 'c',
+        3 => 'd',
+        4 => 'e',
+        5 => 'f',
+        6 => 'g',
+        7 => 'h',
+        _c => return Err(format!("Invalid column {}", _c)),
+    };
+
+    // 8 - n since ranks in the array are mirrored, and the first rank is at index 7
+    let col = match char::from_digit(8 - indx.0 as u32, 10) {
+        Some(c) => c,
+        _ => return Err(format!("Invalid row {}", indx.0)),
+    };
+
+    let mut alg_notation = String::new();
+
+    alg_notation.push(rank);
+    alg_notation.push(col);
+
+    Ok(alg_notation)
+}
+
+/// Get the material value of a piece type in centipawns.
+///
+/// # Returns
+/// * `i32` being `100` for a pawn, `320` for a knight, `330` for a bishop,
+///   `500` for a rook and `900` for a queen. The king is given the value `20000`,
+///   since it can never be traded.
+pub fn piece_value(piece_type : PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 20000,
+    }
+}
+
+/// Get the color of a square on the board.
+///
+/// # Arguments
+/// * `square` - Array index of the square.
+///
+/// # Returns
+/// * `Color::White` for light squares and `Color::Black` for dark squares.
+///
+/// # Examples
+/// ```ignore
+/// assert_eq!(square_color((0, 0)), Color::White); // a8
+/// assert_eq!(square_color((7, 0)), Color::Black); // a1
+/// ```
+pub fn square_color(square : (usize, usize)) -> Color {
+    if (square.0 + square.1).is_multiple_of(2) {
+        return Color::White;
+    }
+
+    Color::Black
+}
+
+/// Get the squares strictly between two squares on the same rank, file or diagonal,
+/// e.g. the squares a piece could be placed on to block a check along that line.
+///
+/// # Arguments
+/// * `a` and `b` - Array indicies of the squares, for more detail refer to `Game` struct.
+///
+/// # Returns
+/// * `Vec` of array indicies ordered from `a` towards `b`. Empty if the squares do not
+///   share a rank, file or diagonal, or if they are adjacent.
+///
+/// # Examples
+/// ```ignore
+/// assert_eq!(squares_between((7, 0), (7, 4)), vec![(7, 1), (7, 2), (7, 3)]); // a1 to e1
+/// assert!(squares_between((7, 0), (6, 2)).is_empty());
+/// ```
+pub fn squares_between(a : (usize, usize), b : (usize, usize)) -> Vec<(usize, usize)> {
+    let d_i = b.0 as i32 - a.0 as i32;
+    let d_j = b.1 as i32 - a.1 as i32;
+
+    if a == b || (d_i != 0 && d_j != 0 && d_i.abs() != d_j.abs()) {
+        return Vec::new();
+    }
+
+    let steps = d_i.abs().max(d_j.abs());
+    let (step_i, step_j) = (d_i.signum(), d_j.signum());
+
+    (1..steps)
+        .map(|n| ((a.0 as i32 + step_i * n) as usize, (a.1 as i32 + step_j * n) as usize))
+        .collect()
+}
+
+/// Get the FEN of every position in a game written in Portable Game Notation (PGN),
+/// e.g. for building training data from a game database.
+///
+/// # Arguments
+/// * `pgn` - A single PGN game. Tag pairs are skipped, except `FEN` which sets the
+///   starting position. Comments, variations and numeric annotations are ignored.
+///
+/// # Returns
+/// * `Vec` containing the FEN of the starting position followed by the FEN after
+///   each half move, so its length is the number of half moves plus one.
+///
+/// # Examples
+/// ```ignore
+/// let pgn = "[Event \"?\"]\n[Result \"*\"]\n\n1. e4 {best by test} e5 (1... c5) 2. Nf3 *";
+/// let fens = pgn_to_fen_list(pgn).unwrap();
+///
+/// assert_eq!(fens.len(), 4);
+/// assert_eq!(fens[3], "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+/// ```
+///
+/// # Errors
+/// * Returns `Err(String)` if the `FEN` tag or a move is invalid, or if a comment or
+///   variation is not closed.
+pub fn pgn_to_fen_list(pgn : &str) -> Result<Vec<String>, String> {
+    let mut game = match pgn_tag_value(pgn, "FEN") {
+        Some(fen) => Game::from_fen(fen)?,
+        None => Game::new_starting_pos(),
+    };
+
+    let mut fens = vec![game.to_fen()];
+
+    for (move_index, san) in pgn_move_tokens(pgn)?.iter().enumerate() {
+        game.make_move_san(san, true)
+            .map_err(|reason| format!("Invalid SAN at move {} '{}': {}", move_index, san, reason))?;
+
+        fens.push(game.to_fen());
+    }
+
+    Ok(fens)
+}
+
+// returns the value of a PGN tag pair, e.g. `[FEN "..."]`
+fn pgn_tag_value<'a>(pgn : &'a str, tag : &str) -> Option<&'a str> {
+    pgn_tag_pairs(pgn)
+        .into_iter()
+        .find(|(name, _)| *name == tag)
+        .map(|(_, value)| value)
+}
+
+// returns the name and value of every PGN tag pair, in the order they are written
+fn pgn_tag_pairs(pgn : &str) -> Vec<(&str, &str)> {
+    pgn.lines()
+        .map(|line| line.trim())
+        .filter_map(|line| line.strip_prefix('[')?.strip_suffix(']'))
+        .filter_map(|pair| pair.split_once(' '))
+        .map(|(name, value)| (name, value.trim().trim_matches('"')))
+        .collect()
+}
+
+// returns the moves of a PGN game in SAN, skipping tag pairs, move numbers,
+// comments, variations, numeric annotation glyphs and the result
+fn pgn_move_tokens(pgn : &str) -> Result<Vec<String>, String> {
+    let mut movetext = String::new();
+
+    for line in pgn.lines() {
+        let line = line.trim();
+
+        //tag pairs and escaped lines are not part of the movetext
+        if line.starts_with('[') || line.starts_with('%') {
+            continue;
+        }
+
+        movetext.push_str(line);
+        movetext.push('\n');
+    }
+
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut variation_depth = 0;
+    let mut in_comment = false;
+    let mut in_line_comment = false;
+
+    for c in movetext.chars() {
+        if in_comment {
+            in_comment = c != '}';
+            continue;
+        }
+
+        if in_line_comment {
+            in_line_comment = c != '\n';
+            continue;
+        }
+
+        match c {
+            '{' => in_comment = true,
+            ';' => in_line_comment = true,
+            '(' => variation_depth += 1,
+            ')' if variation_depth == 0 => return Err("Unexpected ')' in PGN".to_string()),
+            ')' => variation_depth -= 1,
+            _ if variation_depth > 0 => (),
+            c if c.is_whitespace() => (),
+            c => {
+                token.push(c);
+                continue;
+            }
+        }
+
+        if !token.is_empty() {
+            tokens.push(std::mem::take(&mut token));
+        }
+    }
+
+    if in_comment || variation_depth > 0 {
+        return Err("Unclosed comment or variation in PGN".to_string());
+    }
+
+    let mut moves = Vec::new();
+
+    for token in tokens {
+        if ["1-0", "0-1", "1/2-1/2", "*"].contains(&token.as_str()) {
+            break;
+        }
+
+        //move numbers may be written together with the move, e.g. "1.e4", but
+        //only digits followed by dots are a move number, "0-0" is castling
+        let digits = token.trim_start_matches(|c : char| c.is_ascii_digit());
+        let san = match digits.strip_prefix('.') {
+            Some(rest) if digits.len() < token.len() => rest.trim_start_matches('.'),
+            _ => token.as_str(),
+        };
+        let san = san.trim_end_matches(['!', '?']);
+
+        if san.is_empty() || san.starts_with('$') {
+            continue;
+        }
+
+        moves.push(san.to_string());
+    }
+
+    Ok(moves)
+}
+
+// returns the back rank of Chess960 position n, from the a-file to the h-file
+// bishops, queen and knights are placed on the free squares in order, using the
+// digits of n, and the remaining three squares get rook, king, rook
+fn chess960_back_rank(n : u32) -> [PieceType; 8] {
+    //placement of the two knights among the five free squares
+    let knight_table = [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+
+    let mut rank : [Option<PieceType>; 8] = [None; 8];
+    let mut n = n as usize;
+
+    //light squared bishop on b, d, f or h, dark squared bishop on a, c, e or g
+    rank[n % 4 * 2 + 1] = Some(PieceType::Bishop);
+    n /= 4;
+    rank[n % 4 * 2] = Some(PieceType::Bishop);
+    n /= 4;
+
+    let free = |rank : &[Option<PieceType>; 8]| -> Vec<usize> {
+        (0..8).filter(|&j| rank[j].is_none()).collect()
+    };
+
+    rank[free(&rank)[n % 6]] = Some(PieceType::Queen);
+    n /= 6;
+
+    let (first, second) = knight_table[n];
+    let free_squares = free(&rank);
+    rank[free_squares[first]] = Some(PieceType::Knight);
+    rank[free_squares[second]] = Some(PieceType::Knight);
+
+    for (j, piece_type) in free(&rank).into_iter().zip([PieceType::Rook, PieceType::King, PieceType::Rook]) {
+        rank[j] = Some(piece_type);
+    }
+
+    //every square has been filled, so unwrap is safe
+    rank.map(|piece_type| piece_type.unwrap())
+}
+
+// converts a move from the Polyglot format to UCI, where bits 0-5 are the
+// destination, bits 6-11 the origin and bits 12-14 the promotion piece
+fn polyglot_move_to_uci(raw_move : u16) -> Result<String, String> {
+    //polyglot rows start at the first rank, array indicies at the eighth
+    let square = |bits : u16| -> Result<String, String> {
+        indx_to_alg_notation((7 - ((bits >> 3) & 7) as usize, (bits & 7) as usize))
+    };
+
+    let mut uci = square(raw_move >> 6)?;
+    uci.push_str(&square(raw_move)?);
+
+    match (raw_move >> 12) & 7 {
+        0 => (),
+        1 => uci.push('n'),
+        2 => uci.push('b'),
+        3 => uci.push('r'),
+        4 => uci.push('q'),
+        _c => return Err(format!("Invalid promotion piece {}", _c)),
+    }
+
+    Ok(uci)
+}
+
+// returns the number of king moves needed to go between two squares
+fn chebyshev_distance(from : (usize, usize), to : (usize, usize)) -> u32 {
+    from.0.abs_diff(to.0).max(from.1.abs_diff(to.1)) as u32
+}
+
+// splitmix64 generator, advances state and returns the next random number.
+// used instead of an external crate, and works for any seed including 0.
+// const so it can also generate the Zobrist keys at compile time
+const fn next_random(state : &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+
+    z ^ (z >> 31)
+}
+
+// returns the FEN without the half move and full move counters
+fn fen_without_counters(fen : &str) -> &str {
+    match fen.match_indices(' ').nth(3) {
+        Some((idx, _)) => &fen[..idx],
+        None => fen,
+    }
+}
+
+// returns the promotion piece written in a SAN move, e.g. "e8=N",
+// defaults to queen if no promotion piece is written
+fn san_promotion_piece(san : &str) -> PieceType {
+    let stripped = san.trim().trim_end_matches(['+', '#']);
+
+    match stripped.split_once('=') {
+        Some((_, "N")) => PieceType::Knight,
+        Some((_, "B")) => PieceType::Bishop,
+        Some((_, "R")) => PieceType::Rook,
+        _ => PieceType::Queen,
+    }
+}
+
+// returns which colored pawn is allowed to en passant on the given rank
+// solves conflict where 2 pawns of opposite color can move to en passant square
+fn can_en_passant(i : usize) -> Option<Color> {
+    match i {
+        2 => Some(Color::White),
+        5 => Some(Color::Black),
+        _ => None,
+    }
+}
+
+// random keys for Zobrist hashing, see Game::position_hash()
+mod zobrist {
+    use super::{Color, Piece, PieceType};
+
+    // 12 pieces * 64 squares, side to move, 4 castling rights and 8 en passant files
+    const KEY_COUNT : usize = 781;
+    const SIDE_INDEX : usize = 768;
+    const CASTLING_INDEX : usize = 769;
+    const EN_PASSANT_INDEX : usize = 773;
+
+    // generated at compile time with next_random() from a fixed seed,
+    // so hashes are the same between runs
+    const KEYS : [u64; KEY_COUNT] = generate_keys();
+
+    const fn generate_keys() -> [u64; KEY_COUNT] {
+        let mut keys = [0; KEY_COUNT];
+        let mut state : u64 = 0x2545F4914F6CDD1D;
+        let mut n = 0;
+
+        while n < KEY_COUNT {
+            keys[n] = super::next_random(&mut state);
+            n += 1;
+        }
+
+        keys
+    }
+
+    pub fn piece_key(piece : Piece, square : (usize, usize)) -> u64 {
+        let piece_index = match piece.piece_type {
+            PieceType::Pawn => 0,
+            PieceType::Knight => 1,
+            PieceType::Bishop => 2,
+            PieceType::Rook => 3,
+            PieceType::Queen => 4,
+            PieceType::King => 5,
+        };
+
+        let color_index = match piece.color {
+            Color::White => 0,
+            Color::Black => 6,
+        };
+
+        KEYS[(piece_index + color_index) * 64 + square.0 * 8 + square.1]
+    }
+
+    pub fn side_key() -> u64 {
+        KEYS[SIDE_INDEX]
+    }
+
+    pub fn castling_key(color : Color, kingside : bool) -> u64 {
+        let index = match (color, kingside) {
+            (Color::White, true) => 0,
+            (Color::White, false) => 1,
+            (Color::Black, true) => 2,
+            (Color::Black, false) => 3,
+        };
+
+        KEYS[CASTLING_INDEX + index]
+    }
+
+    pub fn en_passant_key(file : usize) -> u64 {
+        KEYS[EN_PASSANT_INDEX + file]
+    }
+}
+
+// keys of the Polyglot opening book format, which has its own Zobrist keys
+mod polyglot {
+    use super::{Color, Piece, PieceType};
+
+    // 12 pieces * 64 squares, 4 castling rights, 8 en passant files and side to move
+    const KEY_COUNT : usize = 781;
+    const CASTLING_INDEX : usize = 768;
+    const EN_PASSANT_INDEX : usize = 772;
+    const TURN_INDEX : usize = 780;
+
+    // the Random64 array from the Polyglot book format
+    const RANDOM64 : [u64; KEY_COUNT] = [
+        0x9D39247E33776D41, 0x2AF7398005AAA5C7, 0x44DB015024623547, 0x9C15F73E62A76AE2,
+        0x75834465489C0C89, 0x3290AC3A203001BF, 0x0FBBAD1F61042279, 0xE83A908FF2FB60CA,
+        0x0D7E765D58755C10, 0x1A083822CEAFE02D, 0x9605D5F0E25EC3B0, 0xD021FF5CD13A2ED5,
+        0x40BDF15D4A672E32, 0x011355146FD56395, 0x5DB4832046F3D9E5, 0x239F8B2D7FF719CC,
+        0x05D1A1AE85B49AA1, 0x679F848F6E8FC971, 0x7449BBFF801FED0B, 0x7D11CDB1C3B7ADF0,
+        0x82C7709E781EB7CC, 0xF3218F1C9510786C, 0x331478F3AF51BBE6, 0x4BB38DE5E7219443,
+        0xAA649C6EBCFD50FC, 0x8DBD98A352AFD40B, 0x87D2074B81D79217, 0x19F3C751D3E92AE1,
+        0xB4AB30F062B19ABF, 0x7B0500AC42047AC4, 0xC9452CA81A09D85D, 0x24AA6C514DA27500,
+        0x4C9F34427501B447, 0x14A68FD73C910841, 0xA71B9B83461CBD93, 0x03488B95B0F1850F,
+        0x637B2B34FF93C040, 0x09D1BC9A3DD90A94, 0x3575668334A1DD3B, 0x735E2B97A4C45A23,
+        0x18727070F1BD400B, 0x1FCBACD259BF02E7, 0xD310A7C2CE9B6555, 0xBF983FE0FE5D8244,
+        0x9F74D14F7454A824, 0x51EBDC4AB9BA3035, 0x5C82C505DB9AB0FA, 0xFCF7FE8A3430B241,
+        0x3253A729B9BA3DDE, 0x8C74C368081B3075, 0xB9BC6C87167C33E7, 0x7EF48F2B83024E20,
+        0x11D505D4C351BD7F, 0x6568FCA92C76A243, 0x4DE0B0F40F32A7B8, 0x96D693460CC37E5D,
+        0x42E240CB63689F2F, 0x6D2BDCDAE2919661, 0x42880B0236E4D951, 0x5F0F4A5898171BB6,
+        0x39F890F579F92F88, 0x93C5B5F47356388B, 0x63DC359D8D231B78, 0xEC16CA8AEA98AD76,
+        0x5355F900C2A82DC7, 0x07FB9F855A997142, 0x5093417AA8A7ED5E, 0x7BCBC38DA25A7F3C,
+        0x19FC8A768CF4B6D4, 0x637A7780DECFC0D9, 0x8249A47AEE0E41F7, 0x79AD695501E7D1E8,
+        0x14ACBAF4777D5776, 0xF145B6BECCDEA195, 0xDABF2AC8201752FC, 0x24C3C94DF9C8D3F6,
+        0xBB6E2924F03912EA, 0x0CE26C0B95C980D9, 0xA49CD132BFBF7CC4, 0xE99D662AF4243939,
+        0x27E6AD7891165C3F, 0x8535F040B9744FF1, 0x54B3F4FA5F40D873, 0x72B12C32127FED2B,
+        0xEE954D3C7B411F47, 0x9A85AC909A24EAA1, 0x70AC4CD9F04F21F5, 0xF9B89D3E99A075C2,
+        0x87B3E2B2B5C907B1, 0xA366E5B8C54F48B8, 0xAE4A9346CC3F7CF2, 0x1920C04D47267BBD,
+        0x87BF02C6B49E2AE9, 0x092237AC237F3859, 0xFF07F64EF8ED14D0, 0x8DE8DCA9F03CC54E,
+        0x9C1633264DB49C89, 0xB3F22C3D0B0B38ED, 0x390E5FB44D01144B, 0x5BFEA5B4712768E9,
+        0x1E1032911FA78984, 0x9A74ACB964E78CB3, 0x4F80F7A035DAFB04, 0x6304D09A0B3738C4,
+        0x2171E64683023A08, 0x5B9B63EB9CEFF80C, 0x506AACF489889342, 0x1881AFC9A3A701D6,
+        0x6503080440750644, 0xDFD395339CDBF4A7, 0xEF927DBCF00C20F2, 0x7B32F7D1E03680EC,
+        0xB9FD7620E7316243, 0x05A7E8A57DB91B77, 0xB5889C6E15630A75, 0x4A750A09CE9573F7,
+        0xCF464CEC899A2F8A, 0xF538639CE705B824, 0x3C79A0FF5580EF7F, 0xEDE6C87F8477609D,
+        0x799E81F05BC93F31, 0x86536B8CF3428A8C, 0x97D7374C60087B73, 0xA246637CFF328532,
+        0x043FCAE60CC0EBA0, 0x920E449535DD359E, 0x70EB093B15B290CC, 0x73A1921916591CBD,
+        0x56436C9FE1A1AA8D, 0xEFAC4B70633B8F81, 0xBB215798D45DF7AF, 0x45F20042F24F1768,
+        0x930F80F4E8EB7462, 0xFF6712FFCFD75EA1, 0xAE623FD67468AA70, 0xDD2C5BC84BC8D8FC,
+        0x7EED120D54CF2DD9, 0x22FE545401165F1C, 0xC91800E98FB99929, 0x808BD68E6AC10365,
+        0xDEC468145B7605F6, 0x1BEDE3A3AEF53302, 0x43539603D6C55602, 0xAA969B5C691CCB7A,
+        0xA87832D392EFEE56, 0x65942C7B3C7E11AE, 0xDED2D633CAD004F6, 0x21F08570F420E565,
+        0xB415938D7DA94E3C, 0x91B859E59ECB6350, 0x10CFF333E0ED804A, 0x28AED140BE0BB7DD,
+        0xC5CC1D89724FA456, 0x5648F680F11A2741, 0x2D255069F0B7DAB3, 0x9BC5A38EF729ABD4,
+        0xEF2F054308F6A2BC, 0xAF2042F5CC5C2858, 0x480412BAB7F5BE2A, 0xAEF3AF4A563DFE43,
+        0x19AFE59AE451497F, 0x52593803DFF1E840, 0xF4F076E65F2CE6F0, 0x11379625747D5AF3,
+        0xBCE5D2248682C115, 0x9DA4243DE836994F, 0x066F70B33FE09017, 0x4DC4DE189B671A1C,
+        0x51039AB7712457C3, 0xC07A3F80C31FB4B4, 0xB46EE9C5E64A6E7C, 0xB3819A42ABE61C87,
+        0x21A007933A522A20, 0x2DF16F761598AA4F, 0x763C4A1371B368FD, 0xF793C46702E086A0,
+        0xD7288E012AEB8D31, 0xDE336A2A4BC1C44B, 0x0BF692B38D079F23, 0x2C604A7A177326B3,
+        0x4850E73E03EB6064, 0xCFC447F1E53C8E1B, 0xB05CA3F564268D99, 0x9AE182C8BC9474E8,
+        0xA4FC4BD4FC5558CA, 0xE755178D58FC4E76, 0x69B97DB1A4C03DFE, 0xF9B5B7C4ACC67C96,
+        0xFC6A82D64B8655FB, 0x9C684CB6C4D24417, 0x8EC97D2917456ED0, 0x6703DF9D2924E97E,
+        0xC547F57E42A7444E, 0x78E37644E7CAD29E, 0xFE9A44E9362F05FA, 0x08BD35CC38336615,
+        0x9315E5EB3A129ACE, 0x94061B871E04DF75, 0xDF1D9F9D784BA010, 0x3BBA57B68871B59D,
+        0xD2B7ADEEDED1F73F, 0xF7A255D83BC373F8, 0xD7F4F2448C0CEB81, 0xD95BE88CD210FFA7,
+        0x336F52F8FF4728E7, 0xA74049DAC312AC71, 0xA2F61BB6E437FDB5, 0x4F2A5CB07F6A35B3,
+        0x87D380BDA5BF7859, 0x16B9F7E06C453A21, 0x7BA2484C8A0FD54E, 0xF3A678CAD9A2E38C,
+        0x39B0BF7DDE437BA2, 0xFCAF55C1BF8A4424, 0x18FCF680573FA594, 0x4C0563B89F495AC3,
+        0x40E087931A00930D, 0x8CFFA9412EB642C1, 0x68CA39053261169F, 0x7A1EE967D27579E2,
+        0x9D1D60E5076F5B6F, 0x3810E399B6F65BA2, 0x32095B6D4AB5F9B1, 0x35CAB62109DD038A,
+        0xA90B24499FCFAFB1, 0x77A225A07CC2C6BD, 0x513E5E634C70E331, 0x4361C0CA3F692F12,
+        0xD941ACA44B20A45B, 0x528F7C8602C5807B, 0x52AB92BEB9613989, 0x9D1DFA2EFC557F73,
+        0x722FF175F572C348, 0x1D1260A51107FE97, 0x7A249A57EC0C9BA2, 0x04208FE9E8F7F2D6,
+        0x5A110C6058B920A0, 0x0CD9A497658A5698, 0x56FD23C8F9715A4C, 0x284C847B9D887AAE,
+        0x04FEABFBBDB619CB, 0x742E1E651C60BA83, 0x9A9632E65904AD3C, 0x881B82A13B51B9E2,
+        0x506E6744CD974924, 0xB0183DB56FFC6A79, 0x0ED9B915C66ED37E, 0x5E11E86D5873D484,
+        0xF678647E3519AC6E, 0x1B85D488D0F20CC5, 0xDAB9FE6525D89021, 0x0D151D86ADB73615,
+        0xA865A54EDCC0F019, 0x93C42566AEF98FFB, 0x99E7AFEABE000731, 0x48CBFF086DDF285A,
+        0x7F9B6AF1EBF78BAF, 0x58627E1A149BBA21, 0x2CD16E2ABD791E33, 0xD363EFF5F0977996,
+        0x0CE2A38C344A6EED, 0x1A804AADB9CFA741, 0x907F30421D78C5DE, 0x501F65EDB3034D07,
+        0x37624AE5A48FA6E9, 0x957BAF61700CFF4E, 0x3A6C27934E31188A, 0xD49503536ABCA345,
+        0x088E049589C432E0, 0xF943AEE7FEBF21B8, 0x6C3B8E3E336139D3, 0x364F6FFA464EE52E,
+        0xD60F6DCEDC314222, 0x56963B0DCA418FC0, 0x16F50EDF91E513AF, 0xEF1955914B609F93,
+        0x565601C0364E3228, 0xECB53939887E8175, 0xBAC7A9A18531294B, 0xB344C470397BBA52,
+        0x65D34954DAF3CEBD, 0xB4B81B3FA97511E2, 0xB422061193D6F6A7, 0x071582401C38434D,
+        0x7A13F18BBEDC4FF5, 0xBC4097B116C524D2, 0x59B97885E2F2EA28, 0x99170A5DC3115544,
+        0x6F423357E7C6A9F9, 0x325928EE6E6F8794, 0xD0E4366228B03343, 0x565C31F7DE89EA27,
+        0x30F5611484119414, 0xD873DB391292ED4F, 0x7BD94E1D8E17DEBC, 0xC7D9F16864A76E94,
+        0x947AE053EE56E63C, 0xC8C93882F9475F5F, 0x3A9BF55BA91F81CA, 0xD9A11FBB3D9808E4,
+        0x0FD22063EDC29FCA, 0xB3F256D8ACA0B0B9, 0xB03031A8B4516E84, 0x35DD37D5871448AF,
+        0xE9F6082B05542E4E, 0xEBFAFA33D7254B59, 0x9255ABB50D532280, 0xB9AB4CE57F2D34F3,
+        0x693501D628297551, 0xC62C58F97DD949BF, 0xCD454F8F19C5126A, 0xBBE83F4ECC2BDECB,
+        0xDC842B7E2819E230, 0xBA89142E007503B8, 0xA3BC941D0A5061CB, 0xE9F6760E32CD8021,
+        0x09C7E552BC76492F, 0x852F54934DA55CC9, 0x8107FCCF064FCF56, 0x098954D51FFF6580,
+        0x23B70EDB1955C4BF, 0xC330DE426430F69D, 0x4715ED43E8A45C0A, 0xA8D7E4DAB780A08D,
+        0x0572B974F03CE0BB, 0xB57D2E985E1419C7, 0xE8D9ECBE2CF3D73F, 0x2FE4B17170E59750,
+        0x11317BA87905E790, 0x7FBF21EC8A1F45EC, 0x1725CABFCB045B00, 0x964E915CD5E2B207,
+        0x3E2B8BCBF016D66D, 0xBE7444E39328A0AC, 0xF85B2B4FBCDE44B7, 0x49353FEA39BA63B1,
+        0x1DD01AAFCD53486A, 0x1FCA8A92FD719F85, 0xFC7C95D827357AFA, 0x18A6A990C8B35EBD,
+        0xCCCB7005C6B9C28D, 0x3BDBB92C43B17F26, 0xAA70B5B4F89695A2, 0xE94C39A54A98307F,
+        0xB7A0B174CFF6F36E, 0xD4DBA84729AF48AD, 0x2E18BC1AD9704A68, 0x2DE0966DAF2F8B1C,
+        0xB9C11D5B1E43A07E, 0x64972D68DEE33360, 0x94628D38D0C20584, 0xDBC0D2B6AB90A559,
+        0xD2733C4335C6A72F, 0x7E75D99D94A70F4D, 0x6CED1983376FA72B, 0x97FCAACBF030BC24,
+        0x7B77497B32503B12, 0x8547EDDFB81CCB94, 0x79999CDFF70902CB, 0xCFFE1939438E9B24,
+        0x829626E3892D95D7, 0x92FAE24291F2B3F1, 0x63E22C147B9C3403, 0xC678B6D860284A1C,
+        0x5873888850659AE7, 0x0981DCD296A8736D, 0x9F65789A6509A440, 0x9FF38FED72E9052F,
+        0xE479EE5B9930578C, 0xE7F28ECD2D49EECD, 0x56C074A581EA17FE, 0x5544F7D774B14AEF,
+        0x7B3F0195FC6F290F, 0x12153635B2C0CF57, 0x7F5126DBBA5E0CA7, 0x7A76956C3EAFB413,
+        0x3D5774A11D31AB39, 0x8A1B083821F40CB4, 0x7B4A38E32537DF62, 0x950113646D1D6E03,
+        0x4DA8979A0041E8A9, 0x3BC36E078F7515D7, 0x5D0A12F27AD310D1, 0x7F9D1A2E1EBE1327,
+        0xDA3A361B1C5157B1, 0xDCDD7D20903D0C25, 0x36833336D068F707, 0xCE68341F79893389,
+        0xAB9090168DD05F34, 0x43954B3252DC25E5, 0xB438C2B67F98E5E9, 0x10DCD78E3851A492,
+        0xDBC27AB5447822BF, 0x9B3CDB65F82CA382, 0xB67B7896167B4C84, 0xBFCED1B0048EAC50,
+        0xA9119B60369FFEBD, 0x1FFF7AC80904BF45, 0xAC12FB171817EEE7, 0xAF08DA9177DDA93D,
+        0x1B0CAB936E65C744, 0xB559EB1D04E5E932, 0xC37B45B3F8D6F2BA, 0xC3A9DC228CAAC9E9,
+        0xF3B8B6675A6507FF, 0x9FC477DE4ED681DA, 0x67378D8ECCEF96CB, 0x6DD856D94D259236,
+        0xA319CE15B0B4DB31, 0x073973751F12DD5E, 0x8A8E849EB32781A5, 0xE1925C71285279F5,
+        0x74C04BF1790C0EFE, 0x4DDA48153C94938A, 0x9D266D6A1CC0542C, 0x7440FB816508C4FE,
+        0x13328503DF48229F, 0xD6BF7BAEE43CAC40, 0x4838D65F6EF6748F, 0x1E152328F3318DEA,
+        0x8F8419A348F296BF, 0x72C8834A5957B511, 0xD7A023A73260B45C, 0x94EBC8ABCFB56DAE,
+        0x9FC10D0F989993E0, 0xDE68A2355B93CAE6, 0xA44CFE79AE538BBE, 0x9D1D84FCCE371425,
+        0x51D2B1AB2DDFB636, 0x2FD7E4B9E72CD38C, 0x65CA5B96B7552210, 0xDD69A0D8AB3B546D,
+        0x604D51B25FBF70E2, 0x73AA8A564FB7AC9E, 0x1A8C1E992B941148, 0xAAC40A2703D9BEA0,
+        0x764DBEAE7FA4F3A6, 0x1E99B96E70A9BE8B, 0x2C5E9DEB57EF4743, 0x3A938FEE32D29981,
+        0x26E6DB8FFDF5ADFE, 0x469356C504EC9F9D, 0xC8763C5B08D1908C, 0x3F6C6AF859D80055,
+        0x7F7CC39420A3A545, 0x9BFB227EBDF4C5CE, 0x89039D79D6FC5C5C, 0x8FE88B57305E2AB6,
+        0xA09E8C8C35AB96DE, 0xFA7E393983325753, 0xD6B6D0ECC617C699, 0xDFEA21EA9E7557E3,
+        0xB67C1FA481680AF8, 0xCA1E3785A9E724E5, 0x1CFC8BED0D681639, 0xD18D8549D140CAEA,
+        0x4ED0FE7E9DC91335, 0xE4DBF0634473F5D2, 0x1761F93A44D5AEFE, 0x53898E4C3910DA55,
+        0x734DE8181F6EC39A, 0x2680B122BAA28D97, 0x298AF231C85BAFAB, 0x7983EED3740847D5,
+        0x66C1A2A1A60CD889, 0x9E17E49642A3E4C1, 0xEDB454E7BADC0805, 0x50B704CAB602C329,
+        0x4CC317FB9CDDD023, 0x66B4835D9EAFEA22, 0x219B97E26FFC81BD, 0x261E4E4C0A333A9D,
+        0x1FE2CCA76517DB90, 0xD7504DFA8816EDBB, 0xB9571FA04DC089C8, 0x1DDC0325259B27DE,
+        0xCF3F4688801EB9AA, 0xF4F5D05C10CAB243, 0x38B6525C21A42B0E, 0x36F60E2BA4FA6800,
+        0xEB3593803173E0CE, 0x9C4CD6257C5A3603, 0xAF0C317D32ADAA8A, 0x258E5A80C7204C4B,
+        0x8B889D624D44885D, 0xF4D14597E660F855, 0xD4347F66EC8941C3, 0xE699ED85B0DFB40D,
+        0x2472F6207C2D0484, 0xC2A1E7B5B459AEB5, 0xAB4F6451CC1D45EC, 0x63767572AE3D6174,
+        0xA59E0BD101731A28, 0x116D0016CB948F09, 0x2CF9C8CA052F6E9F, 0x0B090A7560A968E3,
+        0xABEEDDB2DDE06FF1, 0x58EFC10B06A2068D, 0xC6E57A78FBD986E0, 0x2EAB8CA63CE802D7,
+        0x14A195640116F336, 0x7C0828DD624EC390, 0xD74BBE77E6116AC7, 0x804456AF10F5FB53,
+        0xEBE9EA2ADF4321C7, 0x03219A39EE587A30, 0x49787FEF17AF9924, 0xA1E9300CD8520548,
+        0x5B45E522E4B1B4EF, 0xB49C3B3995091A36, 0xD4490AD526F14431, 0x12A8F216AF9418C2,
+        0x001F837CC7350524, 0x1877B51E57A764D5, 0xA2853B80F17F58EE, 0x993E1DE72D36D310,
+        0xB3598080CE64A656, 0x252F59CF0D9F04BB, 0xD23C8E176D113600, 0x1BDA0492E7E4586E,
+        0x21E0BD5026C619BF, 0x3B097ADAF088F94E, 0x8D14DEDB30BE846E, 0xF95CFFA23AF5F6F4,
+        0x3871700761B3F743, 0xCA672B91E9E4FA16, 0x64C8E531BFF53B55, 0x241260ED4AD1E87D,
+        0x106C09B972D2E822, 0x7FBA195410E5CA30, 0x7884D9BC6CB569D8, 0x0647DFEDCD894A29,
+        0x63573FF03E224774, 0x4FC8E9560F91B123, 0x1DB956E450275779, 0xB8D91274B9E9D4FB,
+        0xA2EBEE47E2FBFCE1, 0xD9F1F30CCD97FB09, 0xEFED53D75FD64E6B, 0x2E6D02C36017F67F,
+        0xA9AA4D20DB084E9B, 0xB64BE8D8B25396C1, 0x70CB6AF7C2D5BCF0, 0x98F076A4F7A2322E,
+        0xBF84470805E69B5F, 0x94C3251F06F90CF3, 0x3E003E616A6591E9, 0xB925A6CD0421AFF3,
+        0x61BDD1307C66E300, 0xBF8D5108E27E0D48, 0x240AB57A8B888B20, 0xFC87614BAF287E07,
+        0xEF02CDD06FFDB432, 0xA1082C0466DF6C0A, 0x8215E577001332C8, 0xD39BB9C3A48DB6CF,
+        0x2738259634305C14, 0x61CF4F94C97DF93D, 0x1B6BACA2AE4E125B, 0x758F450C88572E0B,
+        0x959F587D507A8359, 0xB063E962E045F54D, 0x60E8ED72C0DFF5D1, 0x7B64978555326F9F,
+        0xFD080D236DA814BA, 0x8C90FD9B083F4558, 0x106F72FE81E2C590, 0x7976033A39F7D952,
+        0xA4EC0132764CA04B, 0x733EA705FAE4FA77, 0xB4D8F77BC3E56167, 0x9E21F4F903B33FD9,
+        0x9D765E419FB69F6D, 0xD30C088BA61EA5EF, 0x5D94337FBFAF7F5B, 0x1A4E4822EB4D7A59,
+        0x6FFE73E81B637FB3, 0xDDF957BC36D8B9CA, 0x64D0E29EEA8838B3, 0x08DD9BDFD96B9F63,
+        0x087E79E5A57D1D13, 0xE328E230E3E2B3FB, 0x1C2559E30F0946BE, 0x720BF5F26F4D2EAA,
+        0xB0774D261CC609DB, 0x443F64EC5A371195, 0x4112CF68649A260E, 0xD813F2FAB7F5C5CA,
+        0x660D3257380841EE, 0x59AC2C7873F910A3, 0xE846963877671A17, 0x93B633ABFA3469F8,
+        0xC0C0F5A60EF4CDCF, 0xCAF21ECD4377B28C, 0x57277707199B8175, 0x506C11B9D90E8B1D,
+        0xD83CC2687A19255F, 0x4A29C6465A314CD1, 0xED2DF21216235097, 0xB5635C95FF7296E2,
+        0x22AF003AB672E811, 0x52E762596BF68235, 0x9AEBA33AC6ECC6B0, 0x944F6DE09134DFB6,
+        0x6C47BEC883A7DE39, 0x6AD047C430A12104, 0xA5B1CFDBA0AB4067, 0x7C45D833AFF07862,
+        0x5092EF950A16DA0B, 0x9338E69C052B8E7B, 0x455A4B4CFE30E3F5, 0x6B02E63195AD0CF8,
+        0x6B17B224BAD6BF27, 0xD1E0CCD25BB9C169, 0xDE0C89A556B9AE70, 0x50065E535A213CF6,
+        0x9C1169FA2777B874, 0x78EDEFD694AF1EED, 0x6DC93D9526A50E68, 0xEE97F453F06791ED,
+        0x32AB0EDB696703D3, 0x3A6853C7E70757A7, 0x31865CED6120F37D, 0x67FEF95D92607890,
+        0x1F2B1D1F15F6DC9C, 0xB69E38A8965C6B65, 0xAA9119FF184CCCF4, 0xF43C732873F24C13,
+        0xFB4A3D794A9A80D2, 0x3550C2321FD6109C, 0x371F77E76BB8417E, 0x6BFA9AAE5EC05779,
+        0xCD04F3FF001A4778, 0xE3273522064480CA, 0x9F91508BFFCFC14A, 0x049A7F41061A9E60,
+        0xFCB6BE43A9F2FE9B, 0x08DE8A1C7797DA9B, 0x8F9887E6078735A1, 0xB5B4071DBFC73A66,
+        0x230E343DFBA08D33, 0x43ED7F5A0FAE657D, 0x3A88A0FBBCB05C63, 0x21874B8B4D2DBC4F,
+        0x1BDEA12E35F6A8C9, 0x53C065C6C8E63528, 0xE34A1D250E7A8D6B, 0xD6B04D3B7651DD7E,
+        0x5E90277E7CB39E2D, 0x2C046F22062DC67D, 0xB10BB459132D0A26, 0x3FA9DDFB67E2F199,
+        0x0E09B88E1914F7AF, 0x10E8B35AF3EEAB37, 0x9EEDECA8E272B933, 0xD4C718BC4AE8AE5F,
+        0x81536D601170FC20, 0x91B534F885818A06, 0xEC8177F83F900978, 0x190E714FADA5156E,
+        0xB592BF39B0364963, 0x89C350C893AE7DC1, 0xAC042E70F8B383F2, 0xB49B52E587A1EE60,
+        0xFB152FE3FF26DA89, 0x3E666E6F69AE2C15, 0x3B544EBE544C19F9, 0xE805A1E290CF2456,
+        0x24B33C9D7ED25117, 0xE74733427B72F0C1, 0x0A804D18B7097475, 0x57E3306D881EDB4F,
+        0x4AE7D6A36EB5DBCB, 0x2D8D5432157064C8, 0xD1E649DE1E7F268B, 0x8A328A1CEDFE552C,
+        0x07A3AEC79624C7DA, 0x84547DDC3E203C94, 0x990A98FD5071D263, 0x1A4FF12616EEFC89,
+        0xF6F7FD1431714200, 0x30C05B1BA332F41C, 0x8D2636B81555A786, 0x46C9FEB55D120902,
+        0xCCEC0A73B49C9921, 0x4E9D2827355FC492, 0x19EBB029435DCB0F, 0x4659D2B743848A2C,
+        0x963EF2C96B33BE31, 0x74F85198B05A2E7D, 0x5A0F544DD2B1FB18, 0x03727073C2E134B1,
+        0xC7F6AA2DE59AEA61, 0x352787BAA0D7C22F, 0x9853EAB63B5E0B35, 0xABBDCDD7ED5C0860,
+        0xCF05DAF5AC8D77B0, 0x49CAD48CEBF4A71E, 0x7A4C10EC2158C4A6, 0xD9E92AA246BF719E,
+        0x13AE978D09FE5557, 0x730499AF921549FF, 0x4E4B705B92903BA4, 0xFF577222C14F0A3A,
+        0x55B6344CF97AAFAE, 0xB862225B055B6960, 0xCAC09AFBDDD2CDB4, 0xDAF8E9829FE96B5F,
+        0xB5FDFC5D3132C498, 0x310CB380DB6F7503, 0xE87FBB46217A360E, 0x2102AE466EBB1148,
+        0xF8549E1A3AA5E00D, 0x07A69AFDCC42261A, 0xC4C118BFE78FEAAE, 0xF9F4892ED96BD438,
+        0x1AF3DBE25D8F45DA, 0xF5B4B0B0D2DEEEB4, 0x962ACEEFA82E1C84, 0x046E3ECAAF453CE9,
+        0xF05D129681949A4C, 0x964781CE734B3C84, 0x9C2ED44081CE5FBD, 0x522E23F3925E319E,
+        0x177E00F9FC32F791, 0x2BC60A63A6F3B3F2, 0x222BBFAE61725606, 0x486289DDCC3D6780,
+        0x7DC7785B8EFDFC80, 0x8AF38731C02BA980, 0x1FAB64EA29A2DDF7, 0xE4D9429322CD065A,
+        0x9DA058C67844F20C, 0x24C0E332B70019B0, 0x233003B5A6CFE6AD, 0xD586BD01C5C217F6,
+        0x5E5637885F29BC2B, 0x7EBA726D8C94094B, 0x0A56A5F0BFE39272, 0xD79476A84EE20D06,
+        0x9E4C1269BAA4BF37, 0x17EFEE45B0DEE640, 0x1D95B0A5FCF90BC6, 0x93CBE0B699C2585D,
+        0x65FA4F227A2B6D79, 0xD5F9E858292504D5, 0xC2B5A03F71471A6F, 0x59300222B4561E00,
+        0xCE2F8642CA0712DC, 0x7CA9723FBB2E8988, 0x2785338347F2BA08, 0xC61BB3A141E50E8C,
+        0x150F361DAB9DEC26, 0x9F6A419D382595F4, 0x64A53DC924FE7AC9, 0x142DE49FFF7A7C3D,
+        0x0C335248857FA9E7, 0x0A9C32D5EAE45305, 0xE6C42178C4BBB92E, 0x71F1CE2490D20B07,
+        0xF1BCC3D275AFE51A, 0xE728E8C83C334074, 0x96FBF83A12884624, 0x81A1549FD6573DA5,
+        0x5FA7867CAF35E149, 0x56986E2EF3ED091B, 0x917F1DD5F8886C61, 0xD20D8C88C8FFE65F,
+        0x31D71DCE64B2C310, 0xF165B587DF898190, 0xA57E6339DD2CF3A0, 0x1EF6E6DBB1961EC9,
+        0x70CC73D90BC26E24, 0xE21A6B35DF0C3AD7, 0x003A93D8B2806962, 0x1C99DED33CB890A1,
+        0xCF3145DE0ADD4289, 0xD0E4427A5514FB72, 0x77C621CC9FB3A483, 0x67A34DAC4356550B,
+        0xF8D626AAAF278509,
+    ];
+
+    pub fn piece_key(piece : Piece, square : (usize, usize)) -> u64 {
+        //polyglot orders pieces black pawn, white pawn, black knight, ...
+        let piece_index = match piece.piece_type {
+            PieceType::Pawn => 0,
+            PieceType::Knight => 1,
+            PieceType::Bishop => 2,
+            PieceType::Rook => 3,
+            PieceType::Queen => 4,
+            PieceType::King => 5,
+        };
+
+        let color_index = match piece.color {
+            Color::White => 1,
+            Color::Black => 0,
+        };
+
+        //polyglot rows start at the first rank, array indicies at the eighth
+        RANDOM64[(piece_index * 2 + color_index) * 64 + (7 - square.0) * 8 + square.1]
+    }
+
+    pub fn castling_key(color : Color, kingside : bool) -> u64 {
+        let index = match (color, kingside) {
+            (Color::White, true) => 0,
+            (Color::White, false) => 1,
+            (Color::Black, true) => 2,
+            (Color::Black, false) => 3,
+        };
+
+        RANDOM64[CASTLING_INDEX + index]
+    }
+
+    pub fn en_passant_key(file : usize) -> u64 {
+        RANDOM64[EN_PASSANT_INDEX + file]
+    }
+
+    pub fn turn_key() -> u64 {
+        RANDOM64[TURN_INDEX]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn display_test() {
+        let mut game = Game::new_starting_pos();
+        game.make_move("e2", "e4", true).unwrap();
+
+        let expected = [
+            "8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜",
+            "7 ♟ ♟ ♟ ♟ ♟ ♟ ♟ ♟",
+            "6   ·   ·   ·   ·",
+            "5 ·   ·   ·   ·  ",
+            "4   ·   · ♙ ·   ·",
+            "3 ·   ·   ·   ·  ",
+            "2 ♙ ♙ ♙ ♙   ♙ ♙ ♙",
+            "1 ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖",
+            "  a b c d e f g h",
+            "Black to move, castling: KQkq, en passant: e3, half moves: 0, full moves: 1",
+        ].join("\n");
+
+        assert_eq!(game.to_string(), expected);
+        assert_eq!(game.display_from_perspective(Color::White), expected);
+
+        let flipped = game.display_from_perspective(Color::Black);
+        let lines : Vec<&str> = flipped.lines().collect();
+
+        assert_eq!(lines[0], "1 ♖ ♘ ♗ ♔ ♕ ♗ ♘ ♖");
+        assert_eq!(lines[4], "5   ·   ·   ·   ·");
+        assert_eq!(lines[7], "8 ♜ ♞ ♝ ♚ ♛ ♝ ♞ ♜");
+        assert_eq!(lines[8], "  h g f e d c b a");
+        assert_eq!(lines[9], expected.lines().last().unwrap());
+    }
+
+    #[test]
+    fn square_test() {
+        let square = Square::from_alg("e4").unwrap();
+
+        assert_eq!(square, Square::new(4, 4).unwrap());
+        assert_eq!((square.rank(), square.file()), (4, 4));
+        assert_eq!(square.to_alg(), "e4");
+        assert_eq!(square.to_string(), "e4");
+        assert_eq!("e4".parse::<Square>(), Ok(square));
+        assert_eq!(<(usize, usize)>::from(square), (4, 4));
+        assert_eq!(Square::try_from((0, 7)), Ok(Square::from_alg("h8").unwrap()));
+
+        assert!(Square::new(8, 0).is_err());
+        assert!(Square::new(1 << 32, 0).is_err());
+        assert!(Square::new(0, u32::MAX as usize + 5).is_err());
+        assert_eq!(Game::new_starting_pos().piece_at_index(1 << 32, 0), None);
+        assert!(Square::try_from((0, 8)).is_err());
+        assert!(Square::from_alg("i1").is_err());
+        assert!("".parse::<Square>().is_err());
+
+        let mut game = Game::new_starting_pos();
+        let b1 = Square::from_alg("b1").unwrap();
+        let c3 = Square::from_alg("c3").unwrap();
+
+        assert_eq!(game.piece_at_square(b1), Some(Piece::new(PieceType::Knight, Color::White)));
+        assert_eq!(game.get_legal_moves_square(b1), vec![Square::from_alg("c3").unwrap(), Square::from_alg("a3").unwrap()]);
+        assert_eq!(game.make_move_square(b1, b1, true), Ok(false));
+        assert_eq!(game.make_move_square(b1, c3, true), Ok(true));
+        assert_eq!(game.piece_at_square(b1), None);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn piece_getter_test() {
+        let game = Game::new_starting_pos();
+     
+        let piece = game.piece_at_array_index((0,0));
+        let top_left_piece = Piece::new(PieceType::Rook, Color::Black);
+    
+        assert_eq!(piece, Ok(Some(top_left_piece)));
+    }
+
+    #[test]
+
+    fn possible_moves_test() {
+        let mut board = Game::new_starting_pos();
+
+        let x : HashMap<(usize, usize), Vec<(usize, usize)>> = board.get_all_legal_moves(Color::White);
+
+        let mut expected_map = HashMap::new();
+        expected_map.insert((6, 6), vec![(5, 6), (4, 6)]);
+        expected_map.insert((7, 2), vec![]);
+        expected_map.insert((6, 4), vec![(5, 4), (4, 4)]);
+        expected_map.insert((6, 2), vec![(5, 2), (4, 2)]);
+        expected_map.insert((7, 0), vec![]);
+        expected_map.insert((7, 4), vec![]);
+        expected_map.insert((7, 5), vec![]);
+        expected_map.insert((7, 3), vec![]);
+        expected_map.insert((6, 7), vec![(5, 7), (4, 7)]);
+        expected_map.insert((6, 1), vec![(5, 1), (4, 1)]);
+        expected_map.insert((7, 1), vec![(5, 2), (5, 0)]);
+        expected_map.insert((6, 3), vec![(5, 3), (4, 3)]);
+        expected_map.insert((6, 0), vec![(5, 0), (4, 0)]);
+        expected_map.insert((6, 5), vec![(5, 5), (4, 5)]);
+        expected_map.insert((7, 6), vec![(5, 7), (5, 5)]);
+        expected_map.insert((7, 7), vec![]);
+
+        assert_eq!(x, expected_map);
+    }
+
+    #[test]
+    fn legal_moves_square_test() {
+        let mut game = Game::new_starting_pos();
+
+        let expected_val : Vec<(usize, usize)> = Vec::from([(5, 2), (5, 0)]);
+
+        //print legal moves for knight on b1
+        assert_eq!(expected_val, game.get_legal_moves_alg_notation("b1").ok().unwrap());
+    }
+
+    #[test]
+
+    //tests make_move function with different inputs
+    #[allow(clippy::bool_assert_comparison)]
+    fn move_test() {   
+        let mut board = Game::new_starting_pos();
+
+        let valid_move = board.make_move("e2", "e4", false);
+        let invalid_move = board.make_move("f2", "f5", false);
+        let invalid_move2 = board.make_move("f4", "f5", false);
+        let invalid_input = board.make_move("aksmldkams", "poköakenjf", false);
+        let empty_input = board.make_move("", "", false);
+
+        assert_eq!(valid_move, Ok(true));
+        assert_eq!(invalid_move, Ok(false));
+        assert_eq!(invalid_move2, Ok(false));
+        assert_eq!(invalid_input.is_err(), true);
+        assert_eq!(empty_input.is_err(), true);
+    }
+
+    #[test]
+    fn castling_test() {
+        let mut board = Game::from_fen("r1bqkbnr/pppppppp/8/8/8/6n1/PPPPPPP1/RNBQK2R b KQkq - 0 1").unwrap();
+
+        board.make_move("g3", "e4", true).unwrap();
+
+        println!("{:?}", board);
+        println!("{:?}", board.get_legal_moves_alg_notation("e1").unwrap());
+    }
+
+    #[test]
+    //rook on b8 attacks b1, the king never passes b1 so castling is still legal
+    fn queenside_castling_b_file_attacked_test() {
+        let mut board = Game::from_fen("1r2k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+
+        assert!(board.get_legal_moves_alg_notation("e1").unwrap().contains(&(7, 2)));
+    }
+
+    #[test]
+    //rook on d8 attacks d1, the king may not castle through an attacked square
+    fn queenside_castling_d_file_attacked_test() {
+        let mut board = Game::from_fen("3rk3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+
+        assert!(!board.get_legal_moves_alg_notation("e1").unwrap().contains(&(7, 2)));
+    }
+
+    #[test]
+    fn partial_castling_rights_test() {
+        let mut board = Game::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1").unwrap();
+
+        assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w Kq - 0 1");
+
+        let white_king_moves = board.get_legal_moves_alg_notation("e1").unwrap();
+        assert!(white_king_moves.contains(&(7, 6)));
+        assert!(!white_king_moves.contains(&(7, 2)));
+
+        board.flip_turn();
+
+        let black_king_moves = board.get_legal_moves_alg_notation("e8").unwrap();
+        assert!(black_king_moves.contains(&(0, 2)));
+        assert!(!black_king_moves.contains(&(0, 6)));
+    }
+
+    #[test]
+    //moving the king loses castling rights, even if it does not castle
+    fn king_move_loses_castling_test() {
+        let mut board = Game::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        board.make_move("e1", "f1", true).unwrap();
+
+        assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R4K1R b kq - 1 1");
+
+        board.make_move("e8", "d8", true).unwrap();
+        board.make_move("f1", "e1", true).unwrap();
+
+        assert_eq!(board.to_fen(), "r2k3r/8/8/8/8/8/8/R3K2R b - - 3 2");
+
+        //back on its starting square, but the king can no longer castle
+        let king_moves = board.get_legal_moves_alg_notation("e1").unwrap();
+        assert!(!king_moves.contains(&(7, 6)));
+        assert!(!king_moves.contains(&(7, 2)));
+    }
+
+    #[test]
+    fn null_move_test() {
+        let mut board = Game::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 3 30").unwrap();
+        let fen = board.to_fen();
+
+        assert!(board.is_en_passant_available());
+
+        board.make_null_move();
+
+        assert!(!board.is_en_passant_available());
+        assert_eq!(board.to_fen(), "4k3/8/8/8/3pP3/8/8/4K3 w - - 4 31");
+
+        //moves made after the null move are undone as usual
+        board.make_move("e1", "d2", true).unwrap();
+        board.undo_last_move();
+        board.unmake_null_move();
+
+        assert!(board.is_en_passant_available());
+        assert_eq!(board.to_fen(), fen);
+        assert!(board.get_legal_moves_alg_notation("d4").unwrap().contains(&(5, 4)));
+
+        //nothing to unmake
+        board.unmake_null_move();
+
+        assert_eq!(board.to_fen(), fen);
+    }
+
+    #[test]
+    fn piece_at_test() {
+        let board = Game::new_starting_pos();
+
+        assert_eq!(board.piece_at("e2"), Some(Piece::new(PieceType::Pawn, Color::White)));
+        assert_eq!(board.piece_at("e4"), None);
+        assert_eq!(board.piece_at("z9"), None);
+        assert_eq!(board.piece_at_index(0, 4), Some(Piece::new(PieceType::King, Color::Black)));
+        assert_eq!(board.piece_at_index(4, 4), None);
+        assert_eq!(board.piece_at_index(8, 0), None);
+    }
+
+    #[test]
+    fn board_rows_test() {
+        let board = Game::new_starting_pos();
+        let white_pawn = Some(Piece::new(PieceType::Pawn, Color::White));
+
+        assert_eq!(board.board_rows()[6], [white_pawn; 8]);
+        assert_eq!(board.board_row(2), Ok([white_pawn; 8]));
+        assert_eq!(board.board_row(4), Ok([None; 8]));
+        assert_eq!(board.board_row(8).unwrap()[4], Some(Piece::new(PieceType::King, Color::Black)));
+        assert!(board.board_row(0).is_err());
+        assert!(board.board_row(9).is_err());
+
+        let e_file = board.board_column('e').unwrap();
+
+        assert_eq!(e_file[0], Some(Piece::new(PieceType::King, Color::Black)));
+        assert_eq!(e_file[6], white_pawn);
+        assert_eq!(e_file[7], Some(Piece::new(PieceType::King, Color::White)));
+        assert!(board.board_column('i').is_err());
+    }
+
+    #[test]
+    fn move_struct_test() {
+        let mut board = Game::from_fen("r3k3/1P6/8/3pP3/8/8/8/R3K2R w KQq d6 0 1").unwrap();
+
+        let king_moves = board.get_legal_moves_as_moves(Square::from_alg("e1").unwrap());
+
+        assert!(king_moves.contains(&Move::new((7, 4), (7, 6), MoveFlags::KingsideCastle)));
+        assert!(king_moves.contains(&Move::new((7, 4), (7, 2), MoveFlags::QueensideCastle)));
+        assert!(king_moves.contains(&Move::new((7, 4), (7, 5), MoveFlags::Quiet)));
+
+        let black_pawn = Some(Piece::new(PieceType::Pawn, Color::Black));
+        let black_rook = Some(Piece::new(PieceType::Rook, Color::Black));
+
+        let pawn_moves = board.get_legal_moves_as_moves(Square::from_alg("e5").unwrap());
+
+        assert_eq!(pawn_moves.len(), 2);
+        assert!(pawn_moves.contains(&Move::new((3, 4), (2, 4), MoveFlags::Quiet)));
+        let en_passant = Move::new((3, 4), (2, 3), MoveFlags::EnPassant);
+        assert_eq!(pawn_moves.iter().find(|&&mve| mve == en_passant).unwrap().captured_piece, black_pawn);
+
+        let promotions = board.get_legal_moves_as_moves(Square::from_alg("b7").unwrap());
+
+        assert_eq!(promotions.len(), 8);
+        assert!(promotions.contains(&Move::new((1, 1), (0, 1), MoveFlags::Promotion(PieceType::Queen))));
+        let knight_promotion = Move::new((1, 1), (0, 0), MoveFlags::Promotion(PieceType::Knight));
+        assert_eq!(promotions.iter().find(|&&mve| mve == knight_promotion).unwrap().captured_piece, black_rook);
+
+        let all_moves = board.get_all_legal_moves_as_moves(Color::White);
+
+        //captured_piece is not compared
+        assert!(all_moves.contains(&Move::new((7, 0), (0, 0), MoveFlags::Capture)));
+        assert!(!all_moves.contains(&Move::new((7, 0), (0, 0), MoveFlags::Quiet)));
+        assert_eq!(all_moves.len() as u64, board.perft_bulk(1));
+
+        assert_eq!(board.play_move(Move::new((1, 1), (0, 0), MoveFlags::Promotion(PieceType::Rook))), Ok(true));
+        assert_eq!(board.piece_at("a8"), Some(Piece::new(PieceType::Rook, Color::White)));
+        assert_eq!(board.get_last_move().unwrap().captured_piece, black_rook);
+        assert_eq!(board.get_last_move(), Some(Move::new((1, 1), (0, 0), MoveFlags::Promotion(PieceType::Rook))));
+
+        board.undo_last_move();
+        board.play_move(Move::from_alg("e1", "g1").unwrap()).unwrap();
+
+        assert_eq!(board.get_last_move(), Some(Move::new((7, 4), (7, 6), MoveFlags::KingsideCastle)));
+        assert_eq!(board.play_move(Move::from_alg("e8", "e1").unwrap()), Ok(false));
+        assert!(Move::from_alg("e9", "e1").is_err());
+        assert_eq!(Game::new_starting_pos().get_last_move(), None);
+    }
+
+    #[test]
+    fn make_move_unchecked_test() {
+        let mut board = Game::new_starting_pos();
+
+        board.make_move_unchecked((6, 4), (4, 4), None);
+
+        assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+        let mut board = Game::from_fen("8/1P6/8/8/8/8/8/k6K w - - 0 1").unwrap();
+
+        board.make_move_unchecked((1, 1), (0, 1), Some(PieceType::Rook));
+
+        assert_eq!(board.piece_at_alg_notation("b8"), Ok(Some(Piece::new(PieceType::Rook, Color::White))));
+        assert_eq!(board.get_state(), GameState::InProgress);
+    }
+
+    #[test]
+    fn capture_and_quiet_moves_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert!(board.captures_only_moves(Color::White).is_empty());
+        assert_eq!(board.quiet_moves_only(Color::White).len(), 20);
+
+        //pawn on e5 can capture en passant on d6, pawn on b7 can capture or promote
+        let mut board = Game::from_fen("rn2k3/1P6/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        let captures = board.captures_only_moves(Color::White);
+        let quiet = board.quiet_moves_only(Color::White);
+
+        assert_eq!(captures, vec![(1, 1, 0, 0), (3, 4, 2, 3)]);
+        assert!(quiet.contains(&(3, 4, 2, 4)));
+        assert!(!quiet.contains(&(1, 1, 0, 1)));
+        assert!(!quiet.contains(&(3, 4, 2, 3)));
+    }
+
+    #[test]
+    fn move_gives_check_test() {
+        let board = Game::from_fen("4k3/8/8/4N3/8/8/8/K3R3 w - - 0 1").unwrap();
+
+        //knight checks and uncovers the rook
+        assert!(board.move_gives_check((3, 4), (2, 3)));
+        assert!(board.move_gives_double_check((3, 4), (2, 3)));
+
+        //only the rook gives check
+        assert!(board.move_gives_check((3, 4), (4, 2)));
+        assert!(!board.move_gives_double_check((3, 4), (4, 2)));
+
+        assert!(!board.move_gives_check((7, 0), (6, 1)));
+        assert!(!board.move_gives_check((4, 4), (3, 4)));
+        assert_eq!(board.get_checkers(Color::Black), Vec::new());
+    }
+
+    #[test]
+    fn see_test() {
+        //pawn takes knight defended by pawn
+        let board = Game::from_fen("4k3/8/2p5/3n4/4P3/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.see((3, 3), (4, 4)), 220);
+        assert_eq!(board.attackers_of((3, 3), Color::Black), vec![(2, 2)]);
+
+        //rook takes pawn defended by pawn
+        let board = Game::from_fen("4k3/8/2p5/3p4/8/8/8/3RK3 w - - 0 1").unwrap();
+
+        assert_eq!(board.see((3, 3), (7, 3)), -400);
+
+        //second rook behind the first one wins the exchange
+        let board = Game::from_fen("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1").unwrap();
+
+        assert_eq!(board.see((3, 3), (6, 3)), 100);
+        assert_eq!(board.see((3, 3), (4, 4)), 0);
+    }
+
+    #[test]
+    fn capture_screening_test() {
+        //the undefended knight on d4 can be taken by the rook on d1,
+        //which is worth more than the knight, or by the pawn on e3
+        let board = Game::from_fen("4k3/8/2p5/3p4/3n4/4P3/8/3RK3 w - - 0 1").unwrap();
+
+        assert!(!board.is_capture_profitable((7, 3), (4, 3)));
+        assert!(board.is_capture_profitable((5, 4), (4, 3)));
+        assert!(board.is_hanging_capture((5, 4), (4, 3)));
+        assert!(board.is_hanging_capture((7, 3), (4, 3)));
+        //not a capture
+        assert!(!board.is_hanging_capture((2, 2), (5, 3)));
+
+        //pawn on d5 is defended by the pawn on c6
+        let board = Game::from_fen("4k3/8/2p5/3p4/8/8/8/3RK3 w - - 0 1").unwrap();
+
+        assert!(!board.is_hanging_capture((7, 3), (3, 3)));
+        assert!(!board.is_capture_profitable((7, 3), (6, 3)));
+        assert!(!board.is_capture_profitable((7, 3), (7, 4)));
+    }
+
+    #[test]
+    fn analyze_capture_sequence_test() {
+        let board = Game::from_fen("4k3/8/2p5/3p4/8/8/8/3RK3 w - - 0 1").unwrap();
+
+        assert_eq!(board.analyze_capture_sequence((3, 3)), vec![
+            (Piece::new(PieceType::Rook, Color::White), 100),
+            (Piece::new(PieceType::Pawn, Color::Black), -400),
+        ]);
+
+        //the rooks on d2 and d1 both capture, the rook on d8 recaptures in between
+        let board = Game::from_fen("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1").unwrap();
+
+        assert_eq!(board.analyze_capture_sequence((3, 3)), vec![
+            (Piece::new(PieceType::Rook, Color::White), 100),
+            (Piece::new(PieceType::Rook, Color::Black), -400),
+            (Piece::new(PieceType::Rook, Color::White), 100),
+        ]);
+
+        assert!(board.analyze_capture_sequence((4, 4)).is_empty());
+        assert!(board.analyze_capture_sequence((8, 0)).is_empty());
+    }
+
+    #[test]
+    fn king_safety_test() {
+        let board = Game::from_fen("6k1/5pp1/7p/8/8/7P/5PP1/6K1 w - - 0 1").unwrap();
+
+        assert_eq!(board.pawn_shield_count(Color::White), 3);
+        assert_eq!(board.pawn_shield_count(Color::Black), 3);
+        assert_eq!(Game::new_starting_pos().pawn_shield_count(Color::White), 3);
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8/8 w - - 0 1").unwrap().pawn_shield_count(Color::White), 0);
+
+        let board = Game::from_fen("4k3/8/8/8/3K4/8/8/8 w - - 0 1").unwrap();
+
+        assert_eq!(board.king_attack_zone(Color::White).len(), 16);
+        assert_eq!(board.king_attack_zone(Color::Black).len(), 10);
+        assert!(board.king_attack_zone(Color::White).contains(&(2, 1)));
+        assert!(!board.king_attack_zone(Color::White).contains(&(2, 2)));
+    }
+
+    #[test]
+    fn outpost_test() {
+        //knight on d5 supported by the e4 pawn, the b7 pawn can attack c6 and a5 but not d5
+        let board = Game::from_fen("4k3/1p6/8/3N4/4PB2/8/8/4K3 w - - 0 1").unwrap();
+
+        let outposts = board.outpost_squares(Color::White);
+
+        assert!(outposts.contains(&(3, 3)));
+        assert!(!outposts.contains(&(2, 2)));
+        assert!(!outposts.contains(&(3, 0)));
+        assert!(!outposts.contains(&(4, 5)));
+        assert_eq!(board.knight_outposts(Color::White), vec![(3, 3)]);
+        //the bishop on f4 is not in black's half of the board
+        assert!(board.bishop_outposts(Color::White).is_empty());
+
+        //unsupported knight is not on an outpost
+        let board = Game::from_fen("4k3/1p6/8/3N4/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(board.knight_outposts(Color::White).is_empty());
+    }
+
+    #[test]
+    fn center_control_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.center_control_score(Color::White), 0);
+        assert_eq!(board.attacks_center(Color::White).get(&(5, 2)), Some(&3));
+        assert_eq!(board.attacks_center(Color::White).len(), 16);
+
+        board.make_move("e2", "e4", true).unwrap();
+
+        assert_eq!(board.attacks_center(Color::White).get(&(3, 3)), Some(&1));
+        assert!(board.center_control_score(Color::White) > 0);
+        assert!(board.center_control_score(Color::Black) < 0);
+    }
+
+    #[test]
+    fn development_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.development_score(Color::White), 0);
+        assert_eq!(board.undeveloped_pieces(Color::Black), vec![(0, 1), (0, 2), (0, 5), (0, 6)]);
+
+        board.make_move("g1", "f3", true).unwrap();
+
+        assert_eq!(board.development_score(Color::White), 1);
+        assert_eq!(board.development_score(Color::Black), -1);
+        assert_eq!(board.undeveloped_pieces(Color::White), vec![(7, 1), (7, 2), (7, 5)]);
+    }
+
+    #[test]
+    fn has_castled_test() {
+        let mut board = Game::from_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
+
+        assert!(!board.has_castled(Color::White));
+
+        board.make_move("e1", "g1", true).unwrap();
+
+        assert!(board.has_castled(Color::White));
+        assert!(!board.has_castled(Color::Black));
+        assert!(board.clone().has_castled(Color::White));
+
+        board.undo_last_move();
+
+        assert!(!board.has_castled(Color::White));
+    }
+
+    #[test]
+    fn draw_reason_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert!(!board.is_drawn_position());
+        assert_eq!(board.draw_reason(), None);
+
+        let mut board = Game::from_fen("k7/2Q5/8/8/8/8/8/K7 b - - 0 1").unwrap();
+
+        assert!(board.is_drawn_position());
+        assert_eq!(board.draw_reason(), Some(DrawState::Stalemate));
+
+        let mut board = Game::from_fen("k7/8/8/8/8/8/8/KN6 w - - 0 1").unwrap();
+
+        assert_eq!(board.draw_reason(), Some(DrawState::InsufficientMaterial));
+
+        let mut board = Game::from_fen("k7/8/8/8/8/8/8/KR6 w - - 100 80").unwrap();
+
+        assert_eq!(board.draw_reason(), Some(DrawState::FiftyMoveRule));
+    }
+
+    #[test]
+    fn terminal_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert!(!board.is_terminal());
+        assert_eq!(board.terminal_value(), None);
+
+        //fool's mate
+        let mut board = Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+
+        assert!(board.is_terminal());
+        assert_eq!(board.terminal_value(), Some(-1.0));
+
+        let mut board = Game::from_fen("k7/2Q5/8/8/8/8/8/K7 b - - 0 1").unwrap();
+
+        assert_eq!(board.terminal_value(), Some(0.0));
+
+        let mut board = Game::from_fen("8/1P6/8/8/8/8/8/k6K w - - 0 1").unwrap();
+
+        board.make_move("b7", "b8", false).unwrap();
+
+        assert!(!board.is_terminal());
+    }
+
+    #[test]
+    fn recaptures_test() {
+        let mut board = Game::from_fen("4k3/8/1n6/3p4/4P3/2N5/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.available_captures(Color::White), vec![(4, 4, 3, 3), (5, 2, 3, 3)]);
+
+        board.make_move("e4", "d5", true).unwrap();
+
+        assert_eq!(board.recaptures_at((3, 3)), vec![(2, 1, 3, 3)]);
+        assert!(board.recaptures_at((4, 4)).is_empty());
+    }
+
+    #[test]
+    fn fen_history_test() {
+        let mut board = Game::new_starting_pos();
+
+        board.make_move("e2", "e4", true).unwrap();
+        board.make_move("c7", "c5", true).unwrap();
+
+        assert_eq!(board.fen_at_move(0), Some(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")));
+        assert_eq!(board.fen_at_move(2), Some(board.to_fen()));
+        assert_eq!(board.fen_at_move(3), None);
+        assert_eq!(board.fen_history().len(), 3);
+        assert_eq!(board.fen_history()[1], board.fen_at_move(1).unwrap());
+
+        board.undo_last_move();
+
+        assert_eq!(board.fen_history().len(), 2);
+    }
+
+    #[test]
+    fn discovery_test() {
+        //bishop on e4 blocks the rook on e1 from the king on e8 and the queen on e3
+        //blocks the bishop on c1 from the rook on h6
+        let board = Game::from_fen("4k3/8/7r/8/4B3/4Q3/8/K1B1R3 w - - 0 1").unwrap();
+
+        assert!(board.move_is_discovery((4, 4), (3, 3)));
+        assert!(board.move_is_discovered_check((4, 4), (3, 3)));
+
+        //queen leaves the diagonal, uncovering an attack on the rook, but not the king
+        assert!(board.move_is_discovery((5, 4), (5, 0)));
+        assert!(!board.move_is_discovered_check((5, 4), (5, 0)));
+
+        //queen stays on the diagonal
+        assert!(!board.move_is_discovery((5, 4), (4, 5)));
+        assert!(!board.move_is_discovery((7, 0), (6, 0)));
+        assert!(!board.move_is_discovery((3, 3), (2, 3)));
+    }
+
+    #[test]
+    fn fork_test() {
+        let board = Game::from_fen("r3k3/8/8/1N6/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.move_is_fork((3, 1), (1, 2)), vec![(0, 0), (0, 4)]);
+        assert!(board.move_is_fork((3, 1), (2, 3)).is_empty());
+        assert!(board.move_is_fork((4, 4), (3, 4)).is_empty());
+    }
+
+    #[test]
+    fn pin_test() {
+        //pawn on d7 stands between the knight and the king, so there is no pin
+        let board = Game::from_fen("4k3/3p4/2n5/8/8/8/8/4KB2 w - - 0 1").unwrap();
+
+        assert_eq!(board.move_is_pin((7, 5), (3, 1)), Vec::new());
+
+        //bishop to b5 pins the knight on c6
+        let board = Game::from_fen("4k3/8/2n5/8/8/8/8/4KB2 w - - 0 1").unwrap();
+
+        assert_eq!(board.move_is_pin((7, 5), (3, 1)), vec![(2, 2)]);
+        assert!(!board.is_pinned((2, 2)));
+        assert!(board.move_is_pin((7, 5), (6, 4)).is_empty());
+
+        //knight on e2 is already pinned
+        let board = Game::from_fen("4k3/4r3/8/8/8/8/4N3/4K3 b - - 0 1").unwrap();
+
+        assert!(board.is_pinned((6, 4)));
+        assert!(!board.is_pinned((1, 4)));
+        assert!(board.move_is_pin((1, 4), (2, 4)).is_empty());
+    }
+
+    #[test]
+    fn skewer_test() {
+        //bishop to d4 skewers the queen on f6 and the rook on h8
+        let board = Game::from_fen("k6r/8/5q2/8/8/8/8/K5B1 w - - 0 1").unwrap();
+
+        assert!(board.move_is_skewer((7, 6), (4, 3)));
+        assert!(!board.move_is_skewer((7, 6), (6, 5)));
+
+        //a more valuable piece behind is not a skewer
+        let board = Game::from_fen("k6q/8/5r2/8/8/8/8/K5B1 w - - 0 1").unwrap();
+
+        assert!(!board.move_is_skewer((7, 6), (4, 3)));
+
+        //moving the knight uncovers the rook on a1, skewering the queen and the rook
+        let board = Game::from_fen("r3k3/8/q7/8/8/N7/8/R3K3 w - - 0 1").unwrap();
+
+        assert!(board.move_is_skewer((5, 0), (6, 2)));
+        assert!(!board.move_is_skewer((7, 4), (6, 4)));
+    }
+
+    #[test]
+    //capturing en passant removes both pawns from the fifth rank,
+    //which would expose the king on h5 to the rook on a5
+    fn en_passant_pin_bug() {
+        let mut board = Game::from_fen("k7/8/8/r2Pp2K/8/8/8/8 w - e6 0 1").unwrap();
+
+        let legal_moves = board.get_legal_moves_alg_notation("d5").unwrap();
+
+        assert!(!legal_moves.contains(&(2, 4)));
+        assert!(legal_moves.contains(&(2, 3)));
+        assert_eq!(board.make_move("d5", "e6", true), Ok(false));
+    }
+
+    #[test]
+    fn back_rank_test() {
+        let board = Game::from_fen("3r2k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+
+        assert!(board.back_rank_mate_threat(Color::White));
+        //white has no rook or queen
+        assert!(!board.back_rank_mate_threat(Color::Black));
+
+        //luft on h3
+        let board = Game::from_fen("3r2k1/5ppp/8/8/8/7P/5PP1/6K1 w - - 0 1").unwrap();
+
+        assert!(!board.back_rank_mate_threat(Color::White));
+
+        //king in the corner only needs two pawns in front of it
+        let board = Game::from_fen("3r3k/8/8/8/8/8/6PP/7K w - - 0 1").unwrap();
+
+        assert!(board.back_rank_mate_threat(Color::White));
+    }
+
+    #[test]
+    fn rook_file_test() {
+        let board = Game::from_fen("4k3/ppp2ppp/8/8/8/8/PP1P1PPP/2R1RK2 w - - 0 1").unwrap();
+
+        assert_eq!(board.open_files(), vec![4]);
+        assert_eq!(board.semi_open_files(Color::White), vec![2]);
+        assert_eq!(board.semi_open_files(Color::Black), vec![3]);
+        assert_eq!(board.rooks_on_open_files(Color::White), vec![(7, 4)]);
+        assert_eq!(board.rooks_on_semi_open_files(Color::White), vec![(7, 2)]);
+        assert!(board.connected_rooks(Color::White));
+
+        let board = Game::from_fen("4k3/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+
+        assert!(!board.connected_rooks(Color::White));
+
+        let board = Game::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1").unwrap();
+
+        assert!(board.connected_rooks(Color::White));
+        assert!(!board.connected_rooks(Color::Black));
+    }
+
+    #[test]
+    fn file_control_test() {
+        let board = Game::from_fen("3rk2r/8/8/8/8/8/8/Q2RK3 w - - 0 1").unwrap();
+        let control = board.file_control();
+
+        assert_eq!(control[0], FileControl::White);
+        assert_eq!(control[3], FileControl::Contested);
+        assert_eq!(control[4], FileControl::None);
+        assert_eq!(control[7], FileControl::Black);
+        assert_eq!(board.rooks_on_file(3, Color::White), 1);
+        assert_eq!(board.rooks_on_file(0, Color::Black), 0);
+        assert_eq!(board.rooks_on_file(8, Color::White), 0);
+        assert_eq!(control[3].to_string(), "contested");
+    }
+
+    #[test]
+    fn two_same_color_bishops_is_draw() {
+        // bishops on b3 and c2, both light squares
+        let mut board = Game::from_fen("8/8/8/8/8/1B6/2B5/K6k w - - 0 1").unwrap();
+
+        assert_eq!(board.get_state(), GameState::Draw(DrawState::InsufficientMaterial));
+
+        // bishops on c3 and c2 are on different square colors and can mate
+        let mut board = Game::from_fen("8/8/8/8/8/2B5/2B5/K6k w - - 0 1").unwrap();
+
+        assert_eq!(board.get_state(), GameState::InProgress);
+    }
+
+    #[test]
+    fn last_captured_piece_test() {
+        let mut board = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        assert_eq!(board.last_captured_piece(), None);
+
+        board.make_move("e1", "f1", true).unwrap();
+
+        assert!(!board.last_move_was_capture());
+
+        let mut board = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        board.make_move("e5", "d6", true).unwrap();
+
+        assert_eq!(board.last_captured_piece(), Some(Piece::new(PieceType::Pawn, Color::Black)));
+        assert!(board.last_move_was_capture());
+
+        board.make_move("e8", "d8", true).unwrap();
+
+        assert!(!board.last_move_was_capture());
+
+        board.make_move("d6", "d7", true).unwrap();
+        board.make_move("d8", "d7", true).unwrap();
+
+        assert_eq!(board.last_captured_piece(), Some(Piece::new(PieceType::Pawn, Color::White)));
+    }
+
+    #[test]
+    fn fifty_move_clock_test() {
+        let mut board = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 90 60").unwrap();
+
+        assert_eq!(board.half_move_clock(), 90);
+        assert_eq!(board.full_move_number(), 60);
+        assert_eq!(board.moves_until_fifty_rule(), 10);
+        assert!(board.is_approaching_fifty_move_rule(10));
+        assert!(!board.is_approaching_fifty_move_rule(9));
+
+        board.make_move("a1", "a2", true).unwrap();
+
+        assert_eq!(board.half_move_clock(), 91);
+        assert_eq!(board.moves_until_fifty_rule(), 9);
+
+        let board = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 120 60").unwrap();
+
+        assert_eq!(board.moves_until_fifty_rule(), 0);
+    }
+
+    #[test]
+    fn en_passant_target_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.en_passant_target(), None);
+
+        board.make_move("e2", "e4", true).unwrap();
+
+        assert_eq!(board.en_passant_target(), Some((5, 4)));
+        assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+        board.make_move("a7", "a6", true).unwrap();
+
+        assert!(!board.is_en_passant_available());
+
+        board.make_move("e4", "e5", true).unwrap();
+        board.make_move("d7", "d5", true).unwrap();
+
+        assert!(board.is_en_passant_available());
+        assert!(board.get_legal_moves_alg_notation("e5").unwrap().contains(&(2, 3)));
+
+        board.make_move("e5", "d6", true).unwrap();
+
+        assert_eq!(board.piece_at_alg_notation("d5"), Ok(None));
+    }
+
+    #[test]
+    fn resign_test() {
+        let mut board = Game::new_starting_pos();
+        board.make_move("e2", "e4", true).unwrap();
+        board.resign(Color::Black);
+
+        assert_eq!(board.get_state(), GameState::Win(WinState::Resignation(Color::Black)));
+        assert_eq!(board.terminal_value(), Some(1.0));
+        assert_eq!(board.make_move("e7", "e5", true), Ok(false));
+
+        board.resign(Color::White);
+
+        assert_eq!(board.get_state(), GameState::Win(WinState::Resignation(Color::Black)));
+    }
+
+    #[test]
+    fn board_editing_test() {
+        let mut board = Game::new_starting_pos();
+        board.make_move("e2", "e4", true).unwrap();
+
+        board.clear_board();
+
+        //castling rights and en passant square no longer match the board
+        assert_eq!(board.to_fen(), "8/8/8/8/8/8/8/8 b - - 0 1");
+
+        board.place_piece((7, 4), Piece::new(PieceType::King, Color::White)).unwrap();
+        board.place_piece((0, 4), Piece::new(PieceType::King, Color::Black)).unwrap();
+        board.place_piece((0, 0), Piece::new(PieceType::Rook, Color::White)).unwrap();
+
+        assert!(board.in_check(Color::Black));
+        assert!(board.place_piece((8, 0), Piece::new(PieceType::Rook, Color::White)).is_err());
+
+        board.flip_turn();
+
+        assert_eq!(board.get_active_player(), Color::White);
+        assert_eq!(board.remove_piece((0, 0)), Some(Piece::new(PieceType::Rook, Color::White)));
+        assert_eq!(board.remove_piece((0, 0)), None);
+        assert!(!board.in_check(Color::Black));
+
+        let fen = board.to_fen();
+        board.undo_last_move();
+
+        assert_eq!(board.to_fen(), fen);
+
+        //kings placed on an empty board can not castle, wherever they stand
+        for square in ["e1", "h1", "b1"] {
+            let mut board = Game::new_starting_pos();
+            board.clear_board();
+            board.place_piece(alg_notation_to_indx(square).unwrap(), Piece::new(PieceType::King, Color::White)).unwrap();
+            board.place_piece((0, 4), Piece::new(PieceType::King, Color::Black)).unwrap();
+
+            assert!(board.get_legal_moves_alg_notation(square).unwrap().len() <= 5);
+            assert_eq!(board.make_move("e1", "g1", true), Ok(false));
+        }
+
+        //rights of pieces still on their starting squares are kept
+        let mut board = Game::new_starting_pos();
+        board.remove_piece((7, 7));
+
+        assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN1 w Qkq - 0 1");
+
+        //castling rights without a king on its starting square are ignored
+        let mut board = Game::from_fen("4k3/8/8/8/8/8/8/R6K w KQ - 0 1").unwrap();
+
+        assert_eq!(board.get_legal_moves_alg_notation("h1").unwrap().len(), 3);
+
+        let mut board = Game::from_fen("4k3/8/8/8/8/8/8/4K3 w KQ - 0 1").unwrap();
+
+        assert_eq!(board.make_move("e1", "g1", true), Ok(false));
+    }
+
+    #[test]
+    fn crazyhouse_drop_test() {
+        let mut board = Game::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(board.drop_piece(PieceType::Pawn, (4, 4)).is_err());
+
+        board.set_game_mode(GameMode::Crazyhouse);
+
+        assert_eq!(board.drop_piece(PieceType::Pawn, (4, 4)), Ok(false));
+
+        board.make_move("e4", "d5", true).unwrap();
+
+        assert_eq!(board.get_reserve(Color::White).get(&PieceType::Pawn), Some(&1));
+
+        board.make_move("e8", "d8", true).unwrap();
+
+        //occupied square and back rank pawn drops are illegal
+        assert_eq!(board.drop_piece(PieceType::Pawn, (3, 3)), Ok(false));
+        assert_eq!(board.drop_piece(PieceType::Pawn, (0, 0)), Ok(false));
+        assert_eq!(board.drop_piece(PieceType::Knight, (4, 4)), Ok(false));
+        assert_eq!(board.drop_piece(PieceType::Pawn, (2, 4)), Ok(true));
+
+        assert_eq!(board.to_fen(), "3k4/8/4P3/3P4/8/8/8/4K3 b - - 2 2");
+        assert_eq!(board.pgn_move_text(), Ok(String::from("1. exd5 Kd8 2. P@e6 *")));
+        assert_eq!(board.get_reserve(Color::White).get(&PieceType::Pawn), Some(&0));
+
+        board.undo_last_move();
+
+        assert_eq!(board.get_reserve(Color::White).get(&PieceType::Pawn), Some(&1));
+
+        //undoing the capture takes the pawn out of the reserve again
+        board.undo_last_move();
+        board.undo_last_move();
+
+        assert_eq!(board.to_fen(), "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+        assert_eq!(board.get_reserve(Color::White).get(&PieceType::Pawn), Some(&0));
+    }
+
+    #[test]
+    fn undo_record_test() {
+        let fen = "r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1";
+        let mut board = Game::from_fen(fen).unwrap();
+        let mut fens = vec![board.to_fen()];
+
+        for (from, to) in [("e5", "d6"), ("e8", "g8"), ("e1", "c1"), ("f8", "f1"), ("b7", "b8")] {
+            assert!(board.make_move(from, to, true).unwrap());
+            fens.push(board.to_fen());
+        }
+
+        assert_eq!(board.get_captures(Color::White).len(), 1);
+        assert_eq!(board.last_captured_piece(), None);
+
+        //every move is undone in reverse order, restoring pieces, castling rights and en passant square
+        while fens.len() > 1 {
+            fens.pop();
+            board.undo_last_move();
+
+            assert_eq!(board.to_fen(), *fens.last().unwrap());
+        }
+
+        assert_eq!(board.get_captures(Color::White).len(), 0);
+        assert_eq!(board.get_last_move(), None);
+        assert_eq!(board.fen_history(), vec![fen.to_string()]);
+
+        //undoing without moves does nothing
+        board.undo_last_move();
+
+        assert_eq!(board.to_fen(), fen);
+
+        //undoing a move that is waiting for a promotion piece
+        let mut board = Game::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        board.make_move("b7", "b8", false).unwrap();
+        assert_eq!(board.get_state(), GameState::AwaitPromotion);
+
+        board.undo_last_move();
+
+        assert_eq!(board.promotion_square(), None);
+        assert_eq!(board.get_state(), GameState::InProgress);
+        assert!(board.make_move("b7", "b8", true).unwrap());
+        assert_eq!(board.to_fen(), "1Q2k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn crazyhouse_drop_blocks_mate_test() {
+        //back rank check that would be mate in standard chess
+        let mut board = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+
+        assert_eq!(board.get_state(), GameState::Win(WinState::Checkmate(Color::White)));
+
+        board.set_game_mode(GameMode::Crazyhouse);
+        board.drop_reserve.get_mut(&Color::Black).unwrap().insert(PieceType::Knight, 1);
+
+        assert_eq!(board.get_state(), GameState::InProgress);
+        assert_eq!(board.drop_piece(PieceType::Knight, (2, 2)), Ok(false));
+        assert_eq!(board.drop_piece(PieceType::Knight, (0, 3)), Ok(true));
+    }
+
+    #[test]
+    fn crazyhouse_captured_promoted_piece_test() {
+        let mut board = Game::from_fen("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        board.set_game_mode(GameMode::Crazyhouse);
+
+        board.make_move("b7", "b8", true).unwrap();
+        assert_eq!(board.board[0][1], Some(Piece::new(PieceType::Queen, Color::White)));
+
+        board.make_move("a8", "b8", true).unwrap();
+        assert_eq!(board.get_reserve(Color::Black), HashMap::from([(PieceType::Pawn, 1)]));
+
+        board.undo_last_move();
+
+        assert_eq!(board.get_reserve(Color::Black).get(&PieceType::Pawn).copied().unwrap_or(0), 0);
+        assert_eq!(board.promoted_pieces, square_bit((0, 1)));
+
+        //a piece that was not promoted still goes to the reserve as itself
+        board.make_move("e8", "e7", true).unwrap();
+        board.make_move("b8", "a8", true).unwrap();
+        assert_eq!(board.promoted_pieces, square_bit((0, 0)));
+        assert_eq!(board.get_reserve(Color::White), HashMap::from([(PieceType::Rook, 1)]));
+    }
+
+    #[test]
+    fn revert_keeps_configuration_test() {
+        let mut board = Game::new_starting_pos();
+        board.set_game_mode(GameMode::Crazyhouse);
+        board.set_event("Casual game");
+        board.attach_clock(Clock::new(60_000, 1_000));
+
+        board.make_move("e2", "e4", true).unwrap();
+        board.make_move("d7", "d5", true).unwrap();
+        board.make_move("e4", "d5", true).unwrap();
+        assert_eq!(board.get_reserve(Color::White), HashMap::from([(PieceType::Pawn, 1)]));
+
+        board.revert_to_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.get_game_mode(), GameMode::Crazyhouse);
+        assert_eq!(board.get_pgn_headers(), &[("Event".to_string(), "Casual game".to_string())]);
+        assert!(board.clock.is_some());
+        assert!(board.get_reserve(Color::White).values().all(|&count| count == 0));
+        assert!(board.undo_stack.is_empty());
+
+        board.revert_to_start();
+
+        assert_eq!(board.get_game_mode(), GameMode::Crazyhouse);
+        assert_eq!(board.get_pgn_headers().len(), 1);
+        assert!(board.clock.is_some());
+    }
+
+    #[test]
+    fn three_check_test() {
+        let mut board = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        board.set_game_mode(GameMode::ThreeCheck);
+
+        board.make_move("a1", "a8", true).unwrap();
+        board.make_move("e8", "e7", true).unwrap();
+        board.make_move("a8", "a7", true).unwrap();
+        board.make_move("e7", "e6", true).unwrap();
+
+        assert_eq!(board.checks_given(Color::White), 2);
+        assert_eq!(board.get_state(), GameState::InProgress);
+
+        board.make_move("a7", "a6", true).unwrap();
+
+        assert_eq!(board.checks_given(Color::White), 3);
+        assert_eq!(board.checks_given(Color::Black), 0);
+        assert_eq!(board.get_state(), GameState::Win(WinState::ThreeChecks(Color::White)));
+
+        board.undo_last_move();
+
+        assert_eq!(board.checks_given(Color::White), 2);
+        assert_eq!(board.checks_delivered(Color::White), 2);
+        assert_eq!(board.checks_delivered(Color::Black), 0);
+    }
+
+    #[test]
+    fn promotion_check_counted_once_test() {
+        let mut board = Game::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        board.make_move("b7", "b8", false).unwrap();
+
+        assert_eq!(board.checks_given(Color::White), 0);
+
+        board.promote_to_piece(PieceType::Queen);
+
+        assert_eq!(board.checks_given(Color::White), 1);
+
+        //auto promotion counts the check the same way as a manual promotion
+        let mut board = Game::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        board.set_game_mode(GameMode::ThreeCheck);
+        board.make_move("b7", "b8", true).unwrap();
+
+        assert_eq!(board.checks_given(Color::White), 1);
+
+        //the pawn move itself gives check by discovery, the promoted queen checks as well
+        let mut board = Game::from_fen("2n5/RP1k4/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        board.make_move("b7", "c8", true).unwrap();
+
+        assert!(board.in_check(Color::Black));
+        assert_eq!(board.checks_given(Color::White), 1);
+    }
+
+    #[test]
+    fn king_of_the_hill_test() {
+        let mut board = Game::from_fen("4k3/8/8/8/8/3K4/8/8 w - - 0 1").unwrap();
+        board.make_move("d3", "d4", true).unwrap();
+
+        assert_eq!(board.get_state(), GameState::Draw(DrawState::InsufficientMaterial));
+
+        board.undo_last_move();
+        board.set_game_mode(GameMode::KingOfTheHill);
+        board.make_move("d3", "d4", true).unwrap();
+
+        assert_eq!(board.get_state(), GameState::Win(WinState::KingOfTheHill(Color::White)));
+        assert_eq!(board.terminal_value(), Some(1.0));
+    }
+
+    #[test]
+    fn from_san_list_test() {
+        let game = Game::from_san_list(&["e4", "e5", "Nf3", "Nc6"]).unwrap();
+
+        assert_eq!(game.to_fen(), "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3");
+        assert_eq!(game.fen_history().len(), 5);
+
+        let game = Game::from_san_list(&["e4", "d5", "exd5", "c6", "dxc6", "Qb6", "cxb7", "Qxf2+", "Kxf2", "Kd7", "bxa8=N"]).unwrap();
+
+        assert_eq!(game.piece_at_alg_notation("a8"), Ok(Some(Piece::new(PieceType::Knight, Color::White))));
+
+        let err = Game::from_san_list(&["e4", "e5", "Ke3"]).unwrap_err();
+
+        assert!(err.contains("move 2"));
+        assert!(err.contains("Ke3"));
+    }
+
+    #[test]
+    fn tactical_complexity_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.tactical_complexity(Color::White), 0);
+
+        //black knight on d5 is attacked by the e4 pawn and undefended
+        let mut board = Game::from_fen("r3k3/8/8/3n4/4P3/3P4/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.hanging_pieces(Color::Black), vec![(3, 3)]);
+        assert!(board.hanging_pieces(Color::White).is_empty());
+        //2 for the knight being hanging, 1 for it being attacked
+        assert_eq!(board.tactical_complexity(Color::White), 3);
+        //Ra1+ gives check
+        assert_eq!(board.tactical_complexity(Color::Black), 6);
+    }
+
+    #[test]
+    fn is_quiet_position_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert!(board.is_quiet_position());
+
+        //the d5 knight can be captured
+        let mut board = Game::from_fen("4k3/8/8/3n4/4P3/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(!board.is_quiet_position());
+
+        //the e4 pawn is pinned, so it can not capture
+        let mut board = Game::from_fen("4r1k1/8/8/3n4/4P3/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(board.is_quiet_position());
+
+        //in check
+        let mut board = Game::from_fen("4r1k1/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(!board.is_quiet_position());
+    }
+
+    #[test]
+    fn battery_test() {
+        let board = Game::new_starting_pos();
+
+        assert_eq!(board.rook_queen_batteries(Color::White), vec![]);
+        assert_eq!(board.bishop_queen_batteries(Color::White), vec![]);
+
+        let board = Game::from_fen("4k3/8/8/8/8/2Q5/1B6/2R1K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.rook_queen_batteries(Color::White), vec![(7, 2, 5, 2)]);
+        assert_eq!(board.bishop_queen_batteries(Color::White), vec![(6, 1, 5, 2)]);
+        assert!(board.rook_queen_batteries(Color::Black).is_empty());
+
+        //the bishop blocks the line between the rook and the queen
+        let board = Game::from_fen("4k3/8/Q7/8/8/B7/8/R3K3 w - - 0 1").unwrap();
+
+        assert!(board.rook_queen_batteries(Color::White).is_empty());
+    }
+
+    #[test]
+    fn space_advantage_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.space_advantage(Color::White), 0);
+        assert_eq!(board.space_advantage_relative(), 0);
+
+        board.make_move("e2", "e4", true).unwrap();
+        board.make_move("a7", "a6", true).unwrap();
+        board.make_move("e4", "e5", true).unwrap();
+
+        //d6 and f6 by the pawn, b5 and a6 by the bishop and h5 by the queen
+        assert_eq!(board.space_advantage(Color::White), 5);
+        assert_eq!(board.space_advantage(Color::Black), 0);
+        assert_eq!(board.space_advantage_relative(), 5);
+    }
+
+    #[test]
+    fn weak_squares_test() {
+        let board = Game::from_fen("4k3/8/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1").unwrap();
+
+        assert_eq!(board.controlled_by_pawns(Color::White).len(), 8);
+        assert!(board.weak_squares(Color::White).is_empty());
+
+        //the empty d2 square can never be attacked by a pawn
+        let board = Game::from_fen("4k3/8/8/8/8/8/PPP1PPPP/RNBQKBNR w KQ - 0 1").unwrap();
+
+        assert_eq!(board.weak_squares(Color::White), vec![(6, 3)]);
+
+        let board = Game::from_fen("4k3/8/8/8/8/8/PP3PPP/RNBQKBNR w KQ - 0 1").unwrap();
+
+        assert_eq!(board.weak_squares(Color::White), vec![(4, 3), (5, 3), (6, 2), (6, 3), (6, 4)]);
+    }
+
+    #[test]
+    fn promotion_candidates_test() {
+        let board = Game::new_starting_pos();
+
+        assert_eq!(board.pawn_count(Color::White), 8);
+        assert!(!board.has_promotion_pawns(Color::White));
+        assert!(!board.has_promotion_pawns(Color::Black));
+        assert!(!board.has_promotion_threat(Color::White));
+
+        let board = Game::from_fen("4k3/1P5P/8/8/8/8/p2P4/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.pawn_count(Color::White), 3);
+        assert_eq!(board.pawn_count(Color::Black), 1);
+        assert_eq!(board.promotion_candidates(Color::White), vec![(1, 1), (1, 7)]);
+        assert_eq!(board.promotion_candidates(Color::Black), vec![(6, 0)]);
+        assert!(board.has_promotion_pawns(Color::Black));
+        assert_eq!(board.promotion_threats(Color::White), vec![(1, 1), (1, 7)]);
+        assert_eq!(board.promotion_threats(Color::Black), vec![(6, 0)]);
+        assert!(board.has_promotion_threat(Color::White));
+        assert_eq!(board.fastest_promotion_distance(Color::White), Some(1));
+
+        let board = Game::from_fen("4k3/8/8/2p5/8/8/P7/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.fastest_promotion_distance(Color::White), Some(5));
+        assert_eq!(board.fastest_promotion_distance(Color::Black), Some(4));
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap().fastest_promotion_distance(Color::White), None);
+    }
+
+    #[test]
+    fn pawn_islands_test() {
+        let board = Game::new_starting_pos();
+
+        assert_eq!(board.pawn_islands_count(Color::White), 1);
+        assert_eq!(board.isolated_pawn_count(Color::White), 0);
+
+        //white pawns on a, c, d, d and h, black pawns on b and g
+        let board = Game::from_fen("4k3/1p4p1/8/8/3P4/8/P1PP3P/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.pawn_islands_count(Color::White), 3);
+        assert_eq!(board.isolated_pawn_count(Color::White), 2);
+        assert_eq!(board.pawn_islands_count(Color::Black), 2);
+        assert_eq!(board.isolated_pawn_count(Color::Black), 2);
+
+        let board = Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.pawn_islands_count(Color::White), 0);
+    }
+
+    #[test]
+    fn swap_sides_test() {
+        let game = Game::new_starting_pos();
+        let swapped = game.swap_sides();
+
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(swapped.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1");
+        assert_eq!(swapped.swap_sides().to_fen(), game.to_fen());
+    }
+
+    //xorshift generator, so random tests are reproducible without extra dependencies
+    struct TestRng(u64);
+
+    impl TestRng {
+        fn next(&mut self, bound : u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+
+            self.0 % bound
+        }
+    }
+
+    //generates a structurally valid FEN string, the position is not necessarily legal
+    fn random_fen(rng : &mut TestRng) -> String {
+        let pieces = ['P', 'N', 'B', 'R', 'Q', 'K', 'p', 'n', 'b', 'r', 'q', 'k'];
+        let mut ranks = Vec::new();
+
+        for i in 0..8 {
+            let mut rank = String::new();
+            let mut empty_squares = 0;
+
+            for _ in 0..8 {
+                let piece = pieces[rng.next(pieces.len() as u64) as usize];
+                let is_pawn = piece == 'P' || piece == 'p';
+
+                //no pawns on the first and last rank
+                if rng.next(3) != 0 || (is_pawn && (i == 0 || i == 7)) {
+                    empty_squares += 1;
+                    continue;
+                }
+
+                if empty_squares > 0 {
+                    rank.push_str(&empty_squares.to_string());
+                    empty_squares = 0;
+                }
+
+                rank.push(piece);
+            }
+
+            if empty_squares > 0 {
+                rank.push_str(&empty_squares.to_string());
+            }
+
+            ranks.push(rank);
+        }
+
+        let turn = if rng.next(2) == 0 {"w"} else {"b"};
+        //any subset of the castling rights, in the order KQkq
+        let mut castling : String = "KQkq".chars().filter(|_| rng.next(2) == 0).collect();
+
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = match rng.next(3) {
+            0 => "-".to_string(),
+            _ => {
+                let file = (b'a' + rng.next(8) as u8) as char;
+                let rank = if turn == "w" {6} else {3};
+
+                format!("{}{}", file, rank)
+            }
+        };
+
+        format!("{} {} {} {} {} {}", ranks.join("/"), turn, castling, en_passant, rng.next(100), rng.next(200) + 1)
+    }
+
+    #[test]
+    fn fen_roundtrip_test() {
+        let mut rng = TestRng(0x2545F4914F6CDD1D);
+
+        for _ in 0..500 {
+            let fen = random_fen(&mut rng);
+
+            assert_eq!(Game::from_fen(&fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn random_game_test() {
+        let game = Game::random_game(30, 7);
+
+        assert_eq!(game.fen_history(), Game::random_game(30, 7).fen_history());
+        assert!(game.fen_history().len() <= 31);
+
+        for mut game in Game::random_games(2, 0) {
+            let moves_made = game.fen_history().len() - 1;
+
+            assert!(game.is_terminal() || moves_made == RANDOM_GAMES_MAX_MOVES as usize);
+
+            for fen in game.fen_history() {
+                assert_eq!(Game::from_fen(&fen).unwrap().to_fen(), fen);
+            }
+        }
+    }
+
+    #[test]
+    fn chess960_test() {
+        let game = Game::from_chess960_number(518).unwrap();
+
+        assert_eq!(game.to_fen(), Game::new_starting_pos().to_fen());
+
+        let game = Game::from_chess960_number(0).unwrap();
+
+        assert_eq!(game.to_fen(), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w - - 0 1");
+        assert_eq!(Game::from_chess960_number(959).unwrap().to_fen(), "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB w - - 0 1");
+        assert!(Game::from_chess960_number(960).is_err());
+
+        for n in [0, 1, 518, 959] {
+            assert_eq!(Game::from_chess960_number(n).unwrap().chess960_position_number(), Some(n));
+        }
+
+        let mut game = Game::new_starting_pos();
+        game.make_move("g1", "f3", true).unwrap();
+
+        assert_eq!(game.chess960_position_number(), None);
+    }
+
+    #[test]
+    fn squares_between_test() {
+        assert_eq!(squares_between((7, 0), (7, 7)), vec![(7, 1), (7, 2), (7, 3), (7, 4), (7, 5), (7, 6)]);
+        assert_eq!(squares_between((0, 4), (3, 4)), vec![(1, 4), (2, 4)]);
+        assert_eq!(squares_between((7, 7), (4, 4)), vec![(6, 6), (5, 5)]);
+        assert_eq!(squares_between((0, 7), (2, 5)), vec![(1, 6)]);
+
+        assert!(squares_between((7, 0), (7, 1)).is_empty());
+        assert!(squares_between((7, 0), (5, 1)).is_empty());
+        assert!(squares_between((3, 3), (3, 3)).is_empty());
+    }
+
+    #[test]
+    fn move_annotation_test() {
+        let annotation = MoveAnnotation {
+            evaluation : 35,
+            best_line : vec!["e5".to_string(), "Nf3".to_string()],
+            time_ms : 120,
+        };
+
+        let mut game = Game::new_starting_pos();
+        game.annotate_last_move(annotation.clone());
+
+        assert_eq!(game.get_annotation(0), None);
+
+        game.make_move("e2", "e4", true).unwrap();
+        game.make_move("e7", "e5", true).unwrap();
+        game.annotate_last_move(annotation.clone());
+
+        assert_eq!(game.get_annotation(0), None);
+        assert_eq!(game.get_annotation(1), Some(&annotation));
+        assert_eq!(annotation.to_pgn_comment(), "{ +0.35/2 }");
+
+        game.undo_last_move();
+
+        assert_eq!(game.get_annotation(1), None);
+
+        let annotation = MoveAnnotation { evaluation : -150, best_line : Vec::new(), time_ms : 0 };
+
+        assert_eq!(annotation.to_pgn_comment(), "{ -1.50 }");
+    }
+
+    #[test]
+    fn opening_book_test() {
+        let mut game = Game::new_starting_pos();
+        let mut book = OpeningBook::new();
+
+        assert!(!game.is_book_position(&book));
+
+        book.add_position(&game, "e2e4");
+        book.add_position(&game, "d2d4");
+
+        assert!(game.is_book_position(&book));
+        assert_eq!(game.book_moves(&book), vec!["e2e4", "d2d4"]);
+
+        game.make_move("g1", "f3", true).unwrap();
+
+        assert!(game.book_moves(&book).is_empty());
+
+        game.undo_last_move();
+
+        assert!(game.is_book_position(&book));
+    }
+
+    #[test]
+    fn polyglot_key_test() {
+        //test keys from the Polyglot book format documentation
+        let mut game = Game::new_starting_pos();
+        assert_eq!(game.polyglot_key(), 0x463b96181691fc9c);
+
+        let keys = [
+            ("e2", "e4", 0x823c9b50fd114196),
+            ("d7", "d5", 0x0756b94461c50fb0),
+            ("e4", "e5", 0x662fafb965db29d4),
+            ("f7", "f5", 0x22a48b5a8e47ff78),
+            ("e1", "e2", 0x652a607ca3f242c1),
+            ("e8", "f7", 0x00fdd303c946bdd9),
+        ];
+
+        for (from, to, key) in keys {
+            game.make_move(from, to, true).unwrap();
+            assert_eq!(game.polyglot_key(), key);
+        }
+
+        let mut game = Game::new_starting_pos();
+
+        for (from, to) in [("a2", "a4"), ("b7", "b5"), ("h2", "h4"), ("b5", "b4"), ("c2", "c4")] {
+            game.make_move(from, to, true).unwrap();
+        }
+        assert_eq!(game.polyglot_key(), 0x3c8123ea7b067637);
+
+        game.make_move("b4", "c3", true).unwrap();
+        game.make_move("a1", "a3", true).unwrap();
+        assert_eq!(game.polyglot_key(), 0x5c3f9b829b279560);
+    }
+
+    #[test]
+    fn polyglot_parse_test() {
+        let start = Game::new_starting_pos();
+
+        //e2e4 with weight 1, d2d4 with weight 2, one entry of another position
+        let e4 : u16 = (1 << 9) | (4 << 6) | (3 << 3) | 4;
+        let d4 : u16 = (1 << 9) | (3 << 6) | (3 << 3) | 3;
+
+        let mut data = Vec::new();
+
+        for (key, raw_move, weight) in [(0x463b96181691fc9c_u64, e4, 1_u16), (0x463b96181691fc9c, d4, 2), (1, e4, 1)] {
+            data.extend_from_slice(&key.to_be_bytes());
+            data.extend_from_slice(&raw_move.to_be_bytes());
+            data.extend_from_slice(&weight.to_be_bytes());
+            data.extend_from_slice(&[0; 4]);
+        }
 
-/// Draw states used in `GameState::Draw`
-#[derive(Debug, Clone, PartialEq)]
-pub enum DrawState {
-    Stalemate,
-    InsufficientMaterial,
-    FiftyMoveRule
-}
-#[derive(Debug, Clone, PartialEq)]
-/// Win state used in `GameState::Win`.
-/// `Color` represents the color of the winner.
-pub enum WinState {
-    Checkmate(Color)
-}
-/// Struct for representing a chess piece.
-/// 
-/// # Creation
-/// * `piece_type` represents what type of piece it is e.g. pawn, 
-/// knight, bishop etc.
-/// * `color` represents the color of the piece, `Color::White` or `Color::Black`
-/// 
-/// # Examples
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct Piece {
-    pub piece_type : PieceType,
-    pub color : Color,
-}
+        let book = OpeningBook::from_polyglot(&data).unwrap();
 
-impl Piece {
-    pub fn new(piece_type : PieceType, color : Color) -> Piece{
-        Piece {
-            piece_type,
-            color,
+        assert_eq!(book.len(), 2);
+        assert!(start.is_book_position(&book));
+        assert_eq!(start.book_moves(&book), vec!["d2d4", "e2e4"]);
+        assert!(OpeningBook::from_polyglot(&data[..15]).is_err());
+
+        //castling written as e1h1 in the book
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let castling : u16 = (4 << 6) | 7;
+
+        let mut data = game.polyglot_key().to_be_bytes().to_vec();
+        data.extend_from_slice(&castling.to_be_bytes());
+        data.extend_from_slice(&[0; 6]);
+
+        let book = OpeningBook::from_polyglot(&data).unwrap();
+        assert_eq!(game.book_moves(&book), vec!["e1g1"]);
+
+        //a7a8 promoting to a queen
+        assert_eq!(polyglot_move_to_uci((4 << 12) | (6 << 9) | (7 << 3)), Ok("a7a8q".to_string()));
+    }
+
+    #[test]
+    fn perft_bulk_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.perft_bulk(0), 1);
+        assert_eq!(board.perft_bulk(1), 20);
+        assert_eq!(board.perft_bulk(2), 400);
+        assert_eq!(board.perft_bulk(3), 8902);
+
+        //each promotion piece is counted
+        let mut board = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.perft_bulk(1), 9);
+    }
+
+    #[test]
+    fn perft_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.perft(0), 1);
+        assert_eq!(board.perft(1), 20);
+        assert_eq!(board.perft(2), 400);
+        assert_eq!(board.perft(3), 8902);
+
+        let divide = board.perft_divide(2);
+
+        assert_eq!(divide.len(), 20);
+        assert_eq!(divide.get("e2e4"), Some(&20));
+        assert_eq!(divide.values().sum::<u64>(), 400);
+        assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        //castling, en passant and promotions
+        let mut board = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+
+        assert_eq!(board.perft(1), 48);
+        assert_eq!(board.perft(2), 2039);
+
+        let mut board = Game::from_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1").unwrap();
+
+        assert_eq!(board.perft(1), 24);
+        assert_eq!(board.perft(2), 496);
+        assert_eq!(board.perft_divide(1).get("b2b1q"), None);
+        assert_eq!(board.perft_divide(1).get("g2h1q"), Some(&1));
+    }
+
+    #[test]
+    #[ignore = "slow, run with --ignored"]
+    fn perft_deep_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.perft(4), 197281);
+        assert_eq!(board.perft(5), 4865609);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn clock_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.time_remaining(Color::White), 0);
+        assert_eq!(board.flagged(), None);
+
+        board.attach_clock(Clock::new(60_000, 1_000));
+        board.start_clock();
+
+        assert_eq!(board.make_move_timed("e2", "e5", true), Ok(false));
+        assert_eq!(board.make_move_timed("e2", "e4", true), Ok(true));
+
+        //white got the increment, allowing some time for the test itself
+        let white_ms = board.time_remaining(Color::White);
+        assert!(white_ms > 60_000 && white_ms <= 61_000);
+        assert!(board.time_remaining(Color::Black) <= 60_000);
+
+        board.stop_clock();
+        board.attach_clock(Clock::new(0, 1_000));
+
+        assert_eq!(board.flagged(), Some(Color::Black));
+
+        //no increment after running out of time
+        board.start_clock();
+        board.stop_clock();
+
+        assert_eq!(board.time_remaining(Color::Black), 0);
+    }
+
+    #[test]
+    fn print_legal_moves_visual_test() {
+        let mut board = Game::from_fen("4k3/8/8/8/8/8/8/N3K3 w - - 0 1").unwrap();
+        let visual = board.print_legal_moves_visual("a1").unwrap();
+        let rows : Vec<&str> = visual.lines().collect();
+
+        assert_eq!(rows.len(), 8);
+        assert_eq!(rows[0], ". . . . k . . .    . . . . . . . . ");
+        assert_eq!(rows[5], ". . . . . . . .    . * . . . . . . ");
+        assert_eq!(rows[6], ". . . . . . . .    . . * . . . . . ");
+        assert_eq!(rows[7], "N . . . K . . .    N . . . . . . . ");
+        assert!(board.print_legal_moves_visual("z9").is_err());
+    }
+
+    #[test]
+    fn material_value_map_test() {
+        let board = Game::new_starting_pos();
+        let map = board.material_value_map();
+
+        assert_eq!(map[0], [-500, -320, -330, -900, -20000, -330, -320, -500]);
+        assert_eq!(map[4], [0; 8]);
+        assert_eq!(map[6], [100; 8]);
+        assert_eq!(board.total_material_on_board(), (4000, 4000));
+        assert_eq!(board.piece_count_total(), 32);
+        assert_eq!(board.piece_count_excluding_kings(), 30);
+
+        let board = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.total_material_on_board(), (500, 0));
+        assert_eq!(board.piece_count_total(), 3);
+        assert_eq!(board.piece_count_excluding_kings(), 1);
+    }
+
+    #[test]
+    fn is_only_kings_test() {
+        let mut board = Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(board.is_only_kings());
+        assert_eq!(board.draw_reason(), Some(DrawState::InsufficientMaterial));
+
+        assert!(!Game::new_starting_pos().is_only_kings());
+        assert!(!Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap().is_only_kings());
+
+        //an empty board, a lone king and two kings of the same color
+        assert!(!Game::from_fen("8/8/8/8/8/8/8/8 w - - 0 1").unwrap().is_only_kings());
+        assert!(!Game::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1").unwrap().is_only_kings());
+        assert!(!Game::from_fen("4K3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap().is_only_kings());
+    }
+
+    #[test]
+    fn legal_promotions_test() {
+        let mut board = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.promotion_square(), None);
+
+        board.make_move("a7", "a8", false).unwrap();
+
+        assert_eq!(board.promotion_square(), Some((0, 0)));
+        assert_eq!(board.promotion_square_as_alg(), Some("a8".to_string()));
+        assert!(!board.can_promote_to(PieceType::Pawn));
+        assert!(!board.promote_to_piece(PieceType::King));
+        assert!(!board.promote_to_piece(PieceType::Pawn));
+        assert_eq!(board.get_state(), GameState::AwaitPromotion);
+        assert!(board.promote_to_piece(PieceType::Knight));
+        assert_eq!(board.piece_at_alg_notation("a8"), Ok(Some(Piece::new(PieceType::Knight, Color::White))));
+        assert_eq!(board.promotion_square_as_alg(), None);
+    }
+
+    #[test]
+    fn forced_move_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert!(!board.is_forced_move(Color::White));
+        assert_eq!(board.get_forced_move(Color::White), None);
+
+        //the king can only take the checking queen
+        let mut board = Game::from_fen("7k/6Q1/8/8/8/8/8/K7 b - - 0 1").unwrap();
+
+        assert!(board.is_forced_move(Color::Black));
+        assert_eq!(board.get_forced_move(Color::Black), Some((0, 7, 1, 6)));
+
+        //checkmate, no legal moves
+        let mut board = Game::from_fen("7k/6Q1/8/8/8/8/8/K5R1 b - - 0 1").unwrap();
+
+        assert!(!board.is_forced_move(Color::Black));
+    }
+
+    #[test]
+    fn king_activity_test() {
+        let board = Game::from_fen("7k/8/8/8/4K3/8/8/8 w - - 0 1").unwrap();
+
+        assert_eq!(board.king_activity_score(Color::White), 0);
+        assert_eq!(board.king_activity_score(Color::Black), 3);
+        assert_eq!(board.king_endgame_zone(Color::White).len(), 24);
+        assert_eq!(board.king_endgame_zone(Color::Black), vec![(0, 5), (0, 6), (1, 5), (1, 6), (1, 7), (2, 5), (2, 6), (2, 7)]);
+
+        let board = Game::from_fen("8/8/8/8/8/8/8/K7 w - - 0 1").unwrap();
+
+        assert_eq!(board.king_activity_score(Color::White), 3);
+        assert!(board.king_endgame_zone(Color::Black).is_empty());
+    }
+
+    #[test]
+    fn pgn_move_text_test() {
+        let game = Game::new_starting_pos();
+
+        assert_eq!(game.pgn_move_text(), Ok(String::from("*")));
+
+        let mut game = Game::from_san_list(&["e4", "e5", "Nf3", "Nc6", "Bb5"]).unwrap();
+
+        assert_eq!(game.pgn_move_text(), Ok(String::from("1. e4 e5 2. Nf3 Nc6 3. Bb5 *")));
+
+        game.annotate_last_move(MoveAnnotation { evaluation : 35, best_line : Vec::new(), time_ms : 0 });
+
+        assert_eq!(game.pgn_move_text_with_evaluations(), Ok(String::from("1. e4 e5 2. Nf3 Nc6 3. Bb5 { +0.35 } *")));
+
+        let game = Game::from_san_list(&["f3", "e5", "g4", "Qh4#"]).unwrap();
+
+        assert_eq!(game.pgn_move_text(), Ok(String::from("1. f3 e5 2. g4 Qh4# 0-1")));
+
+        let mut game = Game::from_fen("4k3/P7/8/8/8/8/8/4K2R b K - 0 10").unwrap();
+        game.make_move("e8", "e7", true).unwrap();
+        game.make_move("e1", "g1", true).unwrap();
+        game.make_move("e7", "d6", true).unwrap();
+        game.make_move("a7", "a8", false).unwrap();
+        game.promote_to_piece(PieceType::Knight);
+        game.resign(Color::Black);
+
+        assert_eq!(game.pgn_move_text(), Ok(String::from("10... Ke7 11. O-O Kd6 12. a8=N 1-0")));
+    }
+
+    #[test]
+    fn last_move_pgn_test() {
+        let mut game = Game::new_starting_pos();
+
+        assert!(game.last_move_pgn().is_err());
+
+        game.make_move("e2", "e4", true).unwrap();
+        assert_eq!(game.last_move_pgn(), Ok(String::from("1. e4")));
+
+        let game = Game::from_san_list(&["f3", "e5", "g4", "Qh4#"]).unwrap();
+        assert_eq!(game.last_move_pgn(), Ok(String::from("2... Qh4#")));
+
+        let mut game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 23").unwrap();
+        game.make_move("a7", "a8", false).unwrap();
+        game.promote_to_piece(PieceType::Rook);
+        assert_eq!(game.last_move_pgn(), Ok(String::from("23. a8=R+")));
+    }
+
+    #[test]
+    fn snapshot_for_analysis_test() {
+        let mut game = Game::from_san_list(&["e4", "d5", "exd5"]).unwrap();
+        game.annotate_last_move(MoveAnnotation { evaluation : 0, best_line : Vec::new(), time_ms : 0 });
+
+        let mut analysis = game.snapshot_for_analysis();
+
+        assert_eq!(analysis.to_fen(), game.to_fen());
+        assert_eq!(game.get_captures(Color::White).len(), 1);
+        assert!(analysis.get_captures(Color::White).is_empty());
+        assert_eq!(analysis.get_annotation(2), None);
+        assert_eq!(analysis.pgn_move_text(), Ok(String::from("*")));
+
+        analysis.undo_last_move();
+
+        assert_eq!(analysis.to_fen(), game.to_fen());
+    }
+
+    #[test]
+    fn fen_parse_error_test() {
+        let err = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1").unwrap_err();
+
+        assert_eq!(err, FenParseError { field_index : 1, expected : "'w' or 'b'".to_string(), got : "x".to_string() });
+        assert_eq!(err.to_string(), "Invalid FEN active color field, expected 'w' or 'b' but got 'x'");
+
+        let boxed : Box<dyn std::error::Error> = Box::new(err);
+        assert!(boxed.source().is_none());
+
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8/8 w").unwrap_err().field_index, 2);
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8 w - - 0 1").unwrap_err().field_index, 0);
+        assert_eq!(Game::from_fen("9/8/8/8/8/8/8/8 w - - 0 1").unwrap_err().got, "9");
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8/7RR w - - 0 1").unwrap_err().got, "7RR");
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8/7 w - - 0 1").unwrap_err().got, "7");
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8/8 w - z9 0 1").unwrap_err().field_index, 3);
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8/8 w - - -1 1").unwrap_err().field_index, 4);
+
+        let mut game = Game::new_starting_pos();
+
+        assert!(game.revert_to_fen("8/8/8/8/8/8/8/8 w").unwrap_err().contains("castling"));
+    }
+
+    #[test]
+    fn check_evasion_moves_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert!(board.check_evasion_moves(Color::White).is_empty());
+
+        //rook check can be blocked on e5 by the bishop or the f3 knight,
+        //the d2 knight is pinned by the a5 bishop
+        let mut board = Game::from_fen("4r1k1/8/8/b7/8/5N2/1B1N4/4K3 w - - 0 1").unwrap();
+        let mut evasions = board.check_evasion_moves(Color::White);
+        evasions.sort();
+
+        let mut legal = board.legal_moves_in_board_order(Color::White);
+        legal.sort();
+
+        assert_eq!(evasions, legal);
+        assert!(evasions.contains(&(6, 1, 3, 4)));
+        assert!(!evasions.iter().any(|&(i, j, _, _)| (i, j) == (6, 3)));
+
+        //double check, only the king can move
+        let mut board = Game::from_fen("4r1k1/8/8/8/8/2Bn4/8/4K3 w - - 0 1").unwrap();
+        let evasions = board.check_evasion_moves(Color::White);
+
+        assert!(!evasions.is_empty());
+        assert!(evasions.iter().all(|&(i, j, _, _)| (i, j) == (7, 4)));
+
+        //checking pawn captured en passant
+        let mut board = Game::from_fen("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1").unwrap();
+
+        assert!(board.check_evasion_moves(Color::Black).contains(&(4, 4, 5, 3)));
+    }
+
+    #[test]
+    fn get_all_legal_moves_flat_test() {
+        let mut board = Game::new_starting_pos();
+        let moves = board.get_all_legal_moves_flat(Color::White);
+
+        assert_eq!(moves.len(), 20);
+        assert_eq!(moves[0], ((6, 0), (5, 0)));
+        assert_eq!(moves[19].0, (7, 6));
+        assert!(board.get_all_legal_moves_flat(Color::Black).iter().all(|(from, _)| from.0 <= 1));
+    }
+
+    #[test]
+    fn board_fen_test() {
+        let game = Game::from_san_list(&["e4", "e5"]).unwrap();
+
+        assert_eq!(game.board_fen(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR");
+
+        let game = Game::from_board_fen(&Game::new_starting_pos().board_fen()).unwrap();
+
+        assert_eq!(game.to_fen(), Game::new_starting_pos().to_fen());
+
+        let game = Game::from_board_fen("r3k3/8/8/8/8/8/8/4K2R").unwrap();
+
+        assert_eq!(game.to_fen(), "r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1");
+        assert!(Game::from_board_fen("8/8/8/8/8/8/8/8/8").is_err());
+        assert!(Game::from_board_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").is_err());
+    }
+
+    #[test]
+    fn zobrist_hash_test() {
+        let a = Game::from_san_list(&["Nf3", "Nc6", "Nc3"]).unwrap();
+        let b = Game::from_san_list(&["Nc3", "Nc6", "Nf3"]).unwrap();
+
+        assert_eq!(a.position_hash(), b.position_hash());
+        assert_ne!(a.position_hash(), Game::new_starting_pos().position_hash());
+
+        //the incremental hash always matches the hash computed from scratch
+        let mut game = Game::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+
+        for (from, to) in [("e5", "d6"), ("e8", "g8"), ("e1", "c1"), ("f8", "f1"), ("b7", "b8")] {
+            let hash_before = game.position_hash();
+
+            assert!(game.make_move(from, to, true).unwrap());
+
+            assert_eq!(game.position_hash(), game.compute_zobrist_hash());
+            assert_eq!(game.position_hash(), Game::from_fen(&game.to_fen()).unwrap().position_hash());
+
+            game.undo_last_move();
+            assert_eq!(game.position_hash(), hash_before);
+
+            game.make_move(from, to, true).unwrap();
+        }
+
+        let hash = game.position_hash();
+
+        game.make_null_move();
+
+        assert_eq!(game.position_hash(), game.compute_zobrist_hash());
+
+        game.unmake_null_move();
+
+        assert_eq!(game.position_hash(), hash);
+
+        for game in Game::random_games(2, 11) {
+            for position in &game {
+                assert_eq!(position.position_hash(), position.compute_zobrist_hash());
+            }
         }
     }
-}
 
-/// Enum for all types of standard chess pieces
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum PieceType {
-    Pawn,
-    Knight,
-    Bishop,
-    Rook,
-    Queen,
-    King,
-}
+    #[test]
+    fn to_fen_core_test() {
+        let mut game = Game::new_starting_pos();
+        let start = game.clone();
 
-/// Enum for piece color
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[derive(Hash)]
-pub enum Color {
-    White,
-    Black,
-}
+        assert_eq!(game.to_fen_core(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -");
+
+        game.make_move("g1", "f3", true).unwrap();
+        assert!(!game.same_core_position(&start));
+
+        game.make_move("g8", "f6", true).unwrap();
+        game.make_move("f3", "g1", true).unwrap();
+        game.make_move("f6", "g8", true).unwrap();
+
+        assert_ne!(game.to_fen(), start.to_fen());
+        assert!(game.same_core_position(&start));
+    }
+
+    #[test]
+    fn repetition_count_by_fen_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.repetition_count_by_fen(), 1);
+
+        for _ in 0..2 {
+            board.make_move("g1", "f3", true).unwrap();
+            board.make_move("g8", "f6", true).unwrap();
+            board.make_move("f3", "g1", true).unwrap();
+            board.make_move("f6", "g8", true).unwrap();
+        }
+
+        assert_eq!(board.repetition_count_by_fen(), 3);
+        assert!(board.is_threefold_by_fen());
+        assert_eq!(board.get_state(), GameState::Draw(DrawState::ThreefoldRepetition));
+
+        board.undo_last_move();
+
+        assert_eq!(board.repetition_count_by_fen(), 2);
+        assert!(!board.is_threefold_by_fen());
+        assert_eq!(board.get_state(), GameState::InProgress);
+
+        board.place_piece((5, 0), Piece::new(PieceType::Pawn, Color::White)).unwrap();
+
+        assert_eq!(board.repetition_count_by_fen(), 1);
+    }
+
+    #[test]
+    fn repetition_count_test() {
+        let mut board = Game::new_starting_pos();
+
+        for _ in 0..2 {
+            board.make_move("g1", "f3", true).unwrap();
+            board.make_move("g8", "f6", true).unwrap();
+            board.make_move("f3", "g1", true).unwrap();
+            board.make_move("f6", "g8", true).unwrap();
+        }
+
+        assert_eq!(board.repetition_count(), 3);
+        assert!(board.is_threefold_repetition());
+        assert_eq!(board.get_state(), GameState::Draw(DrawState::ThreefoldRepetition));
+
+        board.undo_last_move();
+
+        assert_eq!(board.repetition_count(), 2);
+        assert_eq!(board.get_state(), GameState::InProgress);
+
+        //the en passant square after 1. e4 can not be used, so the position repeats
+        let board = Game::from_san_list(&["e4", "Nf6", "Nf3", "Ng8", "Ng1"]).unwrap();
+
+        assert_eq!(board.repetition_count(), 2);
+        assert_eq!(board.repetition_count_by_fen(), 1);
+
+        //the en passant capture exf6 is possible
+        let with_capture = Game::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3").unwrap();
+        let without_capture = Game::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3").unwrap();
+
+        assert_ne!(with_capture.position_hash(), without_capture.position_hash());
+
+        //dxe3 would leave the black king in check from the rook
+        let pinned = Game::from_fen("8/8/8/8/k2pP2R/8/8/4K3 b - e3 0 1").unwrap();
+        let without_capture = Game::from_fen("8/8/8/8/k2pP2R/8/8/4K3 b - - 0 1").unwrap();
+
+        assert_eq!(pinned.position_hash(), without_capture.position_hash());
+    }
+
+    #[test]
+    fn king_opposition_test() {
+        let board = Game::from_fen("8/8/4k3/8/4K3/8/8/8 w - - 0 1").unwrap();
+
+        assert_eq!(board.king_proximity(), 2);
+        assert!(board.kings_in_opposition());
+        assert!(!board.kings_in_diagonal_opposition());
+        //white has to give way
+        assert!(!board.active_king_has_opposition());
+
+        let board = Game::from_fen("8/8/2k5/8/4K3/8/8/8 b - - 0 1").unwrap();
+
+        assert!(!board.kings_in_opposition());
+        assert!(board.kings_in_diagonal_opposition());
+        assert!(!board.active_king_has_opposition());
+
+        //Ke4 takes the opposition
+        let board = Game::from_fen("8/8/4k3/8/8/4K3/8/8 w - - 0 1").unwrap();
+
+        assert!(!board.kings_in_opposition());
+        assert!(board.active_king_has_opposition());
+        assert!(Game::from_fen("8/8/4k3/8/8/4K3/8/8 b - - 0 1").unwrap().active_king_has_opposition());
+        assert!(!Game::from_fen("8/8/4k3/8/8/8/4K3/8 w - - 0 1").unwrap().active_king_has_opposition());
+
+        //Kc5 takes the diagonal opposition
+        assert!(Game::from_fen("8/8/2k5/8/8/4K3/8/8 b - - 0 1").unwrap().active_king_has_opposition());
+
+        //the square needed for the opposition is covered by a rook
+        assert!(!Game::from_fen("8/8/4k3/8/7r/4K3/8/8 w - - 0 1").unwrap().active_king_has_opposition());
+
+        let board = Game::from_fen("k7/8/8/8/8/8/8/6K1 w - - 0 1").unwrap();
+
+        assert_eq!(board.king_proximity(), 7);
+        assert!(!board.active_king_has_opposition());
+        assert_eq!(Game::from_fen("8/8/8/8/8/8/8/6K1 w - - 0 1").unwrap().king_proximity(), 0);
+    }
+
+    #[test]
+    fn mobility_imbalance_test() {
+        let mut board = Game::new_starting_pos();
+
+        assert_eq!(board.mobility_imbalance(), 0);
+        assert_eq!(board.mobility_estimate(), 0);
+
+        board.make_move("e2", "e4", true).unwrap();
+
+        assert_eq!(board.mobility_imbalance(), 10);
+        assert_eq!(board.mobility_estimate(), 10);
+
+        //the pinned knight can't move, but its pseudo-legal moves are counted
+        let mut board = Game::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert!(board.mobility_estimate() > board.mobility_imbalance());
+    }
+
+    #[test]
+    fn pgn_to_fen_list_test() {
+        let pgn = "[Event \"Test\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+            [White \"?\"]\n[Black \"?\"]\n[Result \"1-0\"]\n\n\
+            1. e4 e5 {main line} 2. Nf3 (2. f4 exf4 (2... d5)) 2... Nc6 $1 3.Bb5!? a6 1-0";
+        let fens = pgn_to_fen_list(pgn).unwrap();
+
+        assert_eq!(fens.len(), 7);
+        assert_eq!(fens[0], Game::new_starting_pos().to_fen());
+        assert_eq!(fens[6], "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 0 4");
+
+        let fens = pgn_to_fen_list("[FEN \"4k3/P7/8/8/8/8/8/4K3 w - - 0 1\"]\n\n1. a8=R+ *").unwrap();
 
-impl Color {
-    /// Returns the opposite color of the piece
-    pub fn opposite(&self) -> Color {
-        match self {
-            Color::White => Color::Black,
-            Color::Black => Color::White,
+        assert_eq!(fens[1], "R3k3/8/8/8/8/8/8/4K3 b - - 0 1");
+        assert!(pgn_to_fen_list("1. e4 e5 2. Ke3").is_err());
+        assert!(pgn_to_fen_list("1. e4 {unclosed").is_err());
+    }
+
+    #[test]
+    fn pgn_roundtrip_test() {
+        let pgn = "[Event \"Club championship\"]\n[White \"Alice\"]\n[ECO \"C20\"]\n\n1. e4 {best by test} e5 (1... c5) 2. Nf3 *";
+        let game = Game::from_pgn(pgn).unwrap();
+
+        assert_eq!(game.get_pgn_header("Event"), Some("Club championship"));
+        assert_eq!(game.get_pgn_header("ECO"), Some("C20"));
+        assert_eq!(game.to_pgn(), Ok(String::from(
+            "[Event \"Club championship\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+            [White \"Alice\"]\n[Black \"?\"]\n[Result \"*\"]\n[ECO \"C20\"]\n\n1. e4 e5 2. Nf3 *\n"
+        )));
+
+        //castling, en passant and promotion from a custom starting position
+        let mut game = Game::from_fen("4k3/P7/8/8/4p3/8/3P4/4K2R w K - 0 1").unwrap();
+
+        for (from, to) in [("e1", "g1"), ("e8", "d7"), ("d2", "d4"), ("e4", "d3"), ("a7", "a8")] {
+            game.make_move(from, to, true).unwrap();
         }
+
+        let pgn = game.to_pgn().unwrap();
+
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/P7/8/8/4p3/8/3P4/4K2R w K - 0 1\"]\n"));
+        assert!(pgn.ends_with("\n\n1. O-O Kd7 2. d4 exd3 3. a8=Q *\n"));
+
+        let imported = Game::from_pgn(&pgn).unwrap();
+
+        assert_eq!(imported.fen_history(), game.fen_history());
+        assert_eq!(imported.to_pgn(), Ok(pgn));
+
+        //castling written with zeros, including right after a move number
+        let game = Game::from_pgn("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 Nf6 5.d3 0-0 *").unwrap();
+        assert_eq!(game.pgn_move_text(), Ok(String::from("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. O-O Nf6 5. d3 O-O *")));
+
+        //long games are wrapped
+        let game = Game::random_game(60, 3);
+        let pgn = game.to_pgn().unwrap();
+
+        assert!(pgn.lines().all(|line| line.len() <= 80));
+        assert_eq!(Game::from_pgn(&pgn).unwrap().fen_history(), game.fen_history());
     }
-}
 
-fn is_valid_pos(i : i32, j : i32) -> bool {
-    i >= 0 && i <= 7 && j >= 0 && j <= 7
-}
+    #[test]
+    fn pgn_header_test() {
+        let mut game = Game::new_starting_pos();
 
-fn is_valid_move(from : (usize, usize), to : (usize, usize)) -> bool {
-    let (i1, j1) = from;
-    let (i2, j2) = to;
-    
-    is_valid_pos(i1 as i32, j1 as i32) && is_valid_pos(i2 as i32, j2 as i32)
-}
+        assert_eq!(game.get_pgn_header("Event"), None);
+        assert!(game.get_pgn_headers().is_empty());
 
+        game.set_event("Casual game");
+        game.set_white_player("Alice");
+        game.set_pgn_header("ECO", "C20");
+        game.set_event("Club championship");
 
-fn get_piece(chr : char) -> Result<Piece, String> {
-    match chr {
-        'P' => Ok(Piece::new(PieceType::Pawn, Color::White)),
-        'N' => Ok(Piece::new(PieceType::Knight, Color::White)),
-        'B' => Ok(Piece::new(PieceType::Bishop, Color::White)),
-        'R' => Ok(Piece::new(PieceType::Rook, Color::White)),
-        'Q' => Ok(Piece::new(PieceType::Queen, Color::White)),
-        'K' => Ok(Piece::new(PieceType::King, Color::White)),
-        'p' => Ok(Piece::new(PieceType::Pawn, Color::Black)),
-        'n' => Ok(Piece::new(PieceType::Knight, Color::Black)),
-        'b' => Ok(Piece::new(PieceType::Bishop, Color::Black)),
-        'r' => Ok(Piece::new(PieceType::Rook, Color::Black)),
-        'q' => Ok(Piece::new(PieceType::Queen, Color::Black)),
-        'k' => Ok(Piece::new(PieceType::King, Color::Black)),
-        e => Err(e.to_string())
-    }       
-}
+        assert_eq!(game.get_pgn_header("Event"), Some("Club championship"));
+        assert_eq!(game.get_pgn_header("White"), Some("Alice"));
+        assert_eq!(game.get_pgn_header("ECO"), Some("C20"));
 
-fn get_piece_notation(piece : Piece) -> char {
-    let mut letter = match piece.piece_type {
-        PieceType::Pawn=> 'P',
-        PieceType::Knight => 'N',
-        PieceType::Bishop => 'B',
-        PieceType::Rook => 'R',
-        PieceType::Queen => 'Q',
-        PieceType::King => 'K',
-    };
+        let names : Vec<&str> = game.get_pgn_headers().iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["Event", "White", "ECO"]);
 
-    if piece.color == Color::Black {
-        letter = letter.to_ascii_lowercase();
+        //headers are kept when moves are undone
+        game.make_move("e2", "e4", true).unwrap();
+        game.set_black_player("Bob");
+        game.undo_last_move();
+
+        assert_eq!(game.get_pgn_header("Black"), Some("Bob"));
     }
 
-    return letter;
-}
+    #[test]
+    fn positions_test() {
+        let game = Game::from_san_list(&["e4", "e5", "Nf3"]).unwrap();
+        let fens : Vec<String> = game.positions().map(|position| position.to_fen()).collect();
 
-fn get_repr(piece : Piece) -> char {
-    match piece.color {
-        Color::White => match piece.piece_type {
-            PieceType::Pawn => 'P', 
-            PieceType::Knight => 'N', 
-            PieceType::Bishop => 'B', 
-            PieceType::Rook => 'R', 
-            PieceType::Queen => 'Q', 
-            PieceType::King => 'K', 
-        }
-        Color::Black => match piece.piece_type {
-            PieceType::Pawn => 'p', 
-            PieceType::Knight => 'n', 
-            PieceType::Bishop => 'b', 
-            PieceType::Rook => 'r', 
-            PieceType::Queen => 'q', 
-            PieceType::King => 'k', 
+        assert_eq!(fens, game.fen_history());
+        assert_eq!(fens[0], Game::new_starting_pos().to_fen());
+        assert_eq!(fens[3], game.to_fen());
+
+        let mut count = 0;
+
+        //positions are copied without their history
+        for position in &game {
+            assert_eq!(position.fen_history(), vec![fens[count].clone()]);
+            count += 1;
         }
+
+        assert_eq!(count, 4);
     }
-}
 
-/// Get array indicies for a give `notation` written in
-/// algebraic notation.
-/// 
-/// # Arguments
-/// * `notation` is a `str` describing a square on the board in algebraic notation.
-/// 
-/// # Returns
-/// * A `Result` containing the array index `(usize, usize)` corresponding
-/// to the input algebraic notation.
-/// 
-/// # Errors
-/// * Returns `Err(String)` if the provided notation is invalid
-pub fn alg_notation_to_indx(notation : &str) -> Result<(usize , usize), String> {
-    let chr_vec = notation
-        .chars()
-        .collect::<Vec<char>>();
+    #[test]
+    fn legal_move_count_test() {
+        let mut board = Game::new_starting_pos();
 
-    if chr_vec.len() != 2 {
-        return Err(format!("Invalid notation {}", notation));
+        assert_eq!(board.legal_move_count(Color::White), 20);
+        assert!(board.has_legal_moves(Color::White));
+
+        //black is stalemated
+        let mut board = Game::from_fen("k7/2Q5/8/8/8/8/8/K7 b - - 0 1").unwrap();
+
+        assert_eq!(board.legal_move_count(Color::Black), 0);
+        assert!(!board.has_legal_moves(Color::Black));
+        assert_eq!(board.get_state(), GameState::Draw(DrawState::Stalemate));
     }
 
-    let col : usize = match chr_vec[0] {
-        'a' => 0,
-        'b' => 1,
-        'c' => 2,
-        'd' => 3,
-        'e' => 4,
-        'f' => 5,
-        'g' => 6,
-        'h' => 7,
-        _c => return Err(format!("Invalid file {}", _c)),
-    };
+    #[test]
+    fn pseudo_legal_moves_test() {
+        //knight on e2 is pinned to the king by the rook on e7
+        let mut board = Game::from_fen("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.pseudo_legal_moves((6, 4)).unwrap().len(), 6);
+        assert!(board.get_legal_moves_square(Square::new(6, 4).unwrap()).is_empty());
+        assert_eq!(board.pseudo_legal_moves((4, 4)), Ok(Vec::new()));
+        assert!(board.pseudo_legal_moves((8, 0)).is_err());
+    }
 
-    // 8 - n since ranks in the array are mirrored, and the first rank is at index 7
-    let row = match chr_vec[1].to_digit(10) {
-        Some(digit) => 8 - digit as usize,
-        None => return Err(format!("Invalid row {}", chr_vec[1]))
-    };
-    
-    
-    return Ok((row, col));
-}
+    #[test]
+    #[allow(deprecated)]
+    fn is_legal_move_test() {
+        let board = Game::new_starting_pos();
+        let e2 = Square::from_alg("e2").unwrap();
+
+        assert!(board.is_legal_move_square(e2, Square::from_alg("e4").unwrap()));
+        assert!(!board.is_legal_move_square(e2, Square::from_alg("e5").unwrap()));
+        assert!(board.is_legal_move((6, 4), (4, 4)));
+        assert!(!board.is_legal_move((6, 4), (3, 4)));
+        assert!(board.is_legal_move_immutable((6, 4), (4, 4)));
+        assert!(!board.is_legal_move_immutable((8, 4), (4, 4)));
+
+        //indicies that would wrap onto the board if truncated to 32 bits
+        let large = 1usize << 32;
+
+        assert!(!board.is_legal_move_immutable((large + 6, 4), (4, 4)));
+        assert!(!board.is_legal_move_immutable((6, 4), (4, large + 4)));
+        assert!(board.pseudo_legal_moves((large + 6, 4)).is_err());
+        assert!(!board.is_pinned((large, 4)));
+        assert!(board.analyze_capture_sequence((large, 4)).is_empty());
+
+        let mut edited = Game::new_starting_pos();
+
+        assert!(edited.place_piece((large + 4, 4), Piece::new(PieceType::Queen, Color::White)).is_err());
+        assert_eq!(edited.remove_piece((large, 4)), None);
+        assert_eq!(edited.to_fen(), Game::new_starting_pos().to_fen());
+
+        edited.set_game_mode(GameMode::Crazyhouse);
+        assert!(edited.drop_piece(PieceType::Pawn, (large + 4, 4)).is_err());
+        assert_eq!(board.is_legal_move_alg("g1", "f3"), Ok(true));
+        //black piece, but it is white's turn
+        assert_eq!(board.is_legal_move_alg("e7", "e5"), Ok(false));
+        assert!(board.is_legal_move_alg("", "e4").is_err());
+
+        //checking legality must not change the position
+        assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        //the knight on e2 is pinned, and the king can not castle through the bishop's attack
+        let board = Game::from_fen("4k3/4r3/8/8/8/b7/4N3/R3K3 w Q - 0 1").unwrap();
+
+        assert!(!board.is_legal_move_immutable((6, 4), (4, 3)));
+        assert!(board.is_legal_move_immutable((7, 4), (7, 3)));
+        assert!(!board.is_legal_move_immutable((7, 4), (7, 2)));
+        assert!(board.is_legal_move_immutable((7, 0), (5, 0)));
+    }
 
-/// Get algebraic notation for a given `indx`.
-/// 
-/// # Returns
-/// * `Result` containing the algebraic notation as a `String`
-/// 
-/// # Errors
-/// * Returns `Err(String)` if provided index is invalid.
-pub fn indx_to_alg_notation(indx : (usize, usize)) -> Result<String, String> {
-    let rank : char = match indx.1 {
-        0 => 'a',
-        1 => 'b',
-        2 => 'c',
-        3 => 'd',
-        4 => 'e',
-        5 => 'f',
-        6 => 'g',
-        7 => 'h',
-        _c => return Err(format!("Invalid column {}", _c)),
-    };
+    #[test]
+    fn san_test() {
+        let mut board = Game::new_starting_pos();
 
-    // 8 - n since ranks in the array are mirrored, and the first rank is at index 7
-    let col = match char::from_digit(8 - indx.0 as u32, 10) {
-        Some(c) => c,
-        _ => return Err(format!("Invalid row {}", indx.0)),
-    };
+        assert_eq!(board.move_to_san((7, 6), (5, 5)), Ok(String::from("Nf3")));
+        assert_eq!(board.move_to_san((6, 4), (4, 4)), Ok(String::from("e4")));
+        assert!(board.move_to_san((6, 4), (3, 4)).is_err());
 
-    let mut alg_notation = String::new();
+        let mut board = Game::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2").unwrap();
 
-    alg_notation.push(rank);
-    alg_notation.push(col);
+        assert_eq!(board.move_to_san((4, 4), (3, 3)), Ok(String::from("exd5")));
+    }
 
-    return Ok(alg_notation);
-}
+    #[test]
+    fn san_disambiguation_test() {
+        //two rooks on the same rank, disambiguated by file
+        let mut board = Game::from_fen("2k5/8/8/8/8/8/8/R3nR1K w - - 0 1").unwrap();
 
-// returns which colored pawn is allowed to en passant on the given rank
-// solves conflict where 2 pawns of opposite color can move to en passant square
-fn can_en_passant(i : usize) -> Option<Color> {
-    match i {
-        2 => Some(Color::White),
-        5 => Some(Color::Black),
-        _ => None,
+        assert_eq!(board.move_to_san((7, 0), (7, 4)), Ok(String::from("Raxe1")));
+        assert_eq!(board.move_to_san((7, 5), (7, 4)), Ok(String::from("Rfxe1")));
+
+        //two knights on the same file, disambiguated by rank
+        let mut board = Game::from_fen("k7/8/8/8/8/1N6/8/1N5K w - - 0 1").unwrap();
+
+        assert_eq!(board.move_to_san((7, 1), (6, 3)), Ok(String::from("N1d2")));
+        assert_eq!(board.move_to_san((5, 1), (6, 3)), Ok(String::from("N3d2")));
+
+        //three queens, one of them needs both file and rank
+        let mut board = Game::from_fen("8/8/8/7k/8/Q7/8/Q1Q4K w - - 0 1").unwrap();
+
+        assert_eq!(board.move_to_san((7, 0), (6, 1)), Ok(String::from("Qa1b2")));
+        assert_eq!(board.move_to_san((5, 0), (6, 1)), Ok(String::from("Q3b2")));
+        assert_eq!(board.move_to_san((7, 2), (6, 1)), Ok(String::from("Qcb2")));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
     #[test]
+    fn san_check_test() {
+        let mut board = Game::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2").unwrap();
 
-    fn piece_getter_test() {
-        let game = Game::new_starting_pos();
-     
-        let piece = game.piece_at_array_index((0,0));
-        let top_left_piece = Piece::new(PieceType::Rook, Color::Black);
-    
-        assert_eq!(piece, Ok(Some(top_left_piece)));
+        assert_eq!(board.move_to_san((7, 5), (3, 1)), Ok(String::from("Bb5+")));
+
+        //fool's mate
+        let mut board = Game::from_fen("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2").unwrap();
+
+        assert_eq!(board.move_to_san((0, 3), (4, 7)), Ok(String::from("Qh4#")));
+        assert_eq!(board.san_to_move("Qh4#"), Ok(((0, 3), (4, 7))));
+        assert_eq!(board.san_to_move("Qh4"), Ok(((0, 3), (4, 7))));
+
+        //annotating the move must not change the position
+        assert_eq!(board.to_fen(), "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2");
     }
 
     #[test]
+    fn san_edge_cases_test() {
+        //en passant is written as a pawn capture to the en passant square
+        let mut board = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
 
-    fn possible_moves_test() {
-        let mut board = Game::new_starting_pos();
+        assert_eq!(board.move_to_san((3, 4), (2, 3)), Ok(String::from("exd6")));
+        assert!(board.move_to_san((3, 4), (2, 5)).is_err());
+        assert!(board.move_to_san((0, 4), (1, 4)).is_err());
 
-        let x : HashMap<(usize, usize), Vec<(usize, usize)>> = board.get_all_legal_moves(Color::White);
+        //the rook gives check after castling
+        let mut board = Game::from_fen("5k2/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
 
-        let mut expected_map = HashMap::new();
-        expected_map.insert((6, 6), vec![(5, 6), (4, 6)]);
-        expected_map.insert((7, 2), vec![]);
-        expected_map.insert((6, 4), vec![(5, 4), (4, 4)]);
-        expected_map.insert((6, 2), vec![(5, 2), (4, 2)]);
-        expected_map.insert((7, 0), vec![]);
-        expected_map.insert((7, 4), vec![]);
-        expected_map.insert((7, 5), vec![]);
-        expected_map.insert((7, 3), vec![]);
-        expected_map.insert((6, 7), vec![(5, 7), (4, 7)]);
-        expected_map.insert((6, 1), vec![(5, 1), (4, 1)]);
-        expected_map.insert((7, 1), vec![(5, 2), (5, 0)]);
-        expected_map.insert((6, 3), vec![(5, 3), (4, 3)]);
-        expected_map.insert((6, 0), vec![(5, 0), (4, 0)]);
-        expected_map.insert((6, 5), vec![(5, 5), (4, 5)]);
-        expected_map.insert((7, 6), vec![(5, 7), (5, 5)]);
-        expected_map.insert((7, 7), vec![]);
+        assert_eq!(board.move_to_san((7, 4), (7, 6)), Ok(String::from("O-O+")));
 
-        assert_eq!(x, expected_map);
+        //promotion with capture and checkmate
+        let mut board = Game::from_fen("1r5k/P5pp/8/8/8/8/8/6K1 w - - 0 1").unwrap();
+
+        assert_eq!(board.move_to_san((1, 0), (0, 1)), Ok(String::from("axb8=Q#")));
+        assert_eq!(board.move_to_san_with_promotion((1, 0), (0, 1), PieceType::Bishop), Ok(String::from("axb8=B")));
     }
 
     #[test]
-    fn legal_moves_square_test() {
-        let mut game = Game::new_starting_pos();
+    fn san_castling_test() {
+        let mut board = Game::from_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
 
-        let expected_val : Vec<(usize, usize)> = Vec::from([(5, 2), (5, 0)]);
+        assert_eq!(board.move_to_san((7, 4), (7, 6)), Ok(String::from("O-O")));
+        assert_eq!(board.move_to_san((7, 4), (7, 2)), Ok(String::from("O-O-O")));
+        assert_eq!(board.san_to_move("O-O"), Ok(((7, 4), (7, 6))));
+        assert_eq!(board.san_to_move("0-0-0"), Ok(((7, 4), (7, 2))));
 
-        //print legal moves for knight on b1
-        assert_eq!(expected_val, game.get_legal_moves_alg_notation("b1").ok().unwrap());
+        board.make_move("e1", "g1", true).unwrap();
+
+        assert_eq!(board.san_to_move("0-0"), Ok(((0, 4), (0, 6))));
+        assert_eq!(board.move_to_san((0, 4), (0, 2)), Ok(String::from("O-O-O")));
     }
 
     #[test]
+    fn san_promotion_test() {
+        let mut board = Game::from_fen("3r4/4P3/8/8/8/7k/8/K7 w - - 0 1").unwrap();
 
-    //tests make_move function with different inputs
-    fn move_test() {   
+        assert_eq!(board.move_to_san((1, 4), (0, 4)), Ok(String::from("e8=Q")));
+        assert_eq!(board.move_to_san_with_promotion((1, 4), (0, 4), PieceType::Rook), Ok(String::from("e8=R")));
+        assert_eq!(board.move_to_san((1, 4), (0, 3)), Ok(String::from("exd8=Q")));
+        assert_eq!(board.san_to_move("exd8=Q"), Ok(((1, 4), (0, 3))));
+        assert_eq!(board.san_to_move("e8=N"), Ok(((1, 4), (0, 4))));
+        assert!(board.san_to_move("e8=K").is_err());
+
+        //only the knight promotion gives check
+        let mut board = Game::from_fen("8/4P3/5k2/8/8/8/8/K7 w - - 0 1").unwrap();
+
+        assert_eq!(board.move_to_san((1, 4), (0, 4)), Ok(String::from("e8=Q")));
+        assert_eq!(board.move_to_san_with_promotion((1, 4), (0, 4), PieceType::Knight), Ok(String::from("e8=N+")));
+    }
+
+    #[test]
+    fn make_move_san_test() {
         let mut board = Game::new_starting_pos();
 
-        let valid_move = board.make_move("e2", "e4", false);
-        let invalid_move = board.make_move("f2", "f5", false);
-        let invalid_move2 = board.make_move("f4", "f5", false);
-        let invalid_input = board.make_move("aksmldkams", "poköakenjf", false);
-        let empty_input = board.make_move("", "", false);
+        assert_eq!(board.make_move_san("e4", true), Ok(true));
+        assert_eq!(board.make_move_san("d5", true), Ok(true));
+        assert_eq!(board.make_move_san("exd5", true), Ok(true));
+        assert_eq!(board.make_move_san("Nf6", true), Ok(true));
+        assert_eq!(board.make_move_san("Bb5+", true), Ok(true));
+        assert!(board.make_move_san("e5", true).is_err());
+        assert!(board.make_move_san("Nf3", true).is_err());
 
-        assert_eq!(valid_move, Ok(true));
-        assert_eq!(invalid_move, Ok(false));
-        assert_eq!(invalid_move2, Ok(false));
-        assert_eq!(invalid_input.is_err(), true);
-        assert_eq!(empty_input.is_err(), true);
+        //both rooks can reach e1
+        let mut board = Game::from_fen("2k5/8/8/8/8/8/8/R6R w - - 0 1").unwrap();
+
+        assert!(board.make_move_san("Re1", true).is_err());
+        assert_eq!(board.make_move_san("Rhe1", true), Ok(true));
+        assert_eq!(board.piece_at("e1"), Some(Piece::new(PieceType::Rook, Color::White)));
+
+        let mut board = Game::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.make_move_san("b8", false), Ok(true));
+        assert_eq!(board.get_state(), GameState::AwaitPromotion);
+
+        board.undo_last_move();
+
+        assert_eq!(board.make_move_san("b8=N", false), Ok(true));
+        assert_eq!(board.piece_at("b8"), Some(Piece::new(PieceType::Knight, Color::White)));
+
+        board.undo_last_move();
+
+        assert_eq!(board.make_move_san("b8+", true), Ok(true));
+        assert_eq!(board.piece_at("b8"), Some(Piece::new(PieceType::Queen, Color::White)));
     }
 
     #[test]
-    fn castling_test() {
-        let mut board = Game::from_fen("r1bqkbnr/pppppppp/8/8/8/6n1/PPPPPPP1/RNBQK2R b KQkq - 0 1").unwrap();
+    fn san_to_move_test() {
+        let mut board = Game::from_fen("2k5/8/8/8/8/8/8/R3nR1K w - - 0 1").unwrap();
+
+        assert_eq!(board.san_to_move("Raxe1"), Ok(((7, 0), (7, 4))));
+        assert_eq!(board.san_to_move("Rfe1"), Ok(((7, 5), (7, 4))));
+        assert!(board.san_to_move("Rxe1").is_err());
+        assert!(board.san_to_move("Nf3").is_err());
+        assert!(board.san_to_move("e9").is_err());
+        assert!(board.san_to_move("").is_err());
+    }
 
-        board.make_move("g3", "e4", true).unwrap();
+    #[test]
+    fn castling_rights_removed_on_rook_capture() {
+        let mut board = Game::from_fen("4k2r/8/8/8/8/8/8/4K2R b K - 0 1").unwrap();
 
-        println!("{:?}", board);
-        println!("{:?}", board.get_legal_moves_alg_notation("e1").unwrap());
+        board.make_move("h8", "h1", true).unwrap();
+
+        assert_eq!(board.kingside_castle.get(&Color::White), Some(&false));
+        assert!(!board.get_legal_moves_alg_notation("e1").unwrap().contains(&(7, 6)));
+    }
+
+    #[test]
+    fn revert_to_fen_test() {
+        let mut board = Game::new_starting_pos();
+
+        board.make_move("e2", "e4", true).unwrap();
+        board.revert_to_fen("k7/8/8/8/8/8/8/K7 b - - 3 40").unwrap();
+
+        assert_eq!(board.to_fen(), "k7/8/8/8/8/8/8/K7 b - - 3 40");
+
+        //history is cleared, so there is nothing to undo
+        board.undo_last_move();
+        assert_eq!(board.to_fen(), "k7/8/8/8/8/8/8/K7 b - - 3 40");
+
+        assert!(board.revert_to_fen("k7/8/8/8/8/8/8/K7 x - - 3 40").is_err());
+        assert_eq!(board.to_fen(), "k7/8/8/8/8/8/8/K7 b - - 3 40");
+
+        board.revert_to_start();
+        assert_eq!(board, Game::new_starting_pos());
     }
 
     #[test]
@@ -1634,6 +9701,20 @@ mod tests {
         assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
     }
 
+    #[test]
+    fn undo_restores_attacked_squares_test() {
+        let mut board = Game::from_fen("4k3/3r4/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+
+        board.make_move("d7", "e7", true).unwrap();
+
+        assert!(board.in_check(Color::White));
+
+        board.undo_last_move();
+
+        assert!(!board.in_check(Color::White));
+        assert_eq!(board, Game::from_fen("4k3/3r4/8/8/8/8/8/4K3 b - - 0 1").unwrap());
+    }
+
     #[test]
 
     //Shows promotion functionality